@@ -0,0 +1,57 @@
+"""Generate the code to unicode tables of the predefined CJK CMaps in src/tables
+
+The codes of the RKSJ, EUC, GBK, B5 and UHC CMaps are the bytes of legacy encodings,
+they are decoded with the Python codecs:
+- cp932.bin: Shift-JIS with the Microsoft extensions, for the RKSJ CMaps
+- gbk.bin: GBK, a superset of the GB 2312 EUC codes, for the GB CMaps
+- cp950.bin: Big Five with the Microsoft extensions, for the B5 CMaps
+- cp949.bin: Unified Hangul Code, a superset of the KS X 1001 EUC codes, for the KSC CMaps
+
+Each table is a sorted list of big endian (code, unicode) pairs of 2 bytes each, codes
+below 0x100 are one byte codes. Only codes decoding to a single BMP char are kept.
+
+    python3 scripts/cjk_tables.py
+"""
+
+import os
+import struct
+
+TABLES = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "src", "tables")
+ENCODINGS = ["cp932", "gbk", "cp950", "cp949"]
+
+
+def decode(code_bytes, encoding):
+    try:
+        text = code_bytes.decode(encoding)
+    except UnicodeDecodeError:
+        return None
+    if len(text) != 1 or ord(text) > 0xFFFF:
+        return None
+    return ord(text)
+
+
+def table(encoding):
+    pairs = []
+    for code in range(0x100):
+        unicode = decode(bytes([code]), encoding)
+        if unicode is not None:
+            pairs.append((code, unicode))
+    # lead bytes of two bytes codes do not decode on their own
+    leads = [b for b in range(0x80, 0x100) if decode(bytes([b]), encoding) is None]
+    for lead in leads:
+        for trail in range(0x100):
+            unicode = decode(bytes([lead, trail]), encoding)
+            if unicode is not None:
+                pairs.append((lead * 256 + trail, unicode))
+    return b"".join(struct.pack(">HH", code, unicode) for code, unicode in pairs)
+
+
+def main():
+    os.makedirs(TABLES, exist_ok=True)
+    for encoding in ENCODINGS:
+        with open(os.path.join(TABLES, encoding + ".bin"), "wb") as f:
+            f.write(table(encoding))
+
+
+if __name__ == "__main__":
+    main()
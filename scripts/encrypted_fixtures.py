@@ -0,0 +1,200 @@
+"""Generate the encrypted fixtures data/helloworld-{rc4,aesv2,aesv3}.pdf
+
+Each file shows "Hello, world!" with a Title in its Info dictionary, encrypted with the
+standard security handler and owner password "owner":
+- helloworld-rc4.pdf: RC4 128 bits, revision 3, empty user password
+- helloworld-aesv2.pdf: AESV2, revision 4, empty user password
+- helloworld-aesv3.pdf: AESV3, revision 6, user password "user"
+
+Keys, salts and initialization vectors are derived from fixed labels so the output is
+reproducible. Requires the cryptography package for AES.
+
+    python3 scripts/encrypted_fixtures.py
+"""
+
+import hashlib
+import os
+import struct
+
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+
+DATA = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "data")
+PAD = bytes.fromhex("28BF4E5E4E758A4164004E56FFFA01082E2E00B6D0683E802F0CA9FE6453697A")
+ID0 = bytes.fromhex("9f3e6a21c04b7d58e1a2b3c4d5e6f708")
+P = -3904  # print and copy disallowed
+
+
+def fixed_bytes(label, n):
+    return hashlib.sha256(label.encode()).digest()[:n]
+
+
+def rc4(key, data):
+    s = list(range(256))
+    j = 0
+    for i in range(256):
+        j = (j + s[i] + key[i % len(key)]) % 256
+        s[i], s[j] = s[j], s[i]
+    i = j = 0
+    out = bytearray()
+    for b in data:
+        i = (i + 1) % 256
+        j = (j + s[i]) % 256
+        s[i], s[j] = s[j], s[i]
+        out.append(b ^ s[(s[i] + s[j]) % 256])
+    return bytes(out)
+
+
+def aes_cbc(key, iv, data):
+    encryptor = Cipher(algorithms.AES(key), modes.CBC(iv)).encryptor()
+    return encryptor.update(data) + encryptor.finalize()
+
+
+def aes_ecb(key, data):
+    encryptor = Cipher(algorithms.AES(key), modes.ECB()).encryptor()
+    return encryptor.update(data) + encryptor.finalize()
+
+
+def pkcs7(data):
+    n = 16 - len(data) % 16
+    return data + bytes([n]) * n
+
+
+def pad(password):
+    return (password + PAD)[:32]
+
+
+# Algorithm 3: O entry of revisions 3 and 4
+def o_value(owner, user, n):
+    h = hashlib.md5(pad(owner)).digest()
+    for _ in range(50):
+        h = hashlib.md5(h[:n]).digest()
+    key = h[:n]
+    o = rc4(key, pad(user))
+    for i in range(1, 20):
+        o = rc4(bytes(k ^ i for k in key), o)
+    return o
+
+
+# Algorithm 2: file encryption key of revisions 3 and 4
+def file_key(user, o, n):
+    h = hashlib.md5(pad(user) + o + struct.pack("<i", P) + ID0).digest()
+    for _ in range(50):
+        h = hashlib.md5(h[:n]).digest()
+    return h[:n]
+
+
+# Algorithm 5: U entry of revisions 3 and 4
+def u_value(key):
+    u = rc4(key, hashlib.md5(PAD + ID0).digest())
+    for i in range(1, 20):
+        u = rc4(bytes(k ^ i for k in key), u)
+    return u + bytes(16)
+
+
+# Algorithm 2.B: hash of revision 6
+def hash_2b(password, salt, user):
+    k = hashlib.sha256(password + salt + user).digest()
+    i = 0
+    e = b"\xff"
+    while i < 64 or e[-1] > i - 32:
+        e = aes_cbc(k[:16], k[16:32], (password + k + user) * 64)
+        k = hashlib.new(["sha256", "sha384", "sha512"][sum(e[:16]) % 3], e).digest()
+        i += 1
+    return k[:32]
+
+
+def build(name, encrypt_dict, encrypt_object):
+    content = b"BT\n70 50 TD\n/F1 12 Tf\n(Hello, world!) Tj\nET"
+    objects = [
+        b"<<\n  /Type /Catalog\n  /Pages 2 0 R\n>>",
+        b"<<\n  /Type /Pages\n  /MediaBox [ 0 0 200 200 ]\n  /Count 1\n  /Kids [ 3 0 R ]\n>>",
+        b"<<\n  /Type /Page\n  /Parent 2 0 R\n  /Resources <<\n    /Font <<\n      /F1 4 0 R\n    >>\n  >>\n  /Contents 5 0 R\n>>",
+        b"<<\n  /Type /Font\n  /Subtype /Type1\n  /BaseFont /Times-Roman\n>>",
+    ]
+    data = encrypt_object(5, content)
+    objects.append(b"<<\n  /Length %d\n>>\nstream\n" % len(data) + data + b"\nendstream")
+    objects.append(encrypt_dict)
+    objects.append(b"<<\n  /Title <%s>\n>>" % encrypt_object(7, b"Hello, world!").hex().encode())
+    out = bytearray(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n\n")
+    offsets = []
+    for number, o in enumerate(objects, 1):
+        offsets.append(len(out))
+        out += b"%d 0 obj\n" % number + o + b"\nendobj\n\n"
+    xref = len(out)
+    out += b"xref\n0 %d\n0000000000 65535 f \n" % (len(objects) + 1)
+    for offset in offsets:
+        out += b"%010d 00000 n \n" % offset
+    out += (
+        b"trailer\n<<\n  /Size %d\n  /Root 1 0 R\n  /Encrypt 6 0 R\n  /Info 7 0 R\n"
+        b"  /ID [ <%s> <%s> ]\n>>\nstartxref\n%d\n%%%%EOF\n"
+        % (len(objects) + 1, ID0.hex().encode(), ID0.hex().encode(), xref)
+    )
+    with open(os.path.join(DATA, name), "wb") as f:
+        f.write(out)
+
+
+def rc4_revision_3():
+    o = o_value(b"owner", b"", 16)
+    key = file_key(b"", o, 16)
+    u = u_value(key)
+
+    def encrypt_object(number, data):
+        k = hashlib.md5(key + struct.pack("<i", number)[:3] + b"\0\0").digest()[:16]
+        return rc4(k, data)
+
+    build(
+        "helloworld-rc4.pdf",
+        b"<<\n  /Filter /Standard\n  /V 2\n  /R 3\n  /Length 128\n  /O <%s>\n  /U <%s>\n  /P %d\n>>"
+        % (o.hex().encode(), u.hex().encode(), P),
+        encrypt_object,
+    )
+
+
+def aesv2_revision_4():
+    o = o_value(b"owner", b"", 16)
+    key = file_key(b"", o, 16)
+    u = u_value(key)
+
+    def encrypt_object(number, data):
+        k = hashlib.md5(key + struct.pack("<i", number)[:3] + b"\0\0" + b"sAlT").digest()[:16]
+        iv = fixed_bytes("aesv2 iv %d" % number, 16)
+        return iv + aes_cbc(k, iv, pkcs7(data))
+
+    build(
+        "helloworld-aesv2.pdf",
+        b"<<\n  /Filter /Standard\n  /V 4\n  /R 4\n  /Length 128\n"
+        b"  /CF << /StdCF << /CFM /AESV2 /AuthEvent /DocOpen /Length 16 >> >>\n"
+        b"  /StmF /StdCF\n  /StrF /StdCF\n  /O <%s>\n  /U <%s>\n  /P %d\n>>"
+        % (o.hex().encode(), u.hex().encode(), P),
+        encrypt_object,
+    )
+
+
+def aesv3_revision_6():
+    key = fixed_bytes("aesv3 file key", 32)
+    user_validation, user_key = fixed_bytes("user validation", 8), fixed_bytes("user key", 8)
+    owner_validation, owner_key = fixed_bytes("owner validation", 8), fixed_bytes("owner key", 8)
+    u = hash_2b(b"user", user_validation, b"") + user_validation + user_key
+    ue = aes_cbc(hash_2b(b"user", user_key, b""), bytes(16), key)
+    o = hash_2b(b"owner", owner_validation, u) + owner_validation + owner_key
+    oe = aes_cbc(hash_2b(b"owner", owner_key, u), bytes(16), key)
+    perms = aes_ecb(key, struct.pack("<i", P) + b"\xff\xff\xff\xffTadb" + fixed_bytes("perms", 4))
+
+    def encrypt_object(number, data):
+        iv = fixed_bytes("aesv3 iv %d" % number, 16)
+        return iv + aes_cbc(key, iv, pkcs7(data))
+
+    build(
+        "helloworld-aesv3.pdf",
+        b"<<\n  /Filter /Standard\n  /V 5\n  /R 6\n  /Length 256\n"
+        b"  /CF << /StdCF << /CFM /AESV3 /AuthEvent /DocOpen /Length 32 >> >>\n"
+        b"  /StmF /StdCF\n  /StrF /StdCF\n  /O <%s>\n  /U <%s>\n  /OE <%s>\n  /UE <%s>\n  /Perms <%s>\n  /P %d\n>>"
+        % (o.hex().encode(), u.hex().encode(), oe.hex().encode(), ue.hex().encode(), perms.hex().encode(), P),
+        encrypt_object,
+    )
+
+
+if __name__ == "__main__":
+    rc4_revision_3()
+    aesv2_revision_4()
+    aesv3_revision_6()
@@ -15,9 +15,9 @@ impl From<Number> for f32 {
     }
 }
 
-impl Into<i32> for Number {
-    fn into(self) -> i32 {
-        match self {
+impl From<Number> for i32 {
+    fn from(value: Number) -> Self {
+        match value {
             Number::Integer(n) => n,
             Number::Real(f) => f as i32,
         }
@@ -115,9 +115,34 @@ impl Matrix {
         Self(a, b, c, d, e, f)
     }
 
+    pub fn get_a(&self) -> f32 {
+        self.0
+    }
+
     pub fn get_ty(&self) -> f32 {
         self.5
     }
+
+    // Image of the point (x, y) by the transformation
+    pub fn transform(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x * self.0 + y * self.2 + self.4,
+            x * self.1 + y * self.3 + self.5,
+        )
+    }
+
+    // Coordinates of the origin of this matrix in the coordinate space defined by another one
+    pub fn origin_in(&self, space: &Matrix) -> Option<(f32, f32)> {
+        let det = space.0 * space.3 - space.1 * space.2;
+        if det == 0.0 {
+            return None;
+        }
+        let (dx, dy) = (self.4 - space.4, self.5 - space.5);
+        Some((
+            (dx * space.3 - dy * space.2) / det,
+            (dy * space.0 - dx * space.1) / det,
+        ))
+    }
 }
 
 impl From<[Number; 6]> for Matrix {
@@ -165,4 +190,15 @@ mod tests {
         let m2 = Matrix::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
         assert_eq!(m1 * m2, Matrix::new(7.0, 10.0, 15.0, 22.0, 28.0, 40.0))
     }
+
+    #[test]
+    fn test_origin_in() {
+        let space = Matrix::new(0.0, 2.0, -2.0, 0.0, 10.0, 10.0);
+        let point = Matrix::new(1.0, 0.0, 0.0, 1.0, 10.0, 16.0);
+        assert_eq!(point.origin_in(&space), Some((3.0, 0.0)));
+        assert_eq!(
+            point.origin_in(&Matrix::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)),
+            None
+        );
+    }
 }
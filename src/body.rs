@@ -1,86 +1,164 @@
-use core::panic;
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    fmt::Display,
-    rc::{Rc, Weak},
-};
+use std::{collections::HashMap, fmt::Display, rc::Rc};
 
 use crate::{
-    algebra::Number,
-    cmap::ToUnicodeCMap,
+    algebra::{Matrix, Number},
+    cmap::{CMap, ToUnicodeCMap},
     content,
-    filters::flate_decode,
-    object::{Array, Dictionary, Name, Object},
+    encoding::{BaseEncoding, Encoding},
+    filters::{decode, stream_filters, DecodeParms, Filter, DEFAULT_MAX_DECODED_SIZE},
+    layout,
+    metrics::StandardFont,
+    object::{Array, Dictionary, IndirectObject, Name, Object},
     xref::XRef,
-    Extract,
+    Error, Extract,
 };
 
 use crate::object::Stream as StreamObject;
 
-#[derive(Debug, PartialEq)]
-pub struct Rectangle([Number; 4]);
+// Rectangle given by its lower-left and upper-right corners (page 161)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rectangle {
+    pub llx: f32,
+    pub lly: f32,
+    pub urx: f32,
+    pub ury: f32,
+}
+
+// US Letter, used when a page has no MediaBox
+const DEFAULT_MEDIA_BOX: Rectangle = Rectangle {
+    llx: 0.0,
+    lly: 0.0,
+    urx: 612.0,
+    ury: 792.0,
+};
+
+impl Rectangle {
+    pub fn width(&self) -> f32 {
+        self.urx - self.llx
+    }
+
+    pub fn height(&self) -> f32 {
+        self.ury - self.lly
+    }
+
+    // Overlapping area, empty rectangles are collapsed on the lower-left corner
+    pub fn intersect(&self, other: &Rectangle) -> Rectangle {
+        let llx = self.llx.max(other.llx);
+        let lly = self.lly.max(other.lly);
+        Rectangle {
+            llx,
+            lly,
+            urx: self.urx.min(other.urx).max(llx),
+            ury: self.ury.min(other.ury).max(lly),
+        }
+    }
+
+    // Rectangle after a clockwise rotation by a multiple of 90 degrees around the origin
+    pub fn rotate(&self, degrees: i32) -> Rectangle {
+        let [llx, lly, urx, ury] = match degrees.rem_euclid(360) {
+            90 => [self.lly, -self.urx, self.ury, -self.llx],
+            180 => [-self.urx, -self.ury, -self.llx, -self.lly],
+            270 => [-self.ury, self.llx, -self.lly, self.urx],
+            _ => [self.llx, self.lly, self.urx, self.ury],
+        };
+        Rectangle { llx, lly, urx, ury }
+    }
+
+    // Optional rectangle entry of a dictionary
+    fn get(dict: &Dictionary, key: &str) -> Result<Option<Rectangle>, Error> {
+        match dict.get(key).map(Object::resolve).transpose()? {
+            Some(Object::Array(array)) => Ok(Some(Rectangle::try_from(array)?)),
+            None => Ok(None),
+            Some(o) => Err(Error::object(format!(
+                "{key} should be a rectangle, found {o:?}"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<Array<'_>> for Rectangle {
+    type Error = Error;
 
-impl From<Array<'_>> for Rectangle {
-    fn from(array: Array) -> Self {
+    fn try_from(array: Array) -> Result<Self, Self::Error> {
         if array.len() != 4 {
-            panic!("PDF rectangle contains 4 values, found {}", array.len())
+            return Err(Error::object(format!(
+                "PDF rectangle contains 4 values, found {}",
+                array.len()
+            )));
         };
-        let value: [Number; 4] = array
+        let value = array
             .iter()
-            .map(|x| match x {
-                Object::Numeric(n) => n.clone(),
-                o => panic!("PDF rectangle values are numbers, found {o:?}"),
+            .map(|x| match x.resolve()? {
+                Object::Numeric(n) => Ok(f32::from(n)),
+                o => Err(Error::object(format!(
+                    "PDF rectangle values are numbers, found {o:?}"
+                ))),
             })
-            .collect::<Vec<Number>>()
-            .try_into()
-            .unwrap();
-        Rectangle(value)
+            .collect::<Result<Vec<f32>, Error>>()?;
+        // any two diagonally opposite corners may be given
+        Ok(Rectangle {
+            llx: value[0].min(value[2]),
+            lly: value[1].min(value[3]),
+            urx: value[0].max(value[2]),
+            ury: value[1].max(value[3]),
+        })
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Filter {
-    FlateDecode,
+// Rotate entry, a multiple of 90 degrees
+fn rotate(dict: &Dictionary) -> Result<Option<i32>, Error> {
+    match dict.get("Rotate").map(Object::resolve).transpose()? {
+        Some(Object::Numeric(n)) => Ok(Some(i32::from(n))),
+        None => Ok(None),
+        Some(o) => Err(Error::object(format!(
+            "Rotate should be a number, found {o:?}"
+        ))),
+    }
 }
 
-impl From<Name> for Filter {
-    fn from(value: Name) -> Self {
-        match value.as_str() {
-            "FlateDecode" => Filter::FlateDecode,
-            filter => panic!("Filter name {filter:?} is currently not supported"),
+// Matrix entry, an array of 6 numbers
+fn matrix(dict: &Dictionary, key: &str) -> Result<Option<Matrix>, Error> {
+    match dict.get(key).map(Object::resolve).transpose()? {
+        Some(Object::Array(array)) if array.len() == 6 => {
+            let values = array
+                .iter()
+                .map(|o| match o.resolve()? {
+                    Object::Numeric(n) => Ok(n),
+                    o => Err(Error::object(format!(
+                        "{key} should contain numbers, found {o:?}"
+                    ))),
+                })
+                .collect::<Result<Vec<Number>, Error>>()?;
+            Ok(Some(Matrix::from(<[Number; 6]>::try_from(values).unwrap())))
         }
+        None => Ok(None),
+        Some(o) => Err(Error::object(format!(
+            "{key} should be an array of 6 numbers, found {o:?}"
+        ))),
     }
 }
 
 #[derive(Debug, PartialEq)]
 struct StreamDictionary {
     length: Number,
-    filter: Option<Filter>,
-}
-
-impl From<Dictionary<'_>> for StreamDictionary {
-    fn from(value: Dictionary) -> Self {
-        StreamDictionary {
-            length: match value.get("Length").unwrap() {
-                Object::Numeric(n) => n.clone(),
-                Object::Ref((obj, gen), xref, bytes) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => match Object::new(bytes, address, xref.clone()) {
-                            Object::Numeric(n) => n,
-                            _ => panic!("Length should be a numeric"),
-                        },
-                        None => panic!("Length should be an indirect object"),
-                    }
-                }
-                _ => panic!("Length should be a numeric"),
-            },
-            filter: match value.get("Filter") {
-                Some(Object::Name(name)) => Some(Filter::from(name.clone())),
-                None => None,
-                _ => panic!("Filter should be a name"),
+    // filters to apply in order, with their parameters
+    filters: Vec<(Filter, DecodeParms)>,
+    // upper bound of the size of the decoded data
+    max_decoded_size: usize,
+}
+
+impl TryFrom<Dictionary<'_>> for StreamDictionary {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Ok(StreamDictionary {
+            length: match value.get("Length").map(Object::resolve).transpose()? {
+                Some(Object::Numeric(n)) => n,
+                _ => return Err(Error::object("Length should be a numeric")),
             },
-        }
+            filters: stream_filters(&value)?,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+        })
     }
 }
 
@@ -90,60 +168,34 @@ type StreamContent = Vec<u8>;
 pub struct Stream(StreamDictionary, StreamContent);
 
 impl Stream {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        let (dict, stream) = match Object::new(bytes, curr_idx, xref) {
-            Object::Stream(StreamObject { header, bytes }) => {
-                (StreamDictionary::from(header), bytes)
-            }
-            _ => panic!("Stream should be a dictionary"),
-        };
-        Stream(dict, stream)
-    }
-
-    pub fn get_data(&self) -> Vec<u8> {
-        match &self.0.filter {
-            Some(Filter::FlateDecode) => flate_decode(&self.1),
-            // Some(f) => panic!("Filter {f:?} is not supported at the moment"),
-            None => self.1.clone(),
+    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        match Object::new(bytes, curr_idx, xref)? {
+            Object::Stream(stream) => Self::try_from(stream),
+            _ => Err(Error::Object {
+                id: None,
+                offset: Some(curr_idx),
+                message: String::from("Stream should be a dictionary"),
+            }),
         }
     }
-}
 
-impl From<StreamObject<'_>> for Stream {
-    fn from(object: StreamObject<'_>) -> Self {
-        Stream(StreamDictionary::from(object.header), object.bytes)
+    pub fn get_data(&self) -> Result<Vec<u8>, Error> {
+        decode(&self.1, &self.0.filters, self.0.max_decoded_size)
     }
 }
 
-#[derive(Debug)]
-pub enum PageTreeKids {
-    Page(Page),
-    PageTreeNode(Rc<PageTreeNode>),
-}
-
-impl PageTreeKids {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        match Object::new(bytes, curr_idx, xref.clone()) {
-            Object::Dictionary(dict) => match dict.get("Type") {
-                Some(Object::Name(name)) => match name.as_str() {
-                    "Pages" => {
-                        PageTreeKids::PageTreeNode(PageTreeNode::new(bytes, curr_idx, xref.clone()))
-                    }
-                    "Page" => PageTreeKids::Page(Page::new(bytes, curr_idx, xref)),
-                    _ => panic!("Unexpected dictionnary type"),
-                },
-                Some(o) => panic!("Type should be a name, found object {o:?}"),
-                None => panic!("Type was not found in dictionnary, {dict:?}"),
-            },
-            _ => panic!("PageTreeKids should be a dictionary"),
-        }
-    }
+impl TryFrom<StreamObject<'_>> for Stream {
+    type Error = Error;
 
-    pub fn extract(&self, e: Extract) -> String {
-        match self {
-            PageTreeKids::Page(page) => page.extract(e),
-            PageTreeKids::PageTreeNode(page_tree_node) => page_tree_node.extract(e),
-        }
+    fn try_from(object: StreamObject<'_>) -> Result<Self, Self::Error> {
+        // the limit is the one of the document the stream is read from
+        Ok(Stream(
+            StreamDictionary {
+                max_decoded_size: object.max_decoded_size,
+                ..StreamDictionary::try_from(object.header)?
+            },
+            object.bytes,
+        ))
     }
 }
 
@@ -151,21 +203,169 @@ impl PageTreeKids {
 pub struct Font {
     pub subtype: Name,
     name: Option<Name>,
-    pub base_font: Name,
-    first_char: Option<Number>, // Number -> Integer
-    last_char: Option<Number>,  // Number -> Integer
+    pub base_font: Option<Name>, // optional for Type3 fonts only
+    first_char: Option<Number>,  // Number -> Integer
+    last_char: Option<Number>,   // Number -> Integer
     widths: Option<Vec<Number>>,
     pub to_unicode: Option<ToUnicodeCMap>,
-    encoding: Option<Name>,
+    pub encoding: Option<Encoding>,       // simple fonts only
+    pub cmap: Option<CMap>,               // Type0 fonts only, Identity-H when unknown
+    pub descendant_font: Option<CIDFont>, // Type0 fonts only
+    standard_font: Option<StandardFont>,  // widths of simple fonts without Widths
+    font_matrix: Option<Matrix>,          // Type3 fonts only, maps glyph space to text space
 }
 
 impl Font {
+    // Composite fonts select glyphs with multi-byte codes through a CIDFont (page 434)
+    pub fn is_composite(&self) -> bool {
+        self.subtype == "Type0"
+    }
+
+    // Character codes of a shown string
+    // Composite fonts read codes with their encoding CMap, 2 bytes codes as in Identity-H by default
+    pub fn codes(&self, text: &[u8]) -> Vec<usize> {
+        match (self.is_composite(), &self.cmap) {
+            (true, Some(cmap)) => cmap.codes(text),
+            (true, None) => text
+                .chunks(2)
+                .map(|c| c.iter().fold(0, |code, b| code * 256 + *b as usize))
+                .collect(),
+            (false, _) => text.iter().map(|c| usize::from(*c)).collect(),
+        }
+    }
+
+    // CID selected by a code of a composite font
+    pub fn cid(&self, code: usize) -> Option<usize> {
+        match &self.cmap {
+            Some(cmap) => cmap.cid(code),
+            None => Some(code),
+        }
+    }
+
+    // Unicode text of a code, from the ToUnicode CMap, from a Unicode based encoding CMap
+    // or from the glyph name given by the encoding of a simple font
+    pub fn unicode(&self, code: usize) -> Option<String> {
+        self.to_unicode
+            .as_ref()
+            .and_then(|cmap| cmap.unicode(code))
+            .or_else(|| self.cmap.as_ref().and_then(|cmap| cmap.unicode(code)))
+            .or_else(|| match (&self.encoding, u8::try_from(code)) {
+                (Some(encoding), Ok(code)) => encoding.unicode(code),
+                _ => None,
+            })
+    }
+
+    // Encoding of a simple font (page 266)
+    // Without encoding, StandardEncoding is assumed as built-in encoding except for symbolic and Type3 fonts
+    fn simple_encoding(font: &Dictionary) -> Result<Option<Encoding>, Error> {
+        let built_in = match (font.get("Subtype"), font.get("BaseFont")) {
+            (Some(Object::Name(subtype)), _) if subtype == "Type3" => None,
+            (_, Some(Object::Name(base_font)))
+                if base_font.contains("Symbol") || base_font.contains("Dingbats") =>
+            {
+                None
+            }
+            _ => Some(BaseEncoding::Standard),
+        };
+        // unsupported base encodings fall back to the built-in encoding
+        let base = |name: &str| BaseEncoding::try_from(name).ok().or(built_in);
+        match font.get("Encoding").map(Object::resolve).transpose()? {
+            Some(Object::Name(name)) => Ok(Some(Encoding {
+                base: base(&name),
+                differences: HashMap::new(),
+            })),
+            Some(Object::Dictionary(dict)) => Ok(Some(Encoding {
+                base: match dict.get("BaseEncoding") {
+                    Some(Object::Name(name)) => base(name),
+                    _ => built_in,
+                },
+                differences: match dict.get("Differences").map(Object::resolve).transpose()? {
+                    Some(Object::Array(array)) => Font::differences(array)?,
+                    None => HashMap::new(),
+                    Some(o) => {
+                        return Err(Error::font(format!(
+                            "Differences should be an array, found {o:?}"
+                        )))
+                    }
+                },
+            })),
+            None => Ok(built_in.map(|base| Encoding {
+                base: Some(base),
+                differences: HashMap::new(),
+            })),
+            Some(o) => Err(Error::font(format!(
+                "Encoding should be a name or a dictionary, found {o:?}"
+            ))),
+        }
+    }
+
+    // Differences array, each code is followed by the glyph names of consecutive codes (page 269)
+    fn differences(array: Array) -> Result<HashMap<u8, Name>, Error> {
+        let mut differences = HashMap::new();
+        let mut code = None;
+        for object in array {
+            match object.resolve()? {
+                // names following an invalid code are ignored
+                Object::Numeric(Number::Integer(n)) => code = u8::try_from(n).ok(),
+                Object::Name(name) => {
+                    if let Some(c) = code {
+                        differences.insert(c, name);
+                        code = c.checked_add(1);
+                    }
+                }
+                o => {
+                    return Err(Error::font(format!(
+                        "Differences should contain codes and glyph names, found {o:?}"
+                    )))
+                }
+            }
+        }
+        Ok(differences)
+    }
+
+    // Encoding CMap of a Type0 font, given by name or embedded in a stream (page 446)
+    fn encoding_cmap(encoding: Object) -> Result<Option<CMap>, Error> {
+        let cmap = |stream: StreamObject| {
+            CMap::try_from(
+                String::from_utf8_lossy(&Stream::try_from(stream)?.get_data()?).to_string(),
+            )
+        };
+        match encoding {
+            Object::Name(name) => Ok(CMap::predefined(&name)),
+            Object::Stream(stream) => {
+                let parent = match stream
+                    .header
+                    .get("UseCMap")
+                    .map(Object::resolve)
+                    .transpose()?
+                {
+                    Some(Object::Name(name)) => CMap::predefined(&name),
+                    Some(Object::Stream(parent)) => Some(cmap(parent)?),
+                    _ => None,
+                };
+                let vertical = matches!(
+                    stream.header.get("WMode"),
+                    Some(Object::Numeric(Number::Integer(1)))
+                );
+                let mut cmap = cmap(stream)?;
+                cmap.vertical |= vertical;
+                if let Some(parent) = parent {
+                    cmap.use_cmap(parent);
+                }
+                Ok(Some(cmap))
+            }
+            o => Err(Error::font(format!(
+                "Encoding of a Type0 font should be a name or a stream, found {o:?}"
+            ))),
+        }
+    }
+
     pub fn estimate_space_width(&self) -> Number {
-        match self.get_width(b' ') {
-            Ok(space_width) => space_width,
-            Err(_) => match self.average_width() {
+        match self.get_width(usize::from(b' ')) {
+            Ok(space_width) if f32::from(space_width.clone()) > 0.0 => space_width,
+            _ => match self.average_width() {
                 Ok(average_width) => average_width,
-                Err(_) => Number::Integer(200),
+                Err(_) => Number::Real(0.2),
             },
         }
     }
@@ -176,125 +376,365 @@ impl Font {
             for n in widths {
                 sum = sum + n.clone();
             }
-            Ok(sum / Number::Integer(widths.len() as i32) / Number::Real(1000.0))
+            Ok(self.text_space(sum / Number::Integer(widths.len() as i32)))
         } else {
             Err("Font does not contain widths")
         }
     }
 
     // horizontal displacement
-    pub fn get_width(&self, c: u8) -> Result<Number, &str> {
-        if let Some(Number::Integer(first_char)) = &self.first_char {
-            if i32::from(c) < *first_char {
-                return Err("Cannot get character width from the current font range");
-            }
+    pub fn get_width(&self, c: usize) -> Result<Number, &str> {
+        // composite fonts widths are given by CID
+        if let Some(cid_font) = &self.descendant_font {
+            return Ok(match self.cid(c) {
+                Some(cid) => cid_font.get_width(cid),
+                None => cid_font.default_width(),
+            });
+        }
+        let first_char = match &self.first_char {
+            Some(first_char) => usize::from(first_char.clone()),
+            None => 0,
+        };
+        if c < first_char {
+            return Err("Cannot get character width from the current font range");
         }
         match &self.widths {
             Some(widths) => {
-                let c_offset: usize =
-                    usize::from(c) - usize::from(self.first_char.clone().unwrap());
-                match widths.get(c_offset) {
-                    Some(n) => Ok(n.clone() / Number::Real(1000.0)), // cf note on TJ in page 408
+                match widths.get(c - first_char) {
+                    Some(n) => Ok(self.text_space(n.clone())), // cf note on TJ in page 408
                     _ => Err("Width of char was not found in the font"),
                 }
             }
-            None => Err("No character widths stored in the current font"),
+            // standard 14 fonts may omit their widths, given by the font metrics (page 416)
+            None => {
+                let width = match (&self.standard_font, &self.encoding, u8::try_from(c)) {
+                    (Some(font), Some(encoding), Ok(code)) => {
+                        encoding.glyph_name(code).and_then(|name| font.width(name))
+                    }
+                    (Some(font), None, Ok(code)) => font.code_width(code),
+                    _ => None,
+                };
+                match width {
+                    Some(width) => Ok(Number::Integer(i32::from(width)) / Number::Real(1000.0)),
+                    None => Err("No character widths stored in the current font"),
+                }
+            }
+        }
+    }
+
+    // Width in text space of a width given in glyph space
+    // Glyph space is 1/1000 of text space, except for Type3 fonts which define it with their FontMatrix (page 413)
+    fn text_space(&self, width: Number) -> Number {
+        match &self.font_matrix {
+            Some(matrix) => width * Number::Real(matrix.get_a()),
+            None => width / Number::Real(1000.0),
         }
     }
+
+    fn numbers(array: &Array) -> Result<Vec<Number>, Error> {
+        array
+            .iter()
+            .map(|o| match o {
+                Object::Numeric(n) => Ok(n.clone()),
+                o => Err(Error::font(format!(
+                    "Widths should be an array containing only numbers, found {o:?}"
+                ))),
+            })
+            .collect()
+    }
 }
 
 impl Display for Font {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Subtype: {:?}\nName: {:?}\nBaseFont: {:?}\nFirstChar: {:?}\nLastChar: {:?}\nWidths: {:?}\nToUnicode: {:?}", self.subtype, self.name, self.base_font, self.first_char, self.last_char, self.widths, self.to_unicode
+            "Subtype: {:?}\nName: {:?}\nBaseFont: {:?}\nFirstChar: {:?}\nLastChar: {:?}\nWidths: {:?}\nToUnicode: {:?}", self.subtype, self.name, self.base_font.as_deref().unwrap_or_default(), self.first_char, self.last_char, self.widths, self.to_unicode
         )
     }
 }
 
-impl From<Dictionary<'_>> for Font {
-    fn from(value: Dictionary) -> Self {
+impl TryFrom<Dictionary<'_>> for Font {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
         match value.get("Type") {
             Some(Object::Name(t)) => {
                 if t != "Font" {
-                    panic!("Font dictionnary 'Type' key should be 'Font', found {t:?}")
+                    return Err(Error::font(format!(
+                        "Font dictionnary 'Type' key should be 'Font', found {t:?}"
+                    )));
                 }
             }
-            Some(o) => panic!("Font dictionnary 'Type' key a Name object, found {o:?}"),
-            None => panic!("Font dictionnary should have a 'Type' key"),
+            Some(o) => {
+                return Err(Error::font(format!(
+                    "Font dictionnary 'Type' key a Name object, found {o:?}"
+                )))
+            }
+            None => return Err(Error::font("Font dictionnary should have a 'Type' key")),
         };
-        Font {
-            subtype: match value.get("Subtype").unwrap() {
-                Object::Name(name) => name.clone(),
-                _ => panic!("Subtype should be a name"),
-            },
+        let subtype = match value.get("Subtype") {
+            Some(Object::Name(name)) => name.clone(),
+            _ => return Err(Error::font("Subtype should be a name")),
+        };
+        Ok(Font {
             name: match value.get("Name") {
                 Some(Object::Name(name)) => Some(name.clone()),
-                Some(o) => panic!("Name should be a name, found {o:?}"),
+                Some(o) => return Err(Error::font(format!("Name should be a name, found {o:?}"))),
                 None => None,
             },
-            base_font: match value.get("BaseFont").unwrap() {
-                Object::Name(name) => name.clone(),
-                _ => panic!("BaseFont should be a name"),
+            base_font: match value.get("BaseFont") {
+                Some(Object::Name(name)) => Some(name.clone()),
+                None if subtype == "Type3" => None,
+                _ => return Err(Error::font("BaseFont should be a name")),
             },
             first_char: match value.get("FirstChar") {
                 Some(Object::Numeric(n)) => Some(n.clone()),
-                Some(o) => panic!("FirstChar should be a numeric object, found {o:?}"),
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "FirstChar should be a numeric object, found {o:?}"
+                    )))
+                }
                 None => None,
             },
             last_char: match value.get("LastChar") {
                 Some(Object::Numeric(n)) => Some(n.clone()),
-                Some(o) => panic!("LastChar should be a numeric object, found {o:?}"),
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "LastChar should be a numeric object, found {o:?}"
+                    )))
+                }
                 None => None,
             },
-            widths: match value.get("Widths") {
-                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => match Object::new(bytes, address, xref.clone()) {
-                            Object::Array(a) => Some(a.iter().map(|o| match o {
-                                Object::Numeric(n) => n.clone(),
-                                o => panic!("Widths should be an array containing only numbers, found {o:?}")
-                            }).collect()),
-                            o => panic!("ToUnicode should be a stream object, found {o:?}"),
-                        },
-                        None => panic!("ToUnicode stream object not found in xref table"),
-                    }
+            widths: match value.get("Widths").map(Object::resolve).transpose()? {
+                Some(Object::Array(a)) => Some(Font::numbers(&a)?),
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "Widths should be an array of objects, found {o:?}"
+                    )))
                 }
-                Some(Object::Array(a)) => Some(
-                    a.iter()
-                        .map(|o| match o {
-                            Object::Numeric(n) => n.clone(),
-                            o => panic!(
-                                "Widths should be an array containing only numbers, found {o:?}"
-                            ),
-                        })
-                        .collect(),
-                ),
-                Some(o) => panic!("Widths should be an array of objects, found {o:?}"),
                 None => None,
             },
-            to_unicode: match value.get("ToUnicode") {
-                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => match Object::new(bytes, address, xref.clone()) {
-                            Object::Stream(stream) => Some(ToUnicodeCMap::from(
-                                String::from_utf8_lossy(&Stream::from(stream).get_data())
-                                    .to_string(),
-                            )),
-                            o => panic!("ToUnicode should be a stream object, found {o:?}"),
-                        },
-                        None => panic!("ToUnicode stream object not found in xref table"),
+            to_unicode: match value.get("ToUnicode").map(Object::resolve).transpose()? {
+                Some(Object::Stream(stream)) => Some(ToUnicodeCMap::try_from(
+                    String::from_utf8_lossy(&Stream::try_from(stream)?.get_data()?).to_string(),
+                )?),
+                None => None,
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "ToUnicode should be a stream object, found {o:?}"
+                    )))
+                }
+            },
+            encoding: match value.get("Subtype") {
+                Some(Object::Name(subtype)) if subtype == "Type0" => None,
+                _ => Font::simple_encoding(&value)?,
+            },
+            cmap: match value.get("Subtype") {
+                Some(Object::Name(subtype)) if subtype == "Type0" => {
+                    match value.get("Encoding").map(Object::resolve).transpose()? {
+                        Some(encoding) => Font::encoding_cmap(encoding)?,
+                        None => None,
                     }
                 }
-                None => None,
-                _ => panic!("ToUnicode should be an indirect object"),
+                _ => None,
             },
-            encoding: match value.get("Encoding") {
-                Some(Object::Name(name)) => Some(name.clone()),
-                Some(_) => None, // dictionnary encoding not supported at the moment
+            descendant_font: match value
+                .get("DescendantFonts")
+                .map(Object::resolve)
+                .transpose()?
+            {
+                // array of a single CIDFont
+                Some(Object::Array(fonts)) => {
+                    match fonts.first().map(Object::resolve).transpose()? {
+                        Some(Object::Dictionary(dict)) => Some(CIDFont::try_from(dict)?),
+                        o => {
+                            return Err(Error::font(format!(
+                                "DescendantFonts should contain a font dictionary, found {o:?}"
+                            )))
+                        }
+                    }
+                }
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "DescendantFonts should be an array, found {o:?}"
+                    )))
+                }
                 None => None,
             },
+            standard_font: match value.get("BaseFont") {
+                Some(Object::Name(base_font)) if subtype != "Type0" && subtype != "Type3" => {
+                    StandardFont::from_base_font(base_font)
+                }
+                _ => None,
+            },
+            font_matrix: match subtype.as_str() {
+                "Type3" => matrix(&value, "FontMatrix")?,
+                _ => None,
+            },
+            subtype,
+        })
+    }
+}
+
+// Character collection of a CIDFont (page 435)
+#[derive(Debug, PartialEq, Clone)]
+pub struct CIDSystemInfo {
+    pub registry: String,
+    pub ordering: String,
+    pub supplement: i32,
+}
+
+impl TryFrom<Dictionary<'_>> for CIDSystemInfo {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        let string = |key: &str| match value.get(key).map(Object::resolve).transpose()? {
+            Some(Object::String(s)) => Ok(String::from_utf8_lossy(&s).to_string()),
+            o => Err(Error::font(format!(
+                "CIDSystemInfo {key} should be a string, found {o:?}"
+            ))),
+        };
+        Ok(CIDSystemInfo {
+            registry: string("Registry")?,
+            ordering: string("Ordering")?,
+            supplement: match value.get("Supplement").map(Object::resolve).transpose()? {
+                Some(Object::Numeric(Number::Integer(n))) => n,
+                o => {
+                    return Err(Error::font(format!(
+                        "CIDSystemInfo Supplement should be an integer, found {o:?}"
+                    )))
+                }
+            },
+        })
+    }
+}
+
+// Glyph widths of a CIDFont given as ranges of CIDs (page 440)
+// A range holds either one width per CID or a single width shared by all its CIDs
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CIDWidths(Vec<(usize, usize, Vec<Number>)>);
+
+impl CIDWidths {
+    pub fn get(&self, cid: usize) -> Option<Number> {
+        self.0
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&cid))
+            .and_then(|(first, _, widths)| match widths.len() {
+                1 => widths.first(),
+                _ => widths.get(cid - first),
+            })
+            .cloned()
+    }
+}
+
+impl TryFrom<Array<'_>> for CIDWidths {
+    type Error = Error;
+
+    // W array elements are either "c [w1 w2 ... wn]" or "c_first c_last w"
+    fn try_from(value: Array) -> Result<Self, Self::Error> {
+        let cid = |o: Option<Object>| match o.as_ref().map(Object::resolve).transpose()? {
+            Some(Object::Numeric(Number::Integer(n))) if n >= 0 => Ok(n as usize),
+            o => Err(Error::font(format!(
+                "W array should contain CIDs, found {o:?}"
+            ))),
+        };
+        let mut ranges = vec![];
+        let mut elements = value.into_iter();
+        while let Some(first) = elements.next() {
+            let first = cid(Some(first))?;
+            match elements.next().map(|o| o.resolve()).transpose()? {
+                Some(Object::Array(widths)) => {
+                    let widths = Font::numbers(&widths)?;
+                    if !widths.is_empty() {
+                        ranges.push((first, first + widths.len() - 1, widths));
+                    }
+                }
+                Some(Object::Numeric(Number::Integer(last))) if last as usize >= first => {
+                    let width = match elements.next().map(|o| o.resolve()).transpose()? {
+                        Some(Object::Numeric(n)) => n,
+                        o => {
+                            return Err(Error::font(format!(
+                                "W array range should end with a width, found {o:?}"
+                            )))
+                        }
+                    };
+                    ranges.push((first, last as usize, vec![width]));
+                }
+                o => {
+                    return Err(Error::font(format!(
+                        "W array should contain a width array or a last CID, found {o:?}"
+                    )))
+                }
+            }
         }
+        Ok(CIDWidths(ranges))
+    }
+}
+
+// Descendant font of a Type0 font (page 437)
+#[derive(Debug, PartialEq, Clone)]
+pub struct CIDFont {
+    pub subtype: Name, // CIDFontType0 (CFF glyphs) or CIDFontType2 (TrueType glyphs)
+    pub base_font: Name,
+    pub cid_system_info: Option<CIDSystemInfo>,
+    dw: Number, // default width
+    w: CIDWidths,
+}
+
+impl CIDFont {
+    // horizontal displacement of a CID
+    pub fn get_width(&self, cid: usize) -> Number {
+        self.w.get(cid).unwrap_or(self.dw.clone()) / Number::Real(1000.0)
+    }
+
+    pub fn default_width(&self) -> Number {
+        self.dw.clone() / Number::Real(1000.0)
+    }
+}
+
+impl TryFrom<Dictionary<'_>> for CIDFont {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Ok(CIDFont {
+            subtype: match value.get("Subtype") {
+                Some(Object::Name(name)) if name == "CIDFontType0" || name == "CIDFontType2" => {
+                    name.clone()
+                }
+                o => {
+                    return Err(Error::font(format!(
+                        "CIDFont Subtype should be CIDFontType0 or CIDFontType2, found {o:?}"
+                    )))
+                }
+            },
+            base_font: match value.get("BaseFont") {
+                Some(Object::Name(name)) => name.clone(),
+                _ => return Err(Error::font("BaseFont should be a name")),
+            },
+            cid_system_info: match value
+                .get("CIDSystemInfo")
+                .map(Object::resolve)
+                .transpose()?
+            {
+                Some(Object::Dictionary(dict)) => Some(CIDSystemInfo::try_from(dict)?),
+                None => None,
+                Some(o) => {
+                    return Err(Error::font(format!(
+                        "CIDSystemInfo should be a dictionary, found {o:?}"
+                    )))
+                }
+            },
+            dw: match value.get("DW").map(Object::resolve).transpose()? {
+                Some(Object::Numeric(n)) => n,
+                None => Number::Integer(1000),
+                Some(o) => return Err(Error::font(format!("DW should be a number, found {o:?}"))),
+            },
+            w: match value.get("W").map(Object::resolve).transpose()? {
+                Some(Object::Array(array)) => CIDWidths::try_from(array)?,
+                None => CIDWidths::default(),
+                Some(o) => return Err(Error::font(format!("W should be an array, found {o:?}"))),
+            },
+        })
     }
 }
 
@@ -313,268 +753,627 @@ impl Display for FontMap {
     }
 }
 
-impl From<Dictionary<'_>> for FontMap {
-    fn from(value: Dictionary) -> Self {
-        FontMap(
+impl TryFrom<Dictionary<'_>> for FontMap {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Ok(FontMap(
             value
                 .iter()
-                .map(|(key, value)| match value {
-                    Object::Ref((obj, gen), xref, bytes) => match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => {
-                            match Object::new(bytes, address, xref.clone()) {
-                            Object::Dictionary(t) => (key.clone(), Font::from(t)),
-                            o => panic!("Font object is not a dictionary, found {o:?}"),
-                        }},
-                        None => panic!("Font dictionnary object associated to {key:?} was not found in xref table"),
-                    },
-                    _ => panic!("Font should be an indirect object"),
+                .map(|(key, value)| match value.resolve() {
+                    Ok(Object::Dictionary(t)) => Font::try_from(t)
+                        .map(|font| (key.clone(), font))
+                        .map_err(|e| e.with_font_name(key)),
+                    Ok(o) => Err(Error::font(format!(
+                        "Font object is not a dictionary, found {o:?}"
+                    ))
+                    .with_font_name(key)),
+                    Err(e) => Err(e),
                 })
-                .collect(),
-        )
+                .collect::<Result<HashMap<Name, Font>, Error>>()?,
+        ))
+    }
+}
+
+// External object painted with the Do operator (page 332)
+#[derive(Debug, PartialEq, Clone)]
+pub enum XObject {
+    Form(Rc<FormXObject>),
+    Image,
+}
+
+impl XObject {
+    // Forms already being read are skipped to break cycles between forms
+    // Forms already read are shared rather than read again for each resources dictionary invoking them
+    fn read(object: &Object, reader: &mut FormReader) -> Result<Option<Self>, Error> {
+        let id = match object {
+            Object::Ref(id, ..) if reader.visited.contains(id) => {
+                reader.cuts += 1;
+                return Ok(None);
+            }
+            Object::Ref(id, ..) => match reader.cached(id) {
+                Some(form) => return Ok(Some(XObject::Form(form))),
+                None => Some(*id),
+            },
+            _ => None,
+        };
+        // images are classified from their header without reading their data
+        if let Some(header) = object.stream_header()? {
+            match header.get("Subtype") {
+                Some(Object::Name(subtype)) if subtype == "Form" => (),
+                Some(Object::Name(subtype)) if subtype == "Image" => {
+                    return Ok(Some(XObject::Image))
+                }
+                _ => return Ok(None),
+            }
+        }
+        let stream = match object.resolve()? {
+            Object::Stream(stream) => stream,
+            o => {
+                return Err(Error::object(format!(
+                    "XObject should be a stream, found {o:?}"
+                )))
+            }
+        };
+        match stream.header.get("Subtype") {
+            Some(Object::Name(subtype)) if subtype == "Form" => {
+                let cuts = reader.cuts;
+                reader.visited.extend(id);
+                let form = FormXObject::read(stream, id, reader).map_err(|e| match id {
+                    Some(id) => e.with_id(id),
+                    None => e,
+                });
+                reader.visited.retain(|v| Some(*v) != id);
+                let form = Rc::new(form?);
+                if let Some(id) = id {
+                    reader.cache(id, form.clone(), reader.cuts != cuts);
+                }
+                Ok(Some(XObject::Form(form)))
+            }
+            Some(Object::Name(subtype)) if subtype == "Image" => Ok(Some(XObject::Image)),
+            // PostScript XObjects are not painted
+            _ => Ok(None),
+        }
     }
 }
 
+// State shared while reading resources and the forms they contain
+#[derive(Default)]
+struct FormReader {
+    visited: Vec<IndirectObject>, // forms and resources dictionaries being read
+    // forms already read, with the objects being read when a cycle was cut in them
+    forms: HashMap<(IndirectObject, Vec<IndirectObject>), Rc<FormXObject>>,
+    cuts: usize, // number of cycles cut so far
+}
+
+impl FormReader {
+    // Forms whose cycles were cut depend on the objects being read, they are only shared in the same context
+    fn cached(&self, id: &IndirectObject) -> Option<Rc<FormXObject>> {
+        self.forms
+            .get(&(*id, vec![]))
+            .or_else(|| self.forms.get(&(*id, self.visited.clone())))
+            .cloned()
+    }
+
+    fn cache(&mut self, id: IndirectObject, form: Rc<FormXObject>, cut: bool) {
+        let context = match cut {
+            true => self.visited.clone(),
+            false => vec![],
+        };
+        self.forms.insert((id, context), form);
+    }
+}
+
+// Self-contained content stream painted as a whole (page 355)
+#[derive(Debug, PartialEq)]
+pub struct FormXObject {
+    pub id: Option<IndirectObject>,
+    pub bbox: Rectangle,
+    pub matrix: Matrix, // mapping from form space to user space
+    // Forms without resources use the resources of the content invoking them
+    pub resources: Option<Rc<Resources>>,
+    // Resources cut by the cycle guard are those of an outer content, found by their id
+    pub resources_id: Option<IndirectObject>,
+    content: Stream,
+}
+
+impl FormXObject {
+    fn read(
+        stream: StreamObject,
+        id: Option<IndirectObject>,
+        reader: &mut FormReader,
+    ) -> Result<Self, Error> {
+        let header = &stream.header;
+        Ok(FormXObject {
+            id,
+            bbox: match Rectangle::get(header, "BBox")? {
+                Some(bbox) => bbox,
+                None => return Err(Error::object("Form XObject should have a BBox")),
+            },
+            matrix: matrix(header, "Matrix")?.unwrap_or_default(),
+            resources: Resources::read(header.get("Resources"), reader)?.map(Rc::new),
+            resources_id: match header.get("Resources") {
+                Some(Object::Ref(id, ..)) => Some(*id),
+                _ => None,
+            },
+            content: Stream::try_from(stream)?,
+        })
+    }
+
+    pub fn get_data(&self) -> Result<Vec<u8>, Error> {
+        self.content.get_data()
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct XObjectMap(pub HashMap<Name, XObject>);
+
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct Resources {
+    pub id: Option<IndirectObject>, // indirect resources dictionaries only
     pub font: Option<FontMap>,
+    pub xobject: Option<XObjectMap>,
 }
 
 impl Resources {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        match Object::new(bytes, curr_idx, xref) {
-            Object::Dictionary(dict) => Self::from(dict),
-            _ => panic!("Trailer should be a dictionary"),
+    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        match Object::new(bytes, curr_idx, xref)? {
+            Object::Dictionary(dict) => Self::try_from(dict),
+            _ => Err(Error::object("Resources should be a dictionary")),
         }
     }
-}
 
-impl From<Dictionary<'_>> for Resources {
-    fn from(value: Dictionary) -> Self {
-        Resources {
-            font: match value.get("Font") {
-                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                    xref.get_and_fix(&(*obj, *gen), bytes).map(|address| {
-                        FontMap::from(match Object::new(bytes, address, xref.clone()) {
-                            Object::Dictionary(t) => t,
-                            _ => panic!("Font should be a dictionary"),
-                        })
-                    })
+    // Optional resources entry, direct or indirect
+    // Dictionaries already being read are skipped since forms frequently share the resources of their page
+    fn read(object: Option<&Object>, reader: &mut FormReader) -> Result<Option<Self>, Error> {
+        let id = match object {
+            None => return Ok(None),
+            Some(Object::Ref(id, ..)) if reader.visited.contains(id) => {
+                reader.cuts += 1;
+                return Ok(None);
+            }
+            Some(Object::Ref(id, ..)) => Some(*id),
+            Some(_) => None,
+        };
+        let dict = match object.map(Object::resolve).transpose()? {
+            Some(Object::Dictionary(dict)) => dict,
+            o => {
+                return Err(Error::object(format!(
+                    "Resources should be a dictionary, found {o:?}"
+                )))
+            }
+        };
+        reader.visited.extend(id);
+        let resources = Resources::parse(dict, reader);
+        reader.visited.retain(|v| Some(*v) != id);
+        resources.map(|resources| Some(Resources { id, ..resources }))
+    }
+
+    fn parse(value: Dictionary, reader: &mut FormReader) -> Result<Self, Error> {
+        Ok(Resources {
+            id: None,
+            font: match value.get("Font").map(Object::resolve).transpose()? {
+                Some(Object::Dictionary(t)) => Some(FontMap::try_from(t)?),
+                None => None,
+                f => {
+                    return Err(Error::object(format!(
+                        "Font should be an indirect object or a dictionary; found {f:?}"
+                    )))
+                }
+            },
+            xobject: match value.get("XObject").map(Object::resolve).transpose()? {
+                Some(Object::Dictionary(t)) => {
+                    let mut xobjects = HashMap::new();
+                    for (name, object) in t.iter() {
+                        if let Some(xobject) = XObject::read(object, reader)? {
+                            xobjects.insert(name.clone(), xobject);
+                        }
+                    }
+                    Some(XObjectMap(xobjects))
                 }
-                Some(Object::Dictionary(t)) => Some(FontMap::from(t.clone())),
                 None => None,
-                f => panic!("Font should be an indirect object or a dictionary; found {f:?}"),
+                o => {
+                    return Err(Error::object(format!(
+                        "XObject should be a dictionary, found {o:?}"
+                    )))
+                }
             },
-        }
+        })
     }
 }
 
-#[derive(Debug)]
-pub struct PageTreeNode {
-    parent: RefCell<Weak<PageTreeNode>>, // PageTreeNode parent
-    kids: Vec<PageTreeKids>,             // PageTreeNode kids can be a Page or a PageTreeNode
-    // count: Number,                       // Number of leaf nodes
-    // Inheritables (cf page 149)
-    // rotate: Option<Number>, // Number of degrees by which the page should be rotated clockwise when displayeds
-    // crop_box: Option<Rectangle>, // CropBox Rectangle
-    // media_box: Option<Rectangle>, // MediaBox Rectangle
-    resources: Option<Resources>, // Resource dictionary
-}
-
-impl PageTreeNode {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Rc<Self> {
-        match Object::new(bytes, curr_idx, xref) {
-            Object::Dictionary(dict) => {
-                let page_tree_node = Rc::new(Self::from(dict));
-                // update parent weak reference of children
-                page_tree_node.kids.iter().for_each(|k| match k {
-                    PageTreeKids::Page(p) => {
-                        *p.parent.borrow_mut() = Rc::downgrade(&page_tree_node)
+impl TryFrom<Dictionary<'_>> for Resources {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Resources::parse(value, &mut FormReader::default())
+    }
+}
+
+// Attributes that pages inherit from their ancestors in the page tree (page 149)
+const INHERITABLE: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+
+// Lazy access to the page tree, only the nodes leading to the requested pages are read
+pub struct PageTree<'a> {
+    root: Dictionary<'a>,
+}
+
+impl<'a> PageTree<'a> {
+    pub fn new(root: Dictionary<'a>) -> Self {
+        PageTree { root }
+    }
+
+    // Number of pages, read from the Count entry of the root when available
+    pub fn count(&self) -> Result<usize, Error> {
+        PageTree::node_count(&self.root, 0)
+    }
+
+    // Page at the given zero-based index, subtrees before it are skipped using their Count entry
+    pub fn page(&self, index: usize) -> Result<Page, Error> {
+        let mut node = self.root.clone();
+        let mut inherited = PageTree::inherit(Dictionary::new(), &node);
+        let mut remaining = index;
+        let mut depth = 0;
+        'descend: loop {
+            for kid in PageTree::kids(&node)? {
+                let (id, kid) = PageTree::resolve_kid(&kid)?;
+                if !PageTree::is_node(&kid) {
+                    if remaining == 0 {
+                        return PageTree::leaf(kid, &inherited).map_err(|e| e.with_id(id));
                     }
-                    PageTreeKids::PageTreeNode(p) => {
-                        *p.parent.borrow_mut() = Rc::downgrade(&page_tree_node)
+                    remaining -= 1;
+                    continue;
+                }
+                let count = PageTree::node_count(&kid, depth + 1).map_err(|e| e.with_id(id))?;
+                if remaining < count {
+                    depth += 1;
+                    if depth > MAX_PAGE_TREE_DEPTH {
+                        return Err(Error::object("Page tree is too deep or contains a loop"));
                     }
-                });
-                page_tree_node
+                    inherited = PageTree::inherit(inherited, &kid);
+                    node = kid;
+                    continue 'descend;
+                }
+                remaining -= count;
             }
-            _ => panic!("Trailer should be a dictionary"),
+            return Err(Error::object(format!(
+                "Page {index} not found in page tree"
+            )));
         }
     }
 
-    fn get_resources(&self) -> Option<Resources> {
-        match &self.resources {
-            Some(r) => Some(r.clone()), // TODO : improve with smart pointer instead of cloning
-            None => match self.parent.borrow().upgrade() {
-                Some(p) => p.get_resources(),
-                None => None,
+    // Iterate over pages in document order, reading each page only when it is reached
+    pub fn pages(&self) -> Result<Pages<'a>, Error> {
+        Ok(Pages {
+            stack: vec![(
+                PageTree::kids(&self.root)?.into_iter(),
+                PageTree::inherit(Dictionary::new(), &self.root),
+            )],
+        })
+    }
+
+    fn kids(node: &Dictionary<'a>) -> Result<Array<'a>, Error> {
+        match node.get("Kids").map(Object::resolve).transpose()? {
+            Some(Object::Array(kids)) => Ok(kids),
+            o => Err(Error::object(format!(
+                "Kids should be an array, found {o:?}"
+            ))),
+        }
+    }
+
+    fn resolve_kid(kid: &Object<'a>) -> Result<(IndirectObject, Dictionary<'a>), Error> {
+        match kid {
+            Object::Ref(id, ..) => match kid.resolve()? {
+                Object::Dictionary(dict) => Ok((*id, dict)),
+                o => Err(Error::object(format!(
+                    "Page tree kid should be a dictionary, found {o:?}"
+                ))
+                .with_id(*id)),
             },
+            o => Err(Error::object(format!(
+                "Kid should be an indirect object, found {o:?}"
+            ))),
         }
     }
 
-    pub fn extract(&self, e: Extract) -> String {
-        self.kids
-            .iter()
-            .map(|kid| kid.extract(e.clone()))
-            .collect::<Vec<String>>()
-            .join("\n")
+    // Intermediate nodes are recognised by their type, or by their kids when the type is missing
+    fn is_node(dict: &Dictionary) -> bool {
+        match dict.get("Type") {
+            Some(Object::Name(t)) => t == "Pages",
+            _ => dict.contains_key("Kids"),
+        }
+    }
+
+    // Leaves below a node, counted from the kids when Count is missing
+    fn node_count(node: &Dictionary<'a>, depth: usize) -> Result<usize, Error> {
+        if depth > MAX_PAGE_TREE_DEPTH {
+            return Err(Error::object("Page tree is too deep or contains a loop"));
+        }
+        match node.get("Count").map(Object::resolve).transpose()? {
+            Some(Object::Numeric(Number::Integer(n))) if n >= 0 => Ok(n as usize),
+            Some(o) => Err(Error::object(format!(
+                "Count should be a positive integer, found {o:?}"
+            ))),
+            None => PageTree::kids(node)?
+                .iter()
+                .map(|kid| {
+                    let (_, kid) = PageTree::resolve_kid(kid)?;
+                    match PageTree::is_node(&kid) {
+                        true => PageTree::node_count(&kid, depth + 1),
+                        false => Ok(1),
+                    }
+                })
+                .sum(),
+        }
+    }
+
+    // Attributes of a node override the ones inherited from its ancestors
+    fn inherit(mut inherited: Dictionary<'a>, node: &Dictionary<'a>) -> Dictionary<'a> {
+        for key in INHERITABLE {
+            if let Some(value) = node.get(key) {
+                inherited.insert(String::from(key), value.clone());
+            }
+        }
+        inherited
+    }
+
+    fn leaf(mut page: Dictionary<'a>, inherited: &Dictionary<'a>) -> Result<Page, Error> {
+        for (key, value) in inherited {
+            page.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        Page::try_from(page)
     }
 }
 
-impl From<Dictionary<'_>> for PageTreeNode {
-    fn from(value: Dictionary) -> Self {
-        PageTreeNode {
-            parent: RefCell::new(Weak::new()),
-            kids: match value.get("Kids").unwrap() {
-                Object::Array(arr) => arr
-                    .iter()
-                    .map(|kid| match kid {
-                        Object::Ref((obj, gen), xref, bytes) => {
-                            match xref.get_and_fix(&(*obj, *gen), bytes) {
-                                Some(address) => PageTreeKids::new(bytes, address, xref.clone()),
-                                None => panic!("Kid not found in xref table"),
-                            }
-                        }
-                        _ => panic!("Kid should be an indirect object"),
-                    })
-                    .collect(),
-                _ => panic!("Kids should be an array"),
-            },
-            // count: match value.get("Count").unwrap() {
-            //     Object::Numeric(n) => n.clone(),
-            //     _ => panic!("Count should be a numeric"),
-            // },
-            // rotate: match value.get("Rotate") {
-            //     Some(Object::Numeric(n)) => Some(n.clone()),
-            //     None => None,
-            //     _ => panic!("Rotate should be a numeric"),
-            // },
-            // crop_box: match value.get("CropBox") {
-            //     Some(Object::Array(arr)) => Some(Rectangle::from(arr.clone())),
-            //     None => None,
-            //     _ => panic!("CropBox should be an array"),
-            // },
-            // media_box: match value.get("MediaBox") {
-            //     Some(Object::Array(arr)) => Some(Rectangle::from(arr.clone())),
-            //     None => None,
-            //     _ => panic!("MediaBox should be an array"),
-            // },
-            resources: match value.get("Resources") {
-                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => Some(Resources::new(bytes, address, xref.clone())),
-                        None => panic!("Kid not found in xref table"),
+// Deepest page tree accepted, protects against loops between nodes
+const MAX_PAGE_TREE_DEPTH: usize = 256;
+
+// Iterator over pages, holding the remaining kids and inherited attributes of each visited node
+pub struct Pages<'a> {
+    stack: Vec<(std::vec::IntoIter<Object<'a>>, Dictionary<'a>)>,
+}
+
+impl Iterator for Pages<'_> {
+    type Item = Result<Page, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    // Skipped pages are not read, and whole subtrees are skipped using their Count entry
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            let (kids, inherited) = self.stack.last_mut()?;
+            let Some(kid) = kids.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let inherited = inherited.clone();
+            let result = PageTree::resolve_kid(&kid).and_then(|(id, kid)| {
+                if !PageTree::is_node(&kid) {
+                    if n > 0 {
+                        n -= 1;
+                        return Ok(None);
                     }
+                    return PageTree::leaf(kid, &inherited)
+                        .map(Some)
+                        .map_err(|e| e.with_id(id));
                 }
-                None => None,
-                _ => panic!("Resources should be an indirect object"),
-            },
+                if self.stack.len() > MAX_PAGE_TREE_DEPTH {
+                    return Err(Error::object("Page tree is too deep or contains a loop"));
+                }
+                if n > 0 {
+                    let count =
+                        PageTree::node_count(&kid, self.stack.len()).map_err(|e| e.with_id(id))?;
+                    if n >= count {
+                        n -= count;
+                        return Ok(None);
+                    }
+                }
+                let kids = PageTree::kids(&kid).map_err(|e| e.with_id(id))?;
+                self.stack
+                    .push((kids.into_iter(), PageTree::inherit(inherited, &kid)));
+                Ok(None)
+            });
+            match result {
+                Ok(Some(page)) => return Some(Ok(page)),
+                Ok(None) => continue,
+                // stop iterating after an error
+                Err(e) => {
+                    self.stack.clear();
+                    return Some(Err(e));
+                }
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub struct Page {
-    parent: RefCell<Weak<PageTreeNode>>, // Page leaf parent
     // last_modified: Option<String>,       // Date and time of last modification
-    resources: Option<Resources>, // Resource dictionary (inheritable from PageTreeNode)
-    // media_box: Option<Rectangle>,        // MediaBox rectangle (inheritable from PageTreeNode)
-    // crop_box: Option<Rectangle>,         // CropBox rectangle (inheritable from PageTreeNode)
-    contents: Option<Stream>, // Page content
+    resources: Option<Resources>, // Resource dictionary (inheritable from the page tree)
+    media_box: Option<Rectangle>, // MediaBox rectangle (inheritable from the page tree)
+    crop_box: Option<Rectangle>,  // CropBox rectangle (inheritable from the page tree)
+    bleed_box: Option<Rectangle>, // Clipping region in a production environment
+    trim_box: Option<Rectangle>,  // Intended dimensions of the finished page
+    art_box: Option<Rectangle>,   // Extent of the meaningful content
+    rotate: Option<i32>, // Clockwise rotation when displayed (inheritable from the page tree)
+    user_unit: Option<f32>, // Size of default user space units, in multiples of 1/72 inch
+    contents: Vec<Stream>, // Page content, possibly split across several streams
 }
 
 impl Page {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        match Object::new(bytes, curr_idx, xref) {
-            Object::Dictionary(dict) => Self::from(dict),
-            _ => panic!("Trailer should be a dictionary"),
+    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        match Object::new(bytes, curr_idx, xref)? {
+            Object::Dictionary(dict) => Self::try_from(dict),
+            _ => Err(Error::object("Page should be a dictionary")),
         }
     }
 
-    // Get resources from Page or parent if missing
-    pub fn get_resources(&self) -> Box<Resources> {
+    // Resources of the page, including the ones inherited from the page tree
+    pub fn get_resources(&self) -> Result<Box<Resources>, Error> {
         match &self.resources {
-            Some(r) => Box::new(r.clone()),
-            None => match self.parent.borrow().upgrade() {
-                Some(p) => match p.get_resources() {
-                    Some(r) => Box::new(r),
-                    None => panic!("Resources not found for current Page and in parent tree"),
-                },
-                None => panic!("Unable to retrieve Page Resource, current page with no parent"),
-            },
+            Some(r) => Ok(Box::new(r.clone())),
+            None => Err(Error::object(
+                "Resources not found for current Page and in parent tree",
+            )),
+        }
+    }
+
+    // Boundaries of the physical medium, US Letter when missing
+    pub fn get_media_box(&self) -> Rectangle {
+        self.media_box.unwrap_or(DEFAULT_MEDIA_BOX)
+    }
+
+    // Visible region of the page, clipped to the media box
+    pub fn get_crop_box(&self) -> Rectangle {
+        let media_box = self.get_media_box();
+        match self.crop_box {
+            Some(crop_box) => crop_box.intersect(&media_box),
+            None => media_box,
         }
     }
 
-    pub fn extract(&self, e: Extract) -> String {
+    // Bleed, trim and art boxes default to the crop box (page 963)
+    pub fn get_bleed_box(&self) -> Rectangle {
+        self.bleed_box.unwrap_or_else(|| self.get_crop_box())
+    }
+
+    pub fn get_trim_box(&self) -> Rectangle {
+        self.trim_box.unwrap_or_else(|| self.get_crop_box())
+    }
+
+    pub fn get_art_box(&self) -> Rectangle {
+        self.art_box.unwrap_or_else(|| self.get_crop_box())
+    }
+
+    // Clockwise rotation in degrees, one of 0, 90, 180 or 270
+    pub fn get_rotate(&self) -> i32 {
+        match self.rotate {
+            // invalid rotations are ignored
+            Some(r) if r % 90 == 0 => r.rem_euclid(360),
+            _ => 0,
+        }
+    }
+
+    pub fn get_user_unit(&self) -> f32 {
+        self.user_unit.unwrap_or(1.0)
+    }
+
+    // Crop box in display orientation, width and height are swapped for 90 and 270 degrees rotations
+    pub fn effective_bbox(&self) -> Rectangle {
+        self.get_crop_box().rotate(self.get_rotate())
+    }
+
+    pub fn extract(&self, e: Extract) -> Result<String, Error> {
         match e {
             Extract::Text => self.extract_text(false),
+            Extract::Layout => self.extract_layout(),
             Extract::Chars => self.extract_text(true),
             Extract::RawContent => self.extract_stream(),
             Extract::Font => self.extract_font(),
+            Extract::Info => Err(Error::object(
+                "Document information is not part of a page, use Pdf::info",
+            )),
         }
     }
 
-    fn extract_font(&self) -> String {
-        match self.get_resources().font {
-            Some(font_map) => font_map.to_string(),
-            None => panic!("Missing font in current page resources"),
+    fn extract_font(&self) -> Result<String, Error> {
+        match self.get_resources()?.font {
+            Some(font_map) => Ok(font_map.to_string()),
+            None => Err(Error::font("Missing font in current page resources")),
         }
     }
 
-    fn extract_text(&self, char: bool) -> String {
-        let content_bytes = self.extract_stream();
-        let mut text_content =
-            content::TextContent::new(content_bytes.as_bytes(), self.get_resources());
+    fn extract_text(&self, char: bool) -> Result<String, Error> {
+        let content_bytes = self.content_data()?;
+        if content_bytes.is_empty() {
+            return Ok(String::new());
+        }
+        let mut text_content = content::TextContent::new(&content_bytes, self.get_resources()?);
         text_content.get_text(char)
     }
 
-    fn extract_stream(&self) -> String {
-        // Extract text
-        match &self.contents {
-            Some(stream) => String::from_utf8_lossy(&stream.get_data()).to_string(),
-            None => panic!("Contents should not be empty"),
+    // Content of the page parsed into operations
+    pub fn content_stream(&self) -> Result<content::ContentStream, Error> {
+        content::ContentStream::try_from(self.content_data()?.as_slice())
+    }
+
+    // Text placed on a character grid from the device space positions of the glyphs
+    fn extract_layout(&self) -> Result<String, Error> {
+        let content_bytes = self.content_data()?;
+        if content_bytes.is_empty() {
+            return Ok(String::new());
         }
+        let mut text_content = content::TextContent::new(&content_bytes, self.get_resources()?);
+        text_content.get_text(false)?;
+        Ok(layout::layout(text_content.glyphs()))
+    }
+
+    fn extract_stream(&self) -> Result<String, Error> {
+        Ok(String::from_utf8_lossy(&self.content_data()?).to_string())
+    }
+
+    // Decoded content streams, separated by a newline so that tokens at stream boundaries are not merged
+    // A page without contents is empty
+    pub fn content_data(&self) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .contents
+            .iter()
+            .map(Stream::get_data)
+            .collect::<Result<Vec<Vec<u8>>, Error>>()?
+            .join(&b'\n'))
     }
 }
 
-impl From<Dictionary<'_>> for Page {
-    fn from(value: Dictionary) -> Self {
-        Page {
-            parent: RefCell::new(Weak::new()),
+impl TryFrom<Dictionary<'_>> for Page {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Ok(Page {
             // last_modified: match value.get("LastModified") {
             //     Some(Object::String(s)) => Some(s.clone()),
             //     None => None,
             //     _ => panic!("LastModified should be a string"),
             // },
-            resources: match value.get("Resources").unwrap() {
-                Object::Dictionary(t) => Some(Resources::from(t.clone())),
-                Object::Ref((obj, gen), xref, bytes) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => Some(Resources::new(bytes, address, xref.clone())),
-                        None => panic!("Resource dictionnary address not found in xref keys"),
-                    }
+            resources: Resources::read(value.get("Resources"), &mut FormReader::default())?,
+            media_box: Rectangle::get(&value, "MediaBox")?,
+            crop_box: Rectangle::get(&value, "CropBox")?,
+            bleed_box: Rectangle::get(&value, "BleedBox")?,
+            trim_box: Rectangle::get(&value, "TrimBox")?,
+            art_box: Rectangle::get(&value, "ArtBox")?,
+            rotate: rotate(&value)?,
+            user_unit: match value.get("UserUnit").map(Object::resolve).transpose()? {
+                Some(Object::Numeric(n)) => Some(f32::from(n)),
+                None => None,
+                Some(o) => {
+                    return Err(Error::object(format!(
+                        "UserUnit should be a number, found {o:?}"
+                    )))
                 }
-                t => panic!("Resources should be an dictionary object {t:?}"),
             },
-            // media_box: match value.get("MediaBox") {
-            //     Some(Object::Array(arr)) => Some(Rectangle::from(arr.clone())),
-            //     Some(a) => panic!("MediaBox should be an array; found {a:?}"),
-            //     None => None,
-            // },
-            // crop_box: match value.get("CropBox") {
-            //     Some(Object::Array(arr)) => Some(Rectangle::from(arr.clone())),
-            //     Some(a) => panic!("CropBox should be an array; found {a:?}"),
-            //     None => None,
-            // },
-            contents: match value.get("Contents") {
-                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                        Some(address) => Some(Stream::new(bytes, address, xref.clone())),
-                        None => panic!("Resource dictionnary address not found in xref keys"),
-                    }
+            contents: match value.get("Contents").map(Object::resolve).transpose()? {
+                Some(Object::Stream(stream)) => vec![Stream::try_from(stream)?],
+                Some(Object::Array(streams)) => streams
+                    .iter()
+                    .filter_map(|stream| match stream.resolve() {
+                        Ok(Object::Stream(stream)) => Some(Stream::try_from(stream)),
+                        // null references to missing streams are skipped
+                        Ok(Object::Null) => None,
+                        Ok(o) => Some(Err(Error::object(format!(
+                            "Contents should be an array of streams, found {o:?}"
+                        )))),
+                        Err(e) => Some(Err(e)),
+                    })
+                    .collect::<Result<Vec<Stream>, Error>>()?,
+                None => vec![],
+                Some(o) => {
+                    return Err(Error::object(format!(
+                        "Contents should be a stream or an array of streams, found {o:?}"
+                    )))
                 }
-                None => None,
-                _ => panic!("Contents should be an indirect object"),
             },
-        }
+        })
     }
 }
 
@@ -582,50 +1381,480 @@ impl From<Dictionary<'_>> for Page {
 // Defined in page 139;  commented is to be implemented
 #[derive(Debug)]
 pub struct Catalog {
-    // The page tree node that is the root of the document’s page tree
-    // Must be an indirect reference
-    pub pages: Option<Rc<PageTreeNode>>,
+    // Pages of the document's page tree, with the attributes inherited from their ancestors
+    // None when the page tree root is missing from the cross-reference
+    pub pages: Option<Vec<Page>>,
 }
 
 impl Catalog {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        match Object::new(bytes, curr_idx, xref) {
-            Object::Dictionary(dict) => Self::from(dict),
-            o => panic!("Catalog should be a dictionary, found {o:?}"),
+    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        match Object::new(bytes, curr_idx, xref)? {
+            Object::Dictionary(dict) => Self::try_from(dict),
+            o => Err(Error::object(format!(
+                "Catalog should be a dictionary, found {o:?}"
+            ))),
         }
     }
 
-    pub fn extract(&self, e: Extract) -> String {
+    pub fn extract(&self, e: Extract, separator: &str) -> Result<String, Error> {
         match &self.pages {
-            Some(page_tree_node) => page_tree_node.extract(e),
-            None => panic!("Pages should not be empty"),
+            Some(pages) => Ok(pages
+                .iter()
+                .map(|page| page.extract(e.clone()))
+                .collect::<Result<Vec<String>, Error>>()?
+                .join(separator)),
+            None => Err(Error::object("Pages should not be empty")),
         }
     }
 }
 
-impl From<Dictionary<'_>> for Catalog {
-    fn from(value: Dictionary) -> Self {
-        Catalog {
-            pages: match value.get("Pages").unwrap() {
-                Object::Ref((obj, gen), xref, bytes) => xref
-                    .get_and_fix(&(*obj, *gen), bytes)
-                    .map(|address| PageTreeNode::new(bytes, address, xref.clone())),
-                _ => panic!("Pages should be an indirect object"),
+impl TryFrom<Dictionary<'_>> for Catalog {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        Ok(Catalog {
+            pages: match value.get("Pages") {
+                Some(pages @ Object::Ref(id, xref, bytes)) => match xref.get_and_fix(id, bytes) {
+                    Some(_) => match pages.resolve()? {
+                        Object::Dictionary(dict) => Some(
+                            PageTree::new(dict)
+                                .pages()
+                                .and_then(|pages| pages.collect::<Result<Vec<Page>, Error>>())
+                                .map_err(|e| e.with_id(*id))?,
+                        ),
+                        o => {
+                            return Err(Error::object(format!(
+                                "Pages should be a dictionary, found {o:?}"
+                            ))
+                            .with_id(*id))
+                        }
+                    },
+                    None => None,
+                },
+                _ => return Err(Error::object("Pages should be an indirect object")),
             },
-        }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::xref::XRefTable;
+    use crate::{
+        tokenizer::Tokenizer,
+        xref::{Entry, XRefTable},
+    };
 
     use super::*;
 
+    // Document made of objects of generation 0, each object header starting a line
+    fn xref(bytes: &[u8]) -> Rc<XRef> {
+        let entries = (0..bytes.len())
+            .filter(|i| *i == 0 || bytes[i - 1] == b'\n')
+            .filter_map(|i| {
+                let digits = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+                let number = std::str::from_utf8(&bytes[i..i + digits])
+                    .ok()?
+                    .parse()
+                    .ok()?;
+                let entry = Entry::InUse {
+                    offset: i,
+                    generation: 0,
+                };
+                bytes[i + digits..]
+                    .starts_with(b" 0 obj")
+                    .then_some((number, entry))
+            });
+        Rc::new(XRef::XRefTable(XRefTable::from_entries(entries)))
+    }
+
+    // Page object 1 of a document
+    fn page(bytes: &[u8]) -> Page {
+        match Object::Ref((1, 0), xref(bytes), bytes).resolve().unwrap() {
+            Object::Dictionary(dict) => Page::try_from(dict).unwrap(),
+            o => panic!("{o:?}"),
+        }
+    }
+
     #[test]
     fn test_catalog() {
-        let catalog = Catalog::new(b"1 0 obj  % entry point\n    <<\n      /Type /Catalog\n      /Pages 2 0 R\n    >>\n    endobj".as_slice(), 0, Rc::new(XRef::XRefTable(XRefTable::default())));
+        let catalog = Catalog::new(b"1 0 obj  % entry point\n    <<\n      /Type /Catalog\n      /Pages 2 0 R\n    >>\n    endobj".as_slice(), 0, Rc::new(XRef::XRefTable(XRefTable::default()))).unwrap();
         assert!(catalog.pages.is_none())
     }
+
+    #[test]
+    fn test_page_tree() {
+        let mut bytes = b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Pages /Kids [3 0 R 6 0 R 7 0 R] /Count 5 /Resources << >> >>\nendobj\n\
+            3 0 obj\n<< /Type /Pages /Kids [4 0 R 5 0 R] /Count 2 >>\nendobj\n\
+            7 0 obj\n<< /Type /Pages /Kids [8 0 R 9 0 R] >>\nendobj\n\
+            20 0 obj\n<< /Type /Pages /Kids [20 0 R] /Count 1 >>\nendobj\n"
+            .to_vec();
+        for (page, contents) in [(4, 10), (5, 11), (6, 12), (8, 13), (9, 14)] {
+            bytes.extend(format!("{page} 0 obj\n<< /Type /Page /Contents {contents} 0 R >>\nendobj\n{contents} 0 obj\n<< /Length 2 >>\nstream\np{page}\nendstream\nendobj\n").as_bytes());
+        }
+        let xref = xref(&bytes);
+        let dict = |id| match Object::Ref((id, 0), xref.clone(), &bytes)
+            .resolve()
+            .unwrap()
+        {
+            Object::Dictionary(dict) => dict,
+            o => panic!("{o:?}"),
+        };
+        let page_tree = PageTree::new(dict(2));
+        assert_eq!(page_tree.count().unwrap(), 5);
+        let expected = ["p4", "p5", "p6", "p8", "p9"];
+        for (index, contents) in expected.iter().enumerate() {
+            let page = page_tree.page(index).unwrap();
+            assert_eq!(page.extract(Extract::RawContent).unwrap(), *contents);
+            // resources are inherited from the root
+            assert!(page.resources.is_some());
+        }
+        assert!(page_tree.page(5).is_err());
+        let pages = page_tree
+            .pages()
+            .unwrap()
+            .map(|page| page.and_then(|p| p.extract(Extract::RawContent)))
+            .collect::<Result<Vec<String>, Error>>()
+            .unwrap();
+        assert_eq!(pages, expected);
+        let mut pages = page_tree.pages().unwrap();
+        let page = pages.nth(2).unwrap().unwrap();
+        assert_eq!(page.extract(Extract::RawContent).unwrap(), "p6");
+        let page = pages.nth(1).unwrap().unwrap();
+        assert_eq!(page.extract(Extract::RawContent).unwrap(), "p9");
+        assert!(pages.next().is_none());
+        let catalog = Catalog::try_from(dict(1)).unwrap();
+        assert_eq!(
+            catalog.extract(Extract::RawContent, "\x0c").unwrap(),
+            expected.join("\x0c")
+        );
+
+        // a node that contains itself
+        let page_tree = PageTree::new(dict(20));
+        assert!(page_tree.page(0).is_err());
+        assert!(page_tree.pages().unwrap().all(|page| page.is_err()));
+    }
+
+    #[test]
+    fn test_page_contents() {
+        let bytes = b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>\nendobj\n\
+            3 0 obj\n<< /Type /Page /Contents [5 0 R 6 0 R] >>\nendobj\n\
+            4 0 obj\n<< /Type /Page >>\nendobj\n\
+            5 0 obj\n<< /Length 2 >>\nstream\nBT\nendstream\nendobj\n\
+            6 0 obj\n<< /Length 2 >>\nstream\nET\nendstream\nendobj\n";
+        let xref = xref(bytes);
+        let page_tree = match Object::Ref((2, 0), xref, bytes).resolve().unwrap() {
+            Object::Dictionary(dict) => PageTree::new(dict),
+            o => panic!("{o:?}"),
+        };
+        let page = page_tree.page(0).unwrap();
+        assert_eq!(page.extract(Extract::RawContent).unwrap(), "BT\nET");
+        // a page without contents is empty
+        let page = page_tree.page(1).unwrap();
+        assert_eq!(page.extract(Extract::RawContent).unwrap(), "");
+        assert_eq!(page.extract(Extract::Text).unwrap(), "");
+    }
+
+    #[test]
+    fn test_form_xobjects() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources 2 0 R /Contents 3 0 R >>\nendobj\n\
+            2 0 obj\n<< /Font << /F1 4 0 R >> /XObject << /Fm1 5 0 R /Im1 6 0 R >> >>\nendobj\n\
+            3 0 obj\n<< /Length 41 >>\nstream\nBT /F1 12 Tf (Page) Tj ET /Im1 Do /Fm1 Do\nendstream\nendobj\n\
+            4 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>\nendobj\n\
+            5 0 obj\n<< /Subtype /Form /BBox [0 0 100 100] /Matrix [2 0 0 2 10 20] /Resources 2 0 R /Length 33 >>\nstream\nBT /F1 12 Tf (Form) Tj ET /Fm1 Do\nendstream\nendobj\n\
+            6 0 obj\n<< /Subtype /Image /Width 1 /Height 1 /Length 9 0 R >>\nstream\n\x00\nendstream\nendobj\n";
+        let page = page(bytes);
+        let resources = page.get_resources().unwrap();
+        let xobjects = &resources.xobject.as_ref().unwrap().0;
+        // the image data is not read, its Length is missing
+        assert_eq!(xobjects["Im1"], XObject::Image);
+        let form = match &xobjects["Fm1"] {
+            XObject::Form(form) => form,
+            o => panic!("{o:?}"),
+        };
+        assert_eq!(form.id, Some((5, 0)));
+        assert_eq!(form.matrix, Matrix::new(2.0, 0.0, 0.0, 2.0, 10.0, 20.0));
+        // the form shares the resources of the page being read
+        assert_eq!(form.resources, None);
+        assert_eq!(form.resources_id, Some((2, 0)));
+        // the form invoking itself is painted once
+        assert_eq!(page.extract(Extract::Text).unwrap(), "Page\nForm");
+    }
+
+    #[test]
+    fn test_outer_form_resources() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources 2 0 R /Contents 3 0 R >>\nendobj\n\
+            2 0 obj\n<< /Font << /F1 4 0 R >> /XObject << /Fm1 5 0 R /Fm2 6 0 R >> >>\nendobj\n\
+            3 0 obj\n<< /Length 7 >>\nstream\n/Fm2 Do\nendstream\nendobj\n\
+            4 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>\nendobj\n\
+            5 0 obj\n<< /Subtype /Form /BBox [0 0 100 100] /Resources 2 0 R /Length 25 >>\nstream\nBT /F1 12 Tf (Form) Tj ET\nendstream\nendobj\n\
+            6 0 obj\n<< /Subtype /Form /BBox [0 0 100 100] /Resources 7 0 R /Length 7 >>\nstream\n/Fm1 Do\nendstream\nendobj\n\
+            7 0 obj\n<< /XObject << /Fm1 5 0 R >> >>\nendobj\n";
+        let page = page(bytes);
+        // Fm1 invoked by Fm2 uses the fonts of the page rather than the resources of Fm2
+        assert_eq!(page.extract(Extract::Text).unwrap(), "Form");
+    }
+
+    #[test]
+    fn test_shared_form_xobjects() {
+        // each form invokes the next one twice, forms are read once
+        let mut bytes =
+            b"1 0 obj\n<< /Type /Page /Resources << /XObject << /Fm 10 0 R >> >> >>\nendobj\n"
+                .to_vec();
+        for id in 10..50 {
+            bytes.extend(format!("{id} 0 obj\n<< /Subtype /Form /BBox [0 0 1 1] /Resources << /XObject << /A {next} 0 R /B {next} 0 R >> >> /Length 0 >>\nstream\n\nendstream\nendobj\n", next = id + 1).as_bytes());
+        }
+        bytes.extend(b"50 0 obj\n<< /Subtype /Form /BBox [0 0 1 1] /Length 0 >>\nstream\n\nendstream\nendobj\n");
+        let page = page(&bytes);
+        let form = |xobject: &XObject| match xobject {
+            XObject::Form(form) => form.clone(),
+            o => panic!("{o:?}"),
+        };
+        let xobjects =
+            |form: &FormXObject| form.resources.as_ref().unwrap().xobject.clone().unwrap().0;
+        let mut current = form(&page.get_resources().unwrap().xobject.unwrap().0["Fm"]);
+        for id in 11..=50 {
+            let kids = xobjects(&current);
+            let (a, b) = (form(&kids["A"]), form(&kids["B"]));
+            assert!(Rc::ptr_eq(&a, &b));
+            assert_eq!(a.id, Some((id, 0)));
+            current = a;
+        }
+    }
+
+    #[test]
+    fn test_type0_font() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R >> >> /Contents 5 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type0 /BaseFont /Noto /Encoding /Identity-H /DescendantFonts [3 0 R] /ToUnicode 4 0 R >>\nendobj\n\
+            3 0 obj\n<< /Type /Font /Subtype /CIDFontType2 /BaseFont /Noto /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /DW 500 /W [3 [600 700] 10 20 250] >>\nendobj\n\
+            4 0 obj\n<< /Length 103 >>\nstream\nbegincodespacerange\n<0000> <FFFF>\nendcodespacerange\n2 beginbfchar\n<0003> <0048>\n<0004> <0069>\nendbfchar\nendstream\nendobj\n\
+            5 0 obj\n<< /Length 30 >>\nstream\nBT /F1 10 Tf <00030004> Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        let resources = page.get_resources().unwrap();
+        let font = &resources.font.as_ref().unwrap().0["F1"];
+        assert!(font.is_composite());
+        assert_eq!(font.codes(&[0, 3, 1, 2]), vec![3, 258]);
+        let cid_font = font.descendant_font.as_ref().unwrap();
+        assert_eq!(
+            cid_font.cid_system_info,
+            Some(CIDSystemInfo {
+                registry: String::from("Adobe"),
+                ordering: String::from("Identity"),
+                supplement: 0
+            })
+        );
+        // widths are given by CID lists, CID ranges or the default width
+        assert_eq!(f32::from(font.get_width(4).unwrap()), 0.7);
+        assert_eq!(f32::from(font.get_width(15).unwrap()), 0.25);
+        assert_eq!(f32::from(font.get_width(21).unwrap()), 0.5);
+        assert_eq!(page.extract(Extract::Text).unwrap(), "Hi");
+        // the second glyph is moved by the width of the first one
+        assert_eq!(
+            page.extract(Extract::Chars).unwrap(),
+            "'H', \"Type0\", \"Noto\", (1, 0, 0, 1, 0, 0)\n'i', \"Type0\", \"Noto\", (1, 0, 0, 1, 6, 0)\n"
+        );
+    }
+
+    #[test]
+    fn test_type0_encoding() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R >> >> /Contents 5 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type0 /BaseFont /Song /Encoding 4 0 R /DescendantFonts [3 0 R] >>\nendobj\n\
+            3 0 obj\n<< /Type /Font /Subtype /CIDFontType0 /BaseFont /Song /W [145 [300]] >>\nendobj\n\
+            4 0 obj\n<< /Type /CMap /Length 67 >>\nstream\n/UniGB-UCS2-H usecmap\n1 begincidrange\n<4E00> <4EFF> 100\nendcidrange\nendstream\nendobj\n\
+            5 0 obj\n<< /Length 29 >>\nstream\nBT /F1 10 Tf <4E2D0041> Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        let resources = page.get_resources().unwrap();
+        let font = &resources.font.as_ref().unwrap().0["F1"];
+        assert_eq!(font.cid(0x4E2D), Some(145));
+        assert_eq!(font.cid(0x0041), None);
+        // codes are decoded to unicode by the Unicode based CMap without ToUnicode
+        assert_eq!(page.extract(Extract::Text).unwrap(), "中A");
+        // the CID of the first glyph has a width, the second glyph has no CID and the default width
+        assert_eq!(
+            page.extract(Extract::Chars).unwrap(),
+            "'中', \"Type0\", \"Song\", (1, 0, 0, 1, 0, 0)\n'A', \"Type0\", \"Song\", (1, 0, 0, 1, 3, 0)\n"
+        );
+    }
+
+    #[test]
+    fn test_legacy_cjk_encodings() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R /F2 3 0 R >> >> /Contents 5 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type0 /BaseFont /Mincho /Encoding /90ms-RKSJ-H /DescendantFonts [4 0 R] >>\nendobj\n\
+            3 0 obj\n<< /Type /Font /Subtype /Type0 /BaseFont /Song /Encoding /GB-EUC-H /DescendantFonts [4 0 R] >>\nendobj\n\
+            4 0 obj\n<< /Type /Font /Subtype /CIDFontType0 /BaseFont /Mincho >>\nendobj\n\
+            5 0 obj\n<< /Length 54 >>\nstream\nBT /F1 10 Tf <93FA967B> Tj /F2 10 Tf <D6D0CEC4> Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        // Shift-JIS and GB 2312 codes are decoded to unicode without ToUnicode
+        assert_eq!(page.extract(Extract::Text).unwrap(), "日本中文");
+    }
+
+    #[test]
+    fn test_simple_encoding() {
+        let font = |dict: &[u8]| match Object::try_from(Tokenizer::new(dict, 0)).unwrap() {
+            Object::Dictionary(dict) => Font::try_from(dict).unwrap(),
+            o => panic!("{o:?}"),
+        };
+        let f = font(b"<< /Type /Font /Subtype /Type1 /BaseFont /CMR10 /Encoding << /Differences [11 /ff /fi 39 /quoteright 128 /Euro] >> >>");
+        assert_eq!(
+            f.encoding.as_ref().unwrap().base,
+            Some(BaseEncoding::Standard)
+        );
+        assert_eq!(f.unicode(11).as_deref(), Some("\u{fb00}"));
+        assert_eq!(f.unicode(12).as_deref(), Some("\u{fb01}"));
+        assert_eq!(f.unicode(39).as_deref(), Some("’"));
+        assert_eq!(f.unicode(65).as_deref(), Some("A"));
+        assert_eq!(f.unicode(128).as_deref(), Some("€"));
+        let f = font(
+            b"<< /Type /Font /Subtype /TrueType /BaseFont /Arial /Encoding /WinAnsiEncoding >>",
+        );
+        assert_eq!(f.unicode(0x93).as_deref(), Some("“"));
+        let f = font(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Times-Roman /Encoding /MacRomanEncoding >>",
+        );
+        assert_eq!(f.unicode(0x8e).as_deref(), Some("é"));
+        // symbolic fonts use their own built-in encoding
+        let f = font(b"<< /Type /Font /Subtype /Type1 /BaseFont /Symbol >>");
+        assert_eq!(f.encoding, None);
+        assert_eq!(f.unicode(0x61), None);
+    }
+
+    #[test]
+    fn test_ligatures() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R /F2 3 0 R >> >> /Contents 5 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding << /Differences [1 /f_f_i /uni00660066] >> >>\nendobj\n\
+            3 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /ToUnicode 4 0 R >>\nendobj\n\
+            4 0 obj\n<< /Length 85 >>\nstream\nbegincodespacerange\n<00> <FF>\nendcodespacerange\nbeginbfchar\n<01> <00660066>\nendbfchar\nendstream\nendobj\n\
+            5 0 obj\n<< /Length 56 >>\nstream\nBT /F1 10 Tf (\x01x\x02) Tj ET BT /F2 10 Tf 0 -20 Td (\x01) Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        // every char of ligature glyph names and ToUnicode destinations is extracted
+        assert_eq!(page.extract(Extract::Text).unwrap(), "ffixff\nff");
+        assert_eq!(page.extract(Extract::Layout).unwrap(), "ffixff\nff");
+        assert!(page
+            .extract(Extract::Chars)
+            .unwrap()
+            .starts_with("'f', \"Type1\", \"Helvetica\", (1, 0, 0, 1, 0, 0)\n'f', \"Type1\", \"Helvetica\", (1, 0, 0, 1, 0, 0)\n'i'"));
+    }
+
+    #[test]
+    fn test_type3_font() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R /F2 3 0 R >> >> /Contents 4 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type3 /FontBBox [0 0 100 100] /FontMatrix [0.01 0 0 0.01 0 0] /CharProcs << >> /Encoding << /Differences [65 /A /B] >> /FirstChar 65 /LastChar 66 /Widths [50 100] >>\nendobj\n\
+            3 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>\nendobj\n\
+            4 0 obj\n<< /Length 55 >>\nstream\nBT /F1 10 Tf (AB) Tj ET BT /F2 10 Tf 0 -20 Td (C) Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        let resources = page.get_resources().unwrap();
+        let font = &resources.font.as_ref().unwrap().0["F1"];
+        assert_eq!(font.base_font, None);
+        // widths are scaled by the font matrix
+        assert_eq!(f32::from(font.get_width(65).unwrap()), 0.5);
+        assert_eq!(f32::from(font.get_width(66).unwrap()), 1.0);
+        assert_eq!(page.extract(Extract::Text).unwrap(), "AB\nC");
+        assert_eq!(
+            page.extract(Extract::Chars).unwrap(),
+            "'A', \"Type3\", \"\", (1, 0, 0, 1, 0, 0)\n'B', \"Type3\", \"\", (1, 0, 0, 1, 5, 0)\n\n'C', \"Type1\", \"Helvetica\", (1, 0, 0, 1, 0, -20)\n"
+        );
+    }
+
+    #[test]
+    fn test_word_segmentation() {
+        let bytes = b"1 0 obj\n<< /Type /Page /Resources << /Font << /F1 2 0 R >> >> /Contents 3 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>\nendobj\n\
+            3 0 obj\n<< /Length 78 >>\nstream\nBT /F1 10 Tf 0 0 Td [(Hello)-250(world)-20(s)] TJ 100 0 Td (next) Tj (,) Tj ET\nendstream\nendobj\n";
+        let page = page(bytes);
+        // TJ offsets wider than a fraction of the space width separate words, kerning does not
+        // the gap before a text shown further on the same line separates words too
+        assert_eq!(page.extract(Extract::Text).unwrap(), "Hello worlds next,");
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let array = match Object::try_from(Tokenizer::new(b"[612 792 0 0.5]", 0)).unwrap() {
+            Object::Array(array) => array,
+            o => panic!("{o:?}"),
+        };
+        let rectangle = Rectangle::try_from(array).unwrap();
+        assert_eq!(
+            rectangle,
+            Rectangle {
+                llx: 0.0,
+                lly: 0.5,
+                urx: 612.0,
+                ury: 792.0
+            }
+        );
+        assert_eq!(rectangle.height(), 791.5);
+        let other = Rectangle {
+            llx: 600.0,
+            lly: -10.0,
+            urx: 700.0,
+            ury: 10.0,
+        };
+        assert_eq!(
+            rectangle.intersect(&other),
+            Rectangle {
+                llx: 600.0,
+                lly: 0.5,
+                urx: 612.0,
+                ury: 10.0
+            }
+        );
+        let rotated = rectangle.rotate(90);
+        assert_eq!((rotated.width(), rotated.height()), (791.5, 612.0));
+        assert_eq!(rectangle.rotate(-270), rotated);
+        assert_eq!(rectangle.rotate(180).rotate(180), rectangle);
+    }
+
+    #[test]
+    fn test_page_boxes() {
+        let bytes = b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 /MediaBox [0 0 595 842] /Rotate 90 >>\nendobj\n\
+            3 0 obj\n<< /Type /Page /CropBox [10 10 600 800] /TrimBox [20 20 500 700] /UserUnit 2 >>\nendobj\n\
+            4 0 obj\n<< /Type /Page /MediaBox [0 0 200 100] /Rotate -180 >>\nendobj\n";
+        let xref = xref(bytes);
+        let root = match Object::Ref((2, 0), xref, bytes).resolve().unwrap() {
+            Object::Dictionary(dict) => dict,
+            o => panic!("{o:?}"),
+        };
+        // pages read in order or at a given index inherit the same attributes
+        let page_tree = PageTree::new(root);
+        let pages = page_tree
+            .pages()
+            .unwrap()
+            .collect::<Result<Vec<Page>, Error>>()
+            .unwrap();
+        for (index, page) in pages.iter().enumerate() {
+            let lazy = page_tree.page(index).unwrap();
+            for page in [page, &lazy] {
+                match index {
+                    0 => {
+                        assert_eq!(page.get_media_box().width(), 595.0);
+                        let crop_box = page.get_crop_box();
+                        assert_eq!((crop_box.urx, crop_box.ury), (595.0, 800.0));
+                        assert_eq!(page.get_trim_box().llx, 20.0);
+                        assert_eq!(page.get_bleed_box(), crop_box);
+                        assert_eq!(page.get_rotate(), 90);
+                        assert_eq!(page.get_user_unit(), 2.0);
+                        let bbox = page.effective_bbox();
+                        assert_eq!((bbox.width(), bbox.height()), (790.0, 585.0));
+                    }
+                    _ => {
+                        assert_eq!(page.get_crop_box(), page.get_media_box());
+                        assert_eq!(page.get_rotate(), 180);
+                        assert_eq!(page.get_user_unit(), 1.0);
+                        let bbox = page.effective_bbox();
+                        assert_eq!((bbox.width(), bbox.height()), (200.0, 100.0));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_filter_array() {
+        let bytes = b"5 0 obj\n<< /Length 26 /Filter [/ASCIIHexDecode /FlateDecode] /DecodeParms [null << /Foo 1 >>] >>\nstream\n789c0b492d2e010003dd01a1>\nendstream\nendobj";
+        let stream = Stream::new(bytes, 0, Rc::new(XRef::XRefTable(XRefTable::default()))).unwrap();
+        assert_eq!(stream.get_data().unwrap(), b"Test");
+    }
 }
@@ -1,156 +1,462 @@
-use crate::tokenizer::{Token, Tokenizer};
-use std::collections::HashMap;
+use crate::{
+    algebra::Number,
+    tokenizer::{Token, Tokenizer},
+    Error,
+};
+use std::{collections::HashMap, iter::Peekable};
 
+// Codes of a given byte length, bounds are compared byte by byte (page 449)
 #[derive(Debug, PartialEq, Clone)]
-pub struct ToUnicodeCMap {
-    // page 472
-    begin_code_space_range: Vec<u8>,
-    end_code_space_range: Vec<u8>,
-    pub is_two_bytes: bool, // size of char mapping index is 2 bytes (begincodespacerange)
-    pub cmap: HashMap<usize, char>,
+struct CodespaceRange {
+    low: Vec<u8>,
+    high: Vec<u8>,
 }
 
-impl From<String> for ToUnicodeCMap {
-    fn from(value: String) -> Self {
-        let mut tokenizer = Tokenizer::new(value.as_bytes(), 0).peekable();
+impl CodespaceRange {
+    fn contains(&self, bytes: &[u8]) -> bool {
+        bytes.len() == self.low.len()
+            && bytes
+                .iter()
+                .zip(self.low.iter().zip(&self.high))
+                .all(|(b, (low, high))| low <= b && b <= high)
+    }
+}
 
-        // begincodespacerange - endcodespacerange -> size of mapping (1 or 2 bytes)
-        for t in tokenizer.by_ref() {
-            if let Token::String(s) = t {
-                if s.to_vec() == b"begincodespacerange" {
-                    break;
-                }
+// Encoding of the codes of the predefined CMaps decoded to unicode
+// The Uni CMaps use unicode encodings, the other CMaps legacy CJK encodings
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum UnicodeEncoding {
+    Ucs2,
+    Utf16,
+    Utf8,
+    Utf32,
+    ShiftJis,
+    Gbk,
+    Big5,
+    Uhc,
+}
+
+// Sorted big endian (code, unicode) pairs generated by scripts/cjk_tables.py
+static CP932: &[u8] = include_bytes!("tables/cp932.bin");
+static GBK_TABLE: &[u8] = include_bytes!("tables/gbk.bin");
+static CP950: &[u8] = include_bytes!("tables/cp950.bin");
+static CP949: &[u8] = include_bytes!("tables/cp949.bin");
+
+fn table_lookup(table: &[u8], code: usize) -> Option<char> {
+    let code = u16::try_from(code).ok()?;
+    let entry = |i: usize| u16::from_be_bytes([table[4 * i], table[4 * i + 1]]);
+    let (mut low, mut high) = (0, table.len() / 4);
+    while low < high {
+        let middle = (low + high) / 2;
+        match entry(middle).cmp(&code) {
+            std::cmp::Ordering::Less => low = middle + 1,
+            std::cmp::Ordering::Greater => high = middle,
+            std::cmp::Ordering::Equal => {
+                let unicode = u16::from_be_bytes([table[4 * middle + 2], table[4 * middle + 3]]);
+                return char::from_u32(unicode as u32);
             }
         }
-        let begin_code_space_range = match tokenizer.next() {
-            Some(Token::HexString(v)) => v,
-            Some(t) => panic!(
-                "Cmap begincodespacerange next token should be HexString, found {:?}",
-                t
-            ),
-            None => panic!("Reached end of cmap stream before complete"),
-        };
-        let end_code_space_range = match tokenizer.next() {
-            Some(Token::HexString(v)) => v,
-            Some(t) => panic!(
-                "Cmap endcodespacerange next token should be HexString, found {:?}",
-                t
-            ),
-            None => panic!("Reached end of cmap stream before complete"),
+    }
+    None
+}
+
+impl UnicodeEncoding {
+    fn decode(&self, code: usize) -> Option<char> {
+        match self {
+            UnicodeEncoding::Ucs2 | UnicodeEncoding::Utf32 => char::from_u32(code as u32),
+            // 4 bytes codes are surrogate pairs
+            UnicodeEncoding::Utf16 if code > 0xFFFF => {
+                char::decode_utf16([(code >> 16) as u16, code as u16])
+                    .next()?
+                    .ok()
+            }
+            UnicodeEncoding::Utf16 => char::from_u32(code as u32),
+            UnicodeEncoding::Utf8 => {
+                let bytes = (code as u32).to_be_bytes();
+                let start = bytes.iter().position(|b| *b != 0).unwrap_or(3);
+                std::str::from_utf8(&bytes[start..]).ok()?.chars().next()
+            }
+            UnicodeEncoding::ShiftJis => table_lookup(CP932, code),
+            UnicodeEncoding::Gbk => table_lookup(GBK_TABLE, code),
+            UnicodeEncoding::Big5 => table_lookup(CP950, code),
+            UnicodeEncoding::Uhc => table_lookup(CP949, code),
+        }
+    }
+}
+
+const ONE_BYTE: (&[u8], &[u8]) = (&[0x00], &[0x80]);
+const TWO_BYTES: &[(&[u8], &[u8])] = &[(&[0x00, 0x00], &[0xFF, 0xFF])];
+const UTF16: &[(&[u8], &[u8])] = &[
+    (&[0x00, 0x00], &[0xD7, 0xFF]),
+    (&[0xE0, 0x00], &[0xFF, 0xFF]),
+    (&[0xD8, 0x00, 0xDC, 0x00], &[0xDB, 0xFF, 0xDF, 0xFF]),
+];
+const UTF8: &[(&[u8], &[u8])] = &[
+    (&[0x00], &[0x7F]),
+    (&[0xC2, 0x80], &[0xDF, 0xBF]),
+    (&[0xE0, 0xA0, 0x80], &[0xEF, 0xBF, 0xBF]),
+    (&[0xF0, 0x90, 0x80, 0x80], &[0xF4, 0x8F, 0xBF, 0xBF]),
+];
+const UTF32: &[(&[u8], &[u8])] = &[(&[0x00, 0x00, 0x00, 0x00], &[0x00, 0x10, 0xFF, 0xFF])];
+const RKSJ: &[(&[u8], &[u8])] = &[
+    ONE_BYTE,
+    (&[0x81, 0x40], &[0x9F, 0xFC]),
+    (&[0xA0], &[0xDF]),
+    (&[0xE0, 0x40], &[0xFC, 0xFC]),
+];
+const EUC: &[(&[u8], &[u8])] = &[ONE_BYTE, (&[0xA1, 0xA1], &[0xFE, 0xFE])];
+const GBK: &[(&[u8], &[u8])] = &[ONE_BYTE, (&[0x81, 0x40], &[0xFE, 0xFE])];
+const BIG5: &[(&[u8], &[u8])] = &[ONE_BYTE, (&[0xA1, 0x40], &[0xFE, 0xFE])];
+const UHC: &[(&[u8], &[u8])] = &[ONE_BYTE, (&[0x81, 0x41], &[0xFE, 0xFE])];
+
+// Mapping of character codes to CIDs or to unicode chars (page 449)
+// Encoding CMaps of Type0 fonts use cidchar and cidrange mappings, ToUnicode CMaps use bfchar and bfrange
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CMap {
+    pub name: Option<String>,
+    pub vertical: bool, // writing mode
+    codespace_ranges: Vec<CodespaceRange>,
+    cid_ranges: Vec<(usize, usize, usize)>, // first code, last code, CID of the first code
+    pub cmap: HashMap<usize, String>,       // unicode text, several chars for ligatures
+    unicode_encoding: Option<UnicodeEncoding>,
+}
+
+pub type ToUnicodeCMap = CMap;
+
+impl CMap {
+    // Predefined CMaps of the Adobe CJK character collections (page 442)
+    // Codes are decoded to unicode by their encoding, CIDs are only known for the Identity CMaps
+    // Widths of the other CMaps fall back to the default width without a CID
+    pub fn predefined(name: &str) -> Option<CMap> {
+        let (base, vertical) = match name.rsplit_once('-') {
+            Some((base, "H")) => (base, false),
+            Some((base, "V")) => (base, true),
+            _ => return None,
         };
-        let is_two_bytes = match begin_code_space_range.len() {
-            1 => false,
-            2 => true,
-            n => panic!("Cmap index with byte length {n:?} is not supported"),
+        let (ranges, unicode_encoding) = match base {
+            "Identity" => (TWO_BYTES, None),
+            b if b.starts_with("Uni") && b.contains("-UCS2") => {
+                (TWO_BYTES, Some(UnicodeEncoding::Ucs2))
+            }
+            b if b.starts_with("Uni") && b.ends_with("-UTF16") => {
+                (UTF16, Some(UnicodeEncoding::Utf16))
+            }
+            b if b.starts_with("Uni") && b.ends_with("-UTF8") => {
+                (UTF8, Some(UnicodeEncoding::Utf8))
+            }
+            b if b.starts_with("Uni") && b.ends_with("-UTF32") => {
+                (UTF32, Some(UnicodeEncoding::Utf32))
+            }
+            b if b.ends_with("-RKSJ") => (RKSJ, Some(UnicodeEncoding::ShiftJis)),
+            "GB-EUC" | "GBpc-EUC" => (EUC, Some(UnicodeEncoding::Gbk)),
+            "KSC-EUC" | "KSCpc-EUC" => (EUC, Some(UnicodeEncoding::Uhc)),
+            "GBK-EUC" | "GBKp-EUC" => (GBK, Some(UnicodeEncoding::Gbk)),
+            // HKSCS extensions are not in the Big Five table
+            "B5pc" | "ETen-B5" | "ETenms-B5" | "HKscs-B5" => (BIG5, Some(UnicodeEncoding::Big5)),
+            "KSCms-UHC" => (UHC, Some(UnicodeEncoding::Uhc)),
+            _ => return None,
         };
+        Some(CMap {
+            name: Some(name.to_string()),
+            vertical,
+            codespace_ranges: ranges
+                .iter()
+                .map(|(low, high)| CodespaceRange {
+                    low: low.to_vec(),
+                    high: high.to_vec(),
+                })
+                .collect(),
+            cid_ranges: match base {
+                "Identity" => vec![(0, 0xFFFF, 0)],
+                _ => vec![],
+            },
+            cmap: HashMap::new(),
+            unicode_encoding,
+        })
+    }
 
-        // Read CMap
-        let mut cmap: HashMap<usize, char> = HashMap::new();
-        while let Some(t) = tokenizer.next() {
-            if let Token::String(s) = t {
-                if s.to_vec() == b"beginbfchar" {
-                    loop {
-                        // end condition
-                        if tokenizer.peek() == Some(&Token::String(b"endbfchar".to_vec())) {
-                            break;
+    // Split a string into codes, the shortest code matching a codespace range is taken (page 449)
+    // Unmatched bytes are read with the length of the ranges matching their first byte, or one by one
+    pub fn codes(&self, bytes: &[u8]) -> Vec<usize> {
+        let mut codes = vec![];
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let length = (1..=rest.len().min(4))
+                .find(|n| {
+                    self.codespace_ranges
+                        .iter()
+                        .any(|r| r.contains(&rest[..*n]))
+                })
+                .or_else(|| {
+                    self.codespace_ranges
+                        .iter()
+                        .filter(|r| r.low[0] <= rest[0] && rest[0] <= r.high[0])
+                        .map(|r| r.low.len())
+                        .min()
+                })
+                .unwrap_or(1)
+                .min(rest.len());
+            codes.push(
+                rest[..length]
+                    .iter()
+                    .fold(0, |code, b| code * 256 + *b as usize),
+            );
+            rest = &rest[length..];
+        }
+        codes
+    }
+
+    pub fn cid(&self, code: usize) -> Option<usize> {
+        self.cid_ranges
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&code))
+            .map(|(first, _, cid)| cid + code - first)
+    }
+
+    pub fn unicode(&self, code: usize) -> Option<String> {
+        match self.cmap.get(&code) {
+            Some(text) => Some(text.clone()),
+            None => self
+                .unicode_encoding
+                .and_then(|e| e.decode(code))
+                .map(String::from),
+        }
+    }
+
+    // Include the mappings of another CMap, the mappings of this CMap take precedence
+    pub fn use_cmap(&mut self, parent: CMap) {
+        self.codespace_ranges.extend(parent.codespace_ranges);
+        self.cid_ranges.extend(parent.cid_ranges);
+        for (code, text) in parent.cmap {
+            self.cmap.entry(code).or_insert(text);
+        }
+        self.unicode_encoding = self.unicode_encoding.or(parent.unicode_encoding);
+    }
+
+    // CMap source code (1 to 4 bytes, high byte first)
+    fn code(token: Option<Token>, label: &str) -> Result<usize, Error> {
+        match token {
+            Some(Token::HexString(x)) => match x.len() {
+                1..=4 => Ok(x.iter().fold(0, |code, b| code * 256 + *b as usize)),
+                n => Err(Error::font(format!(
+                    "{label} should contain one to four bytes, found {n}"
+                ))),
+            },
+            Some(t) => Err(Error::font(format!(
+                "{label} should be an hex string, found {t:?}"
+            ))),
+            None => Err(Error::font(
+                "CMap unreadable because end of cmap file is reached",
+            )),
+        }
+    }
+
+    // CMap destination CID
+    fn cid_value(token: Option<Token>, label: &str) -> Result<usize, Error> {
+        match token {
+            Some(Token::Numeric(Number::Integer(n))) if n >= 0 => Ok(n as usize),
+            Some(t) => Err(Error::font(format!(
+                "{label} should be a positive integer, found {t:?}"
+            ))),
+            None => Err(Error::font(
+                "CMap unreadable because end of cmap file is reached",
+            )),
+        }
+    }
+
+    // CMap destination, an UTF-16BE string of one or more chars
+    fn destination(x: &[u8]) -> Result<String, Error> {
+        let units = x
+            .chunks(2)
+            .map(|c| c.iter().fold(0, |unit, b| unit * 256 + *b as u16));
+        let text = char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .map_err(|e| {
+                Error::font(format!(
+                    "CMap destination {:#06x} is not a valid unicode char",
+                    e.unpaired_surrogate()
+                ))
+            })?;
+        match text.is_empty() {
+            true => Err(Error::font("CMap destination string is empty")),
+            false => Ok(text),
+        }
+    }
+
+    // Reached the end of a mapping section, the end operator is left to the caller
+    fn section_end(tokenizer: &mut Peekable<Tokenizer>, end: &[u8]) -> bool {
+        match tokenizer.peek() {
+            Some(Token::String(s)) => s == end,
+            None => true,
+            _ => false,
+        }
+    }
+
+    fn codespace_range(&mut self, tokenizer: &mut Peekable<Tokenizer>) -> Result<(), Error> {
+        while !CMap::section_end(tokenizer, b"endcodespacerange") {
+            match (tokenizer.next(), tokenizer.next()) {
+                (Some(Token::HexString(low)), Some(Token::HexString(high)))
+                    if low.len() == high.len() && (1..=4).contains(&low.len()) =>
+                {
+                    self.codespace_ranges.push(CodespaceRange { low, high })
+                }
+                (low, high) => {
+                    return Err(Error::font(format!(
+                        "Codespace range should be two hex strings of 1 to 4 bytes, found {low:?} {high:?}"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn bfchar(&mut self, tokenizer: &mut Peekable<Tokenizer>) -> Result<(), Error> {
+        while !CMap::section_end(tokenizer, b"endbfchar") {
+            // BFChar mapping key (1 to 4 bytes depending on cmap codespacerange)
+            let key = CMap::code(tokenizer.next(), "BFChar key")?;
+
+            // BFChar mapping value is a unicode character
+            let val = match tokenizer.next() {
+                Some(Token::HexString(x)) => CMap::destination(&x)?,
+                Some(t) => {
+                    return Err(Error::font(format!(
+                        "CMap val should be an hex string, found {t:?}"
+                    )))
+                }
+                None => {
+                    return Err(Error::font(
+                        "CMap unreadable because end of cmap file is reached",
+                    ))
+                }
+            };
+            self.cmap.insert(key, val);
+        }
+        Ok(())
+    }
+
+    fn bfrange(&mut self, tokenizer: &mut Peekable<Tokenizer>) -> Result<(), Error> {
+        while !CMap::section_end(tokenizer, b"endbfrange") {
+            let src_code_1 = CMap::code(tokenizer.next(), "BFRange first source code")?;
+            let src_code_2 = CMap::code(tokenizer.next(), "BFRange second source code")?;
+            // codes of a range differ in their last byte only, ranges crossing it are tolerated
+            if src_code_2 < src_code_1 || src_code_2 - src_code_1 > 255 {
+                return Err(Error::font(format!(
+                    "BFRange should cover 1 to 256 codes, found {src_code_1:#x} to {src_code_2:#x}"
+                )));
+            }
+            // BFRange destination strings
+            match tokenizer.next() {
+                // Destination of the first code, the last char is incremented for the next codes
+                Some(Token::HexString(x)) => {
+                    let mut first = CMap::destination(&x)?;
+                    let last = first.pop().map_or(0, u32::from);
+                    for (offset, idx) in (src_code_1..src_code_2 + 1).enumerate() {
+                        if let Some(c) = char::from_u32(last + offset as u32) {
+                            self.cmap.insert(idx, format!("{first}{c}"));
                         }
-                        // BFChar mapping key (1 or 2 bytes depending on cmap codespacerange)
-                        let key = match tokenizer.next() {
-                            Some(Token::HexString(x)) => match x.len() {
-                                2 => x[0] as usize * 256 + x[1] as usize,
-                                1 => x[0] as usize,
-                                n => {
-                                    panic!("BFChar key should contain one or two bytes, found {n}")
-                                }
-                            },
-                            Some(t) => panic!("CMap key should be an hex string, found {t:?}"),
-                            None => panic!("CMap unreadable because end of cmap file is reached"),
-                        };
-
-                        // BFChar mapping value is a unicode character
-                        let val = match tokenizer.next() {
-                            Some(Token::HexString(x)) => {
-                                let code = x[0] as u16 * 256 + x[1] as u16;
-                                char::decode_utf16([code]).next().unwrap().ok().unwrap()
-                            }
-                            Some(t) => panic!("CMap val should be an hex string, found {t:?}"),
-                            None => panic!("CMap unreadable because end of cmap file is reached"),
-                        };
-                        cmap.insert(key, val);
                     }
                 }
-                if s.to_vec() == b"beginbfrange" {
+                // List of unicode chars
+                Some(Token::ArrayBegin) => {
+                    let mut idx = 0;
                     loop {
-                        // end condition
-                        if tokenizer.peek() == Some(&Token::String(b"endbfrange".to_vec())) {
-                            break;
-                        }
-                        let src_code_1 = match tokenizer.next() {
-                            Some(Token::HexString(x)) => match x.len() {
-                                2 => x[0] as usize * 256 + x[1] as usize,
-                                1 => x[0] as usize,
-                                n => panic!("BFRange first source code should contain one or two bytes, found {n}")
-                            },
-                            Some(t) => panic!("CMap srcCode1 should be an hex string, found {t:?}"),
-                            None => panic!("CMap unreadable because end of cmap file is reached"),
-                        };
-                        let src_code_2 = match tokenizer.next() {
-                            Some(Token::HexString(x)) => match x.len() {
-                                2 => x[0] as usize * 256 + x[1] as usize,
-                                1 => x[0] as usize,
-                                n => panic!("BFRange second source code should contain one or two bytes, found {n}")
-                            },
-                            Some(t) => panic!("CMap srcCode2 should be an hex string, found {t:?}"),
-                            None => panic!("CMap unreadable because end of cmap file is reached"),
-                        };
-                        // BFRange destination strings
                         match tokenizer.next() {
-                            // First unicode char
+                            Some(Token::ArrayEnd) => break,
                             Some(Token::HexString(x)) => {
-                                let mut dst_string = x[0] as u16 * 256 + x[1] as u16;
-                                for idx in src_code_1..src_code_2 + 1 {
-                                    cmap.insert(
-                                        idx,
-                                        char::decode_utf16([dst_string])
-                                            .next()
-                                            .unwrap()
-                                            .ok()
-                                            .unwrap(),
-                                    );
-                                    dst_string += 1;
-                                }
+                                self.cmap.insert(src_code_1 + idx, CMap::destination(&x)?);
+                                idx += 1;
+                            }
+                            Some(t) => {
+                                return Err(Error::font(format!(
+                                    "CMap range should only contain hex strings, found {t:?}"
+                                )))
                             }
-                            // List of unicode chars
-                            Some(Token::ArrayBegin) => {
-                                let mut idx = 0;
-                                loop {
-                                    match tokenizer.next() {
-                                    Some(Token::ArrayEnd) => break,
-                                    Some(Token::HexString(x)) => {
-                                        let code = x[0] as u16 * 256 + x[1] as u16;
-                                        cmap.insert(src_code_1+idx, char::decode_utf16([code]).next().unwrap().ok().unwrap());
-                                        idx += 1;
-                                    },
-                                    Some(t) => panic!("CMap range should only contain hex strings, found {t:?}"),
-                                    None => panic!("CMap unreadable because end of cmap file is reached")
-                                }
-                                }
+                            None => {
+                                return Err(Error::font(
+                                    "CMap unreadable because end of cmap file is reached",
+                                ))
                             }
-                            Some(t) => panic!(
-                                "CMap dst_string should be an hex string or an array, found {t:?}"
-                            ),
-                            None => panic!("CMap unreadable because end of cmap file is reached"),
-                        };
+                        }
                     }
                 }
+                Some(t) => {
+                    return Err(Error::font(format!(
+                        "CMap dst_string should be an hex string or an array, found {t:?}"
+                    )))
+                }
+                None => {
+                    return Err(Error::font(
+                        "CMap unreadable because end of cmap file is reached",
+                    ))
+                }
+            };
+        }
+        Ok(())
+    }
+
+    fn cidchar(&mut self, tokenizer: &mut Peekable<Tokenizer>) -> Result<(), Error> {
+        while !CMap::section_end(tokenizer, b"endcidchar") {
+            let code = CMap::code(tokenizer.next(), "CIDChar code")?;
+            let cid = CMap::cid_value(tokenizer.next(), "CIDChar CID")?;
+            self.cid_ranges.push((code, code, cid));
+        }
+        Ok(())
+    }
+
+    fn cidrange(&mut self, tokenizer: &mut Peekable<Tokenizer>) -> Result<(), Error> {
+        while !CMap::section_end(tokenizer, b"endcidrange") {
+            let first = CMap::code(tokenizer.next(), "CIDRange first code")?;
+            let last = CMap::code(tokenizer.next(), "CIDRange last code")?;
+            let cid = CMap::cid_value(tokenizer.next(), "CIDRange CID")?;
+            self.cid_ranges.push((first, last, cid));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for CMap {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut tokenizer = Tokenizer::new(value.as_bytes(), 0).peekable();
+        let mut cmap = CMap::default();
+        let mut parent = None;
+        // operands of the def and usecmap operators
+        let mut operands = vec![];
+        while let Some(t) = tokenizer.next() {
+            let Token::String(operator) = t else {
+                operands.push(t);
+                continue;
+            };
+            match operator.as_slice() {
+                b"begincodespacerange" => cmap.codespace_range(&mut tokenizer)?,
+                b"beginbfchar" => cmap.bfchar(&mut tokenizer)?,
+                b"beginbfrange" => cmap.bfrange(&mut tokenizer)?,
+                b"begincidchar" => cmap.cidchar(&mut tokenizer)?,
+                b"begincidrange" => cmap.cidrange(&mut tokenizer)?,
+                // only predefined CMaps can be used by name
+                b"usecmap" => {
+                    if let Some(Token::Name(name)) = operands.last() {
+                        parent = CMap::predefined(name);
+                    }
+                }
+                b"def" => match operands.as_slice() {
+                    [.., Token::Name(key), Token::Name(name)] if key == "CMapName" => {
+                        cmap.name = Some(name.clone())
+                    }
+                    [.., Token::Name(key), Token::Numeric(mode)] if key == "WMode" => {
+                        cmap.vertical = f32::from(mode.clone()) == 1.0
+                    }
+                    _ => (),
+                },
+                _ => (),
             }
+            operands.clear();
         }
-        ToUnicodeCMap {
-            begin_code_space_range,
-            end_code_space_range,
-            is_two_bytes,
-            cmap,
+        if let Some(parent) = parent {
+            cmap.use_cmap(parent);
         }
+        Ok(cmap)
     }
 }
 
@@ -162,34 +468,121 @@ mod tests {
     #[test]
     fn test_tounicode_cmap_0() {
         let cmap_string: String = String::from("/CIDInit/ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo<<\n/Registry (Adobe)\n/Ordering (UCS)\n/Supplement 0\n>> def\n/CMapName/Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<00> <FF>\nendcodespacerange\n27 beginbfchar\n<01> <004C>\n<02> <006F>\n<03> <0072>\n<04> <0065>\n<05> <006D>\n<06> <0020>\n<07> <0069>\n<08> <0070>\n<09> <0073>\n<0A> <0075>\n<0B> <0064>\n<0C> <006C>\n<0D> <0074>\n<0E> <0061>\n<0F> <002C>\n<10> <0063>\n<11> <006E>\n<12> <0067>\n<13> <0079>\n<14> <0076>\n<15> <0062>\n<16> <0071>\n<17> <002E>\n<18> <0041>\n<19> <006A>\n<1A> <0053>\n<1B> <006B>\nendbfchar\nendcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\nCMapNam");
-        let tounicode: ToUnicodeCMap = ToUnicodeCMap::from(cmap_string);
-        assert_eq!(tounicode.cmap.get(&1), Some(&'L'));
-        assert_eq!(tounicode.cmap.get(&2), Some(&'o'));
-        assert_eq!(tounicode.cmap.get(&3), Some(&'r'));
-        assert_eq!(tounicode.cmap.get(&4), Some(&'e'));
-        assert_eq!(tounicode.cmap.get(&5), Some(&'m'));
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(1).as_deref(), Some("L"));
+        assert_eq!(tounicode.unicode(2).as_deref(), Some("o"));
+        assert_eq!(tounicode.unicode(3).as_deref(), Some("r"));
+        assert_eq!(tounicode.unicode(4).as_deref(), Some("e"));
+        assert_eq!(tounicode.unicode(5).as_deref(), Some("m"));
     }
 
     #[test]
     fn test_tounicode_cmap_1() {
         let cmap_string: String = String::from("/CIDInit /ProcSet findresource begin\n22 dict begin\nbegincmap\n/CIDSystemInfo\n<< /Registry (Adobe)\n/Ordering (UCS)\n/Supplement 0\n>> def\n/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n10 beginbfchar\n<0001> <0041>\n<001A> <0042>\n<001C> <0043>\n<0022> <0044>\n<0028> <0045>\n<003E> <0046>\n<0040> <0047>\n<0046> <0048>\n<0049> <0049>\n<005B> <004C>\nendbfchar\n1 beginbfrange\n<0061> <0062> <004D>\nendbfrange\n14 beginbfchar\n<0069> <004F>\n<0084> <0050>\n<0087> <0052>\n<008B> <0053>\n<0093> <0054>\n<0098> <0055>\n<00AB> <0056>\n<00AE> <0057>\n<00B4> <0059>\n<00CD> <0061>\n<00E6> <0062>\n<00E8> <0063>\n<00EE> <0064>\n<00F4> <0065>\nendbfchar\n1 beginbfrange\n<010B> <010C> <0066>\nendbfrange\n5 beginbfchar\n<0113> <0068>\n<0116> <0069>\n<0124> <006A>\n<0127> <006B>\n<012B> <006C>\nendbfchar\n1 beginbfrange\n<0131> <0132> <006D>\nendbfrange\n8 beginbfchar\n<013A> <006F>\n<0155> <0070>\n<0158> <0072>\n<015C> <0073>\n<0165> <0074>\n<016A> <0075>\n<017D> <0076>\n<017F> <0077>\nendbfchar\n1 beginbfrange\n<0184> <0185> <0078>\nendbfrange\n3 beginbfchar\n<018F> <007A>\n<01AF> <00660066>\n<01B1> <00660069>\nendbfchar\n1 beginbfrange\n<034F> <0358> <0030>\nendbfrange\n3 beginbfchar\n<03D9> <0020>\n<03DF> <002E>\n<03E2> <003B>\nendbfchar\n1 beginbfrange\n<03FC> <03FD> <0028>\nendbfrange\n1 beginbfchar\n<042D> <0026>\nendbfchar\nendcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\nCMapNam");
-        let tounicode: ToUnicodeCMap = ToUnicodeCMap::from(cmap_string);
-        assert_eq!(tounicode.cmap.get(&1), Some(&'A'));
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(1).as_deref(), Some("A"));
+        // ligatures map to several chars
+        assert_eq!(tounicode.unicode(0x01AF).as_deref(), Some("ff"));
+        assert_eq!(tounicode.unicode(0x01B1).as_deref(), Some("fi"));
     }
 
     #[test]
     fn test_one_bfchar() {
         let cmap_string: String = String::from("begincodespacerange\n<0000> <FFFF>\nendcodespacerange\nbeginbfchar\n<03D9> <0020>\nendbfchar");
-        let tounicode: ToUnicodeCMap = ToUnicodeCMap::from(cmap_string);
-        assert_eq!(tounicode.cmap.get(&985), Some(&' '));
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(985).as_deref(), Some(" "));
     }
 
     #[test]
     fn test_multiple_bfrange() {
         let cmap_string: String = String::from("begincodespacerange\n<0000> <FFFF>\nendcodespacerange\nbeginbfrange\n<03DF> <03E0> [<002E> <002C>]\n<03E1> <03E2> <003A>\nendbfrange");
-        let tounicode: ToUnicodeCMap = ToUnicodeCMap::from(cmap_string);
-        assert_eq!(tounicode.cmap.get(&991), Some(&'.'));
-        assert_eq!(tounicode.cmap.get(&992), Some(&','));
-        assert_eq!(tounicode.cmap.get(&993), Some(&':'));
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(991).as_deref(), Some("."));
+        assert_eq!(tounicode.unicode(992).as_deref(), Some(","));
+        assert_eq!(tounicode.unicode(993).as_deref(), Some(":"));
+        // the last char of a destination string is incremented through the range
+        let cmap_string = String::from(
+            "beginbfrange\n<01> <03> <00660066>\n<04> <04> [<00540068> <0041>]\nendbfrange",
+        );
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(1).as_deref(), Some("ff"));
+        assert_eq!(tounicode.unicode(3).as_deref(), Some("fh"));
+        assert_eq!(tounicode.unicode(4).as_deref(), Some("Th"));
+        assert!(
+            ToUnicodeCMap::try_from(String::from("beginbfchar\n<01> <0041D800>\nendbfchar"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_four_bytes_codespace() {
+        let cmap_string: String = String::from("begincodespacerange\n<00000000> <FFFFFFFF>\nendcodespacerange\nbeginbfchar\n<00010203> <0041>\nendbfchar");
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.codes(&[0, 1, 2, 3]), vec![0x10203]);
+        assert_eq!(tounicode.unicode(0x10203).as_deref(), Some("A"));
+        // ranges are limited to 256 codes and ordered
+        for range in ["<00000000> <FFFFFFFF> <0041>", "<0002> <0001> <0041>"] {
+            let cmap_string = format!("beginbfrange\n{range}\nendbfrange");
+            assert!(ToUnicodeCMap::try_from(cmap_string).is_err());
+        }
+        let cmap_string = String::from("beginbfrange\n<00F0> <01EF> <0041>\nendbfrange");
+        let tounicode: ToUnicodeCMap = ToUnicodeCMap::try_from(cmap_string).unwrap();
+        assert_eq!(tounicode.unicode(0x1EF).as_deref(), Some("\u{140}"));
+    }
+
+    #[test]
+    fn test_variable_length_codes() {
+        let cmap = CMap::predefined("90ms-RKSJ-H").unwrap();
+        assert_eq!(cmap.codes(b"a\x82\xa0\xb1"), vec![0x61, 0x82a0, 0xb1]);
+        // trailing byte of a truncated two bytes code
+        assert_eq!(cmap.codes(b"\x82"), vec![0x82]);
+        let text: String = cmap
+            .codes(b"a\x82\xa0\xb1\x93\xfa\x96\x7b")
+            .into_iter()
+            .filter_map(|code| cmap.unicode(code))
+            .collect();
+        assert_eq!(text, "aあｱ日本");
+        assert_eq!(cmap.unicode(0x82), None);
+        let cmap = CMap::predefined("GB-EUC-H").unwrap();
+        assert_eq!(cmap.unicode(0xd6d0).as_deref(), Some("中"));
+        let cmap = CMap::predefined("KSC-EUC-H").unwrap();
+        assert_eq!(cmap.unicode(0xc7d1).as_deref(), Some("한"));
+        let cmap = CMap::predefined("B5pc-H").unwrap();
+        assert_eq!(cmap.unicode(0xa4a4).as_deref(), Some("中"));
+        let cmap = CMap::predefined("KSCms-UHC-V").unwrap();
+        assert_eq!(cmap.unicode(0x8141).as_deref(), Some("갂"));
+        assert_eq!(cmap.cid(0x8141), None);
+        let cmap = CMap::predefined("UniGB-UTF16-V").unwrap();
+        assert!(cmap.vertical);
+        let codes = cmap.codes(b"\x4e\x2d\xd8\x3d\xde\x00");
+        assert_eq!(codes, vec![0x4e2d, 0xd83dde00]);
+        assert_eq!(cmap.unicode(codes[0]).as_deref(), Some("中"));
+        assert_eq!(cmap.unicode(codes[1]).as_deref(), Some("😀"));
+        assert_eq!(cmap.cid(codes[0]), None);
+        let cmap = CMap::predefined("UniJIS-UTF8-H").unwrap();
+        let codes = cmap.codes("a日".as_bytes());
+        assert_eq!(cmap.unicode(codes[0]).as_deref(), Some("a"));
+        assert_eq!(cmap.unicode(codes[1]).as_deref(), Some("日"));
+        assert_eq!(
+            CMap::predefined("Identity-H").unwrap().cid(0x1234),
+            Some(0x1234)
+        );
+        assert_eq!(CMap::predefined("Unknown-H"), None);
+    }
+
+    #[test]
+    fn test_cid_cmap() {
+        let cmap_string: String = String::from("/CMapName /Custom-V def\n/WMode 1 def\n/UniJIS-UCS2-H usecmap\n1 begincodespacerange\n<40> <7F>\nendcodespacerange\n1 begincidchar\n<41> 10\nendcidchar\n1 begincidrange\n<0100> <01FF> 500\nendcidrange\n1 beginbfchar\n<30A2> <0042>\nendbfchar");
+        let cmap = CMap::try_from(cmap_string).unwrap();
+        assert_eq!(cmap.name.as_deref(), Some("Custom-V"));
+        assert!(cmap.vertical);
+        // one byte codes of the CMap and two bytes codes of the used CMap
+        assert_eq!(cmap.codes(b"A\x01\x10"), vec![0x41, 0x0110]);
+        assert_eq!(cmap.cid(0x41), Some(10));
+        assert_eq!(cmap.cid(0x0110), Some(516));
+        assert_eq!(cmap.cid(0x0200), None);
+        // mappings of the CMap take precedence over the used CMap
+        assert_eq!(cmap.unicode(0x30A2).as_deref(), Some("B"));
+        assert_eq!(cmap.unicode(0x30A3).as_deref(), Some("ィ"));
     }
 }
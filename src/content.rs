@@ -1,10 +1,13 @@
 use core::iter::Iterator;
+use std::rc::Rc;
 
 use crate::{
     algebra::{Matrix, Number},
-    body::Resources,
-    object::Name,
+    body::{Font, FormXObject, Resources, XObject},
+    layout::Glyph,
+    object::{IndirectObject, Name},
     tokenizer::{Token, Tokenizer},
+    Error,
 };
 
 #[derive(Default)]
@@ -13,11 +16,484 @@ struct TextObject {
     tlm: Matrix, // text line matrix
 }
 
+// Operand of a content stream operator (page 151)
+#[derive(Debug, PartialEq, Clone)]
+pub enum Operand {
+    Number(Number),
+    Boolean(bool),
+    Null,
+    Name(Name),
+    String(Vec<u8>), // litteral string
+    HexString(Vec<u8>),
+    Array(Vec<Operand>),
+    Dictionary(Vec<(Name, Operand)>), // inline dictionary, entries in stream order
+}
+
+impl Operand {
+    // Append the operand in content stream syntax
+    fn write(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Operand::Number(Number::Integer(i)) => bytes.extend(i.to_string().as_bytes()),
+            // Display of f32 never uses the exponent notation, which is not allowed in PDF
+            Operand::Number(Number::Real(f)) => bytes.extend(f.to_string().as_bytes()),
+            Operand::Boolean(b) => bytes.extend(b.to_string().as_bytes()),
+            Operand::Null => bytes.extend(b"null"),
+            Operand::Name(name) => write_name(name, bytes),
+            Operand::String(s) => {
+                bytes.push(b'(');
+                for c in s {
+                    match c {
+                        b'(' | b')' | b'\\' => bytes.extend([b'\\', *c]),
+                        b'\r' => bytes.extend(b"\\r"),
+                        c => bytes.push(*c),
+                    }
+                }
+                bytes.push(b')');
+            }
+            Operand::HexString(s) => {
+                bytes.push(b'<');
+                for c in s {
+                    bytes.extend(format!("{c:02X}").as_bytes());
+                }
+                bytes.push(b'>');
+            }
+            Operand::Array(array) => {
+                bytes.push(b'[');
+                for (i, operand) in array.iter().enumerate() {
+                    if i > 0 {
+                        bytes.push(b' ');
+                    }
+                    operand.write(bytes);
+                }
+                bytes.push(b']');
+            }
+            Operand::Dictionary(entries) => {
+                bytes.extend(b"<<");
+                for (key, value) in entries {
+                    write_name(key, bytes);
+                    bytes.push(b' ');
+                    value.write(bytes);
+                }
+                bytes.extend(b">>");
+            }
+        }
+    }
+}
+
+// Names are written with a leading slash, delimiters and whitespaces are escaped as #xx (page 17)
+// # is kept as is since the tokenizer does not decode escape sequences of names
+fn write_name(name: &str, bytes: &mut Vec<u8>) {
+    bytes.push(b'/');
+    for c in name.bytes() {
+        match c {
+            b'!'..=b'~' if !b"()<>[]{}/%".contains(&c) => bytes.push(c),
+            c => bytes.extend(format!("#{c:02X}").as_bytes()),
+        }
+    }
+}
+
+// Operator with its operands, offset is the position of the first operand or of the operator
+#[derive(Debug, PartialEq, Clone)]
+pub struct Operation {
+    pub operator: String,
+    pub operands: Vec<Operand>,
+    pub offset: usize,
+}
+
+impl Operation {
+    // Operation to be serialized, its offset is unknown until written
+    pub fn new(operator: &str, operands: Vec<Operand>) -> Self {
+        Operation {
+            operator: operator.to_string(),
+            operands,
+            offset: 0,
+        }
+    }
+}
+
+// Content stream parsed into operators with their operands (page 151)
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ContentStream {
+    pub operations: Vec<Operation>,
+}
+
+impl ContentStream {
+    // Content stream bytes, one operation per line
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        for operation in &self.operations {
+            // inline images are written as BI parameters ID data EI
+            if let [Operand::Dictionary(parameters), Operand::String(data)] =
+                operation.operands.as_slice()
+            {
+                if operation.operator == "BI" {
+                    bytes.extend(b"BI");
+                    for (key, value) in parameters {
+                        bytes.push(b' ');
+                        write_name(key, &mut bytes);
+                        bytes.push(b' ');
+                        value.write(&mut bytes);
+                    }
+                    bytes.extend(b" ID ");
+                    bytes.extend(data);
+                    bytes.extend(b"\nEI\n");
+                    continue;
+                }
+            }
+            for operand in &operation.operands {
+                operand.write(&mut bytes);
+                bytes.push(b' ');
+            }
+            bytes.extend(operation.operator.as_bytes());
+            bytes.push(b'\n');
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for ContentStream {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Ok(ContentStream {
+            operations: Operations::from(Tokenizer::new(value, 0))
+                .collect::<Result<Vec<Operation>, Error>>()?,
+        })
+    }
+}
+
+const WHITESPACES: &[u8] = b"\0\t\n\x0c\r ";
+
+// Image given inline in a content stream by the BI, ID and EI operators (page 352)
+#[derive(Debug, PartialEq, Clone)]
+pub struct InlineImage {
+    pub parameters: Vec<(Name, Operand)>, // abbreviated keys and names are expanded
+    pub data: Vec<u8>,                    // image data, still encoded by its filters
+}
+
+impl InlineImage {
+    pub fn new(parameters: &[(Name, Operand)], data: Vec<u8>) -> Self {
+        InlineImage {
+            parameters: parameters
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key.as_str() {
+                        "BPC" => "BitsPerComponent",
+                        "CS" => "ColorSpace",
+                        "D" => "Decode",
+                        "DP" => "DecodeParms",
+                        "F" => "Filter",
+                        "H" => "Height",
+                        "IM" => "ImageMask",
+                        "I" => "Interpolate",
+                        "L" => "Length",
+                        "W" => "Width",
+                        key => key,
+                    };
+                    let value = match key {
+                        "ColorSpace" | "Filter" => InlineImage::expand(value),
+                        _ => value.clone(),
+                    };
+                    (key.to_string(), value)
+                })
+                .collect(),
+            data,
+        }
+    }
+
+    // Full names of the abbreviated color spaces and filters, also in arrays
+    fn expand(value: &Operand) -> Operand {
+        match value {
+            Operand::Name(name) => Operand::Name(String::from(match name.as_str() {
+                "G" => "DeviceGray",
+                "RGB" => "DeviceRGB",
+                "CMYK" => "DeviceCMYK",
+                "I" => "Indexed",
+                "AHx" => "ASCIIHexDecode",
+                "A85" => "ASCII85Decode",
+                "LZW" => "LZWDecode",
+                "Fl" => "FlateDecode",
+                "RL" => "RunLengthDecode",
+                "CCF" => "CCITTFaxDecode",
+                "DCT" => "DCTDecode",
+                name => name,
+            })),
+            Operand::Array(array) => {
+                Operand::Array(array.iter().map(InlineImage::expand).collect())
+            }
+            value => value.clone(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Operand> {
+        self.parameters
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    fn integer(&self, key: &str) -> Option<usize> {
+        match self.get(key) {
+            Some(Operand::Number(n)) => usize::try_from(i32::from(n.clone())).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn width(&self) -> Option<usize> {
+        self.integer("Width")
+    }
+
+    pub fn height(&self) -> Option<usize> {
+        self.integer("Height")
+    }
+
+    pub fn bits_per_component(&self) -> Option<usize> {
+        match self.get("ImageMask") {
+            Some(Operand::Boolean(true)) => Some(1),
+            _ => self.integer("BitsPerComponent"),
+        }
+    }
+
+    // Filters applied to the data, in decoding order
+    pub fn filters(&self) -> Vec<Name> {
+        match self.get("Filter") {
+            Some(Operand::Name(name)) => vec![name.clone()],
+            Some(Operand::Array(array)) => array
+                .iter()
+                .filter_map(|o| match o {
+                    Operand::Name(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    // Length of the data, given by the Length key or computed for unfiltered images in a device color space
+    fn data_length(&self) -> Option<usize> {
+        if let Some(length) = self.integer("Length") {
+            return Some(length);
+        }
+        if !self.filters().is_empty() {
+            return None;
+        }
+        let components = match (self.get("ImageMask"), self.get("ColorSpace")) {
+            (Some(Operand::Boolean(true)), _) => 1,
+            (_, Some(Operand::Name(name))) => match name.as_str() {
+                "DeviceGray" => 1,
+                "DeviceRGB" => 3,
+                "DeviceCMYK" => 4,
+                _ => return None,
+            },
+            (_, Some(Operand::Array(array))) => match array.first() {
+                Some(Operand::Name(name)) if name == "Indexed" => 1,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        // rows start on byte boundaries, the EI operator is searched when the length overflows
+        let row = self
+            .width()?
+            .checked_mul(components)?
+            .checked_mul(self.bits_per_component()?)?
+            .div_ceil(8);
+        row.checked_mul(self.height()?)
+    }
+}
+
+// End of the inline image data starting at start, and offset after its EI operator
+// The data length is trusted when EI follows it, otherwise EI is searched as a token
+// followed by content stream operations, as EI may appear in binary data
+fn inline_image_end(bytes: &[u8], start: usize, length: Option<usize>) -> Option<(usize, usize)> {
+    let is_ei = |i: usize| {
+        bytes[i..].starts_with(b"EI")
+            && bytes
+                .get(i + 2)
+                .is_none_or(|c| WHITESPACES.contains(c) || b"/[<(%".contains(c))
+    };
+    if let Some(end) = length.and_then(|length| start.checked_add(length)) {
+        if let Some(i) = bytes
+            .get(end..)
+            .and_then(|tail| tail.iter().position(|c| !WHITESPACES.contains(c)))
+        {
+            if is_ei(end + i) {
+                return Some((end, end + i + 2));
+            }
+        }
+    }
+    (start..bytes.len().saturating_sub(1))
+        .find(|&i| {
+            (i == start || WHITESPACES.contains(&bytes[i - 1]))
+                && is_ei(i)
+                && Operations::from(Tokenizer::new(bytes, i + 2))
+                    .take(3)
+                    .all(|o| {
+                        o.is_ok_and(|o| {
+                            o.operator.len() <= 3
+                                && o.operator.bytes().all(|c| c.is_ascii_graphic())
+                        })
+                    })
+        })
+        // the whitespace before EI is not part of the data
+        .map(|i| (i.saturating_sub(1).max(start), i + 2))
+}
+
+// Operations read one at a time from the tokens of a content stream
+struct Operations<'a> {
+    tokenizer: Tokenizer<'a>,
+}
+
+impl<'a> From<Tokenizer<'a>> for Operations<'a> {
+    fn from(tokenizer: Tokenizer<'a>) -> Self {
+        Operations { tokenizer }
+    }
+}
+
+impl<'a> Operations<'a> {
+    fn error(&self, message: String) -> Error {
+        Error::ContentStream {
+            offset: self.tokenizer.offset(),
+            message,
+        }
+    }
+
+    // Offset of the first token read from the given offset, whitespaces are skipped
+    fn token_offset(&self, offset: usize) -> usize {
+        offset
+            + self.tokenizer.bytes[offset..]
+                .iter()
+                .position(|c| !WHITESPACES.contains(c))
+                .unwrap_or(0)
+    }
+
+    // Operand starting with the given token, arrays and dictionaries are read up to their end
+    fn operand(&mut self, token: Token<'a>) -> Result<Operand, Error> {
+        match token {
+            Token::Numeric(n) => Ok(Operand::Number(n)),
+            Token::Boolean(b) => Ok(Operand::Boolean(b)),
+            Token::Null => Ok(Operand::Null),
+            Token::Name(name) => Ok(Operand::Name(name)),
+            Token::LitteralString(s) => Ok(Operand::String(s)),
+            Token::HexString(s) => Ok(Operand::HexString(s)),
+            Token::ArrayBegin => {
+                let mut array = vec![];
+                loop {
+                    match self.next_token()? {
+                        Token::ArrayEnd => return Ok(Operand::Array(array)),
+                        t => array.push(self.operand(t)?),
+                    }
+                }
+            }
+            Token::DictBegin => {
+                let mut entries = vec![];
+                loop {
+                    match self.next_token()? {
+                        Token::DictEnd => return Ok(Operand::Dictionary(entries)),
+                        Token::Name(key) => {
+                            let value = self.next_token()?;
+                            entries.push((key, self.operand(value)?));
+                        }
+                        t => {
+                            return Err(
+                                self.error(format!("Dictionary key should be a name, found {t:?}"))
+                            )
+                        }
+                    }
+                }
+            }
+            t => Err(self.error(format!("Unexpected token {t:?} in content stream"))),
+        }
+    }
+
+    // Parameters and data of an inline image, read after the BI operator (page 352)
+    fn inline_image(&mut self) -> Result<Vec<Operand>, Error> {
+        let mut parameters = vec![];
+        loop {
+            match self.next_token()? {
+                Token::String(s) if s == b"ID" => break,
+                Token::Name(key) => {
+                    let value = self.next_token()?;
+                    parameters.push((key, self.operand(value)?));
+                }
+                t => {
+                    return Err(self.error(format!(
+                        "Inline image parameter should be a name, found {t:?}"
+                    )))
+                }
+            }
+        }
+        // a single whitespace separates ID from the image data
+        let bytes = self.tokenizer.bytes;
+        let mut start = self.tokenizer.offset();
+        if bytes.get(start).is_some_and(|c| WHITESPACES.contains(c)) {
+            start += 1;
+        }
+        let length = InlineImage::new(&parameters, vec![]).data_length();
+        let (end, next) = inline_image_end(bytes, start, length)
+            .ok_or(self.error(String::from("End of inline image data EI not found")))?;
+        self.tokenizer = Tokenizer::new(bytes, next);
+        Ok(vec![
+            Operand::Dictionary(parameters),
+            Operand::String(bytes[start..end].to_vec()),
+        ])
+    }
+
+    // Next token of an array or a dictionary, comments are skipped
+    fn next_token(&mut self) -> Result<Token<'a>, Error> {
+        loop {
+            match self.tokenizer.next() {
+                Some(Token::Comment(_)) => continue,
+                Some(t) => return Ok(t),
+                None => return Err(self.error(String::from("End of stream inside an operand"))),
+            }
+        }
+    }
+}
+
+impl Iterator for Operations<'_> {
+    type Item = Result<Operation, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut operands = vec![];
+        let mut offset = None;
+        loop {
+            let start = self.tokenizer.offset();
+            let token = self.tokenizer.next()?;
+            match token {
+                Token::Comment(_) => continue,
+                // an inline image is read as a single BI operation, ID and EI are consumed with it
+                Token::String(operator) if operator == b"BI" => {
+                    return Some(self.inline_image().map(|operands| Operation {
+                        operator: String::from("BI"),
+                        operands,
+                        offset: self.token_offset(start),
+                    }))
+                }
+                Token::String(operator) => {
+                    return Some(Ok(Operation {
+                        operator: String::from_utf8_lossy(&operator).to_string(),
+                        operands,
+                        offset: offset.unwrap_or_else(|| self.token_offset(start)),
+                    }))
+                }
+                t => {
+                    offset = offset.or_else(|| Some(self.token_offset(start)));
+                    match self.operand(t) {
+                        Ok(operand) => operands.push(operand),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+            }
+        }
+    }
+}
+
 struct Content<'a> {
     graphic_state: GraphicsState,
     graphic_state_stack: Vec<GraphicsState>,
     text_object: TextObject,
-    tokenizer: Tokenizer<'a>,
+    operations: Operations<'a>,
+    compatibility: usize, // nesting level of BX/EX compatibility sections
 }
 
 #[derive(Debug, PartialEq)]
@@ -48,19 +524,21 @@ enum GraphicsInstruction {
     // Graphic state operators (page 219)
     LowerQ,
     UpperQ,
-    BDC, // Structure content operator (page 850) -> ignored at the moment
-    BMC,
-    EMC,
     Cm(Number, Number, Number, Number, Number, Number), // Modify current transfo matrix
     LowerW(LineWidth),                                  // Set the line width in the graphics state
     UpperJ(LineStyle),            // Set the line cap style in the graphics state
+    LowerJ(LineStyle),            // Set the line join style in the graphics state
+    UpperM(Number),               // Set the miter limit in the graphics state
     LowerD(DashArray, DashPhase), // Set the line dash pattern in the graphics state
+    Ri(Name),                     // Set the color rendering intent in the graphics state
     LowerI(Number),               // Set the flatness tolerance in the graphics state
-    Gs,                           // Set the specified parameters in the graphics state
+    Gs(Name),                     // Set the specified parameters in the graphics state
     // Path construction operators (page 226)
     LowerM(X, Y), // Begin a new subpath by moving the current point to coordinates (x, y)
     LowerL(X, Y), // Append a straight line segment from the current point to the point (x, y). The new current point is (x, y)
     LowerC(X1, Y1, X2, Y2, X3, Y3), // Append a cubic Bézier curve to the current path
+    LowerV(X2, Y2, X3, Y3), // Append a cubic Bézier curve, the current point being the first control point
+    LowerY(X1, Y1, X3, Y3), // Append a cubic Bézier curve, (x3, y3) being the second control point
     LowerH, // Close the current subpath by appending a straight line segment from the current point to the starting point of the subpath
     Re(Number, Number, Number, Number), // Append a rectangle to the current path as a complete subpath, with lower-left corner (x, y) and dimensions width and height in user space.
     // Clipping paths operators (page 235)
@@ -68,16 +546,30 @@ enum GraphicsInstruction {
     WStar,
     // Path painting operators (page 230)
     S,
+    LowerS, // Close and stroke the path
     LowerF,
+    UpperF,     // Same as f, for compatibility only
     LowerFStar, // Fill the path, using the even-odd rule to determine the region to fill
+    UpperB,     // Fill and then stroke the path
+    UpperBStar, // Fill using the even-odd rule and then stroke the path
+    LowerB,     // Close, fill and then stroke the path
+    LowerBStar, // Close, fill using the even-odd rule and then stroke the path
     N,
     // Color operators (page 287)
-    Cs(String),
-    Sc(Number),
-    UpperG(Gray),               // Set the stroking color space to DeviceGray
-    LowerG(Gray),               // Same as G but used for nonstroking operations
+    CS(Name),                            // Set the stroking color space
+    Cs(Name),                            // Set the nonstroking color space
+    SC(Vec<Number>),                     // Set the stroking color
+    Sc(Vec<Number>),                     // Set the nonstroking color
+    UpperScn(Vec<Number>, Option<Name>), // Same as SC, with a pattern name for Pattern color spaces
+    LowerScn(Vec<Number>, Option<Name>), // Same as sc, with a pattern name for Pattern color spaces
+    UpperG(Gray),                        // Set the stroking color space to DeviceGray
+    LowerG(Gray),                        // Same as G but used for nonstroking operations
     RG(Number, Number, Number), // Set the stroking color space to DeviceRGB and set the color intensities
     Rg(R, G, B),
+    UpperK(Number, Number, Number, Number), // Set the stroking color space to DeviceCMYK
+    LowerK(Number, Number, Number, Number), // Same as K but used for nonstroking operations
+    // Shading operator (page 303)
+    Sh(Name),
     // Text positionning operators (page 406)
     Td(Number, Number), // move to the start of next line
     TD(Number, Number), // move to the start of next line
@@ -85,16 +577,36 @@ enum GraphicsInstruction {
     TStar,
     // Text state operators (page 398)
     Tc(Number),         // set char space
+    Tw(Number),         // set word space
+    Tz(Number),         // set horizontal scaling
+    TL(Number),         // set leading
     Tf(String, Number), // set text font
     Tr(Number),         // set text mode
+    Ts(Number),         // set text rise
     // Text-showing operators (page 407)
-    Tj(Vec<u8>),       // show text string
-    TJ(Vec<ArrayVal>), // show text array
+    Tj(Vec<u8>),                          // show text string
+    TJ(Vec<ArrayVal>),                    // show text array
+    Quote(Vec<u8>),                       // move to the next line and show text string
+    DoubleQuote(Number, Number, Vec<u8>), // set word and char spacing, move to the next line and show text
     // Text object operator (page 405)
     BeginText,
     EndText,
+    // Type 3 font operators (page 393)
+    D0(Number, Number),
+    D1(Number, Number, Number, Number, Number, Number),
     // XObject operator (page 332)
     Do(String),
+    // Inline image operators (page 352)
+    InlineImage(InlineImage),
+    // Marked content operators (page 851), inline property lists are not kept
+    Mp(Name),
+    Dp(Name, Option<Name>),
+    Bmc(Name),
+    Bdc(Name, Option<Name>),
+    Emc,
+    // Compatibility operators (page 152)
+    Bx,
+    Ex,
 }
 
 impl<'a> From<Tokenizer<'a>> for Content<'a> {
@@ -103,7 +615,8 @@ impl<'a> From<Tokenizer<'a>> for Content<'a> {
             graphic_state: GraphicsState::default(),
             graphic_state_stack: vec![],
             text_object: TextObject::default(),
-            tokenizer,
+            operations: Operations::from(tokenizer),
+            compatibility: 0,
         }
     }
 }
@@ -114,25 +627,40 @@ impl<'a> From<&'a [u8]> for Content<'a> {
             graphic_state: GraphicsState::default(),
             graphic_state_stack: vec![],
             text_object: TextObject::default(),
-            tokenizer: Tokenizer::new(bytes, 0),
+            operations: Operations::from(Tokenizer::new(bytes, 0)),
+            compatibility: 0,
         }
     }
 }
 
 impl Content<'_> {
+    // Current font size selected in the text state
+    fn font_size(&self) -> Result<Number, Error> {
+        match &self.graphic_state.text_state.tfs {
+            Some(n) => Ok(n.clone()),
+            None => Err(self.error(String::from(
+                "Font size should be set before painting a glyph",
+            ))),
+        }
+    }
+
     fn process_q(&mut self) {
         self.graphic_state_stack.push(self.graphic_state.clone())
     }
 
-    fn process_upper_q(&mut self) {
+    fn process_upper_q(&mut self) -> Result<(), Error> {
         self.graphic_state = self
             .graphic_state_stack
             .pop()
-            .expect("Unable to restore graphic state from empty stack");
+            .ok_or(self.error(String::from(
+                "Unable to restore graphic state from empty stack",
+            )))?;
+        Ok(())
     }
 
     fn process_cm(&mut self, cm: [Number; 6]) {
-        self.graphic_state.ctm = Matrix::from(cm);
+        // the new matrix is concatenated with the current transformation matrix
+        self.graphic_state.ctm = Matrix::from(cm) * self.graphic_state.ctm;
     }
 
     fn process_w(&mut self, line_width: Number) {
@@ -143,8 +671,20 @@ impl Content<'_> {
         self.graphic_state.line_cap = line_cap;
     }
 
+    fn process_lower_j(&mut self, line_join: Number) {
+        self.graphic_state.line_join = line_join;
+    }
+
+    fn process_upper_m(&mut self, miter_limit: Number) {
+        self.graphic_state.miter_limit = miter_limit;
+    }
+
     fn process_d(&mut self, _dash_array: DashArray) {}
 
+    fn process_ri(&mut self, rendering_intent: Name) {
+        self.graphic_state.rendering_intent = rendering_intent;
+    }
+
     fn process_i(&mut self, flatness: Number) {
         self.graphic_state.flatness = flatness;
     }
@@ -176,6 +716,23 @@ impl Content<'_> {
         self.graphic_state.text_state.tc = tc;
     }
 
+    fn process_tw(&mut self, tw: Number) {
+        self.graphic_state.text_state.tw = tw;
+    }
+
+    // horizontal scaling is given as a percentage
+    fn process_tz(&mut self, scale: Number) {
+        self.graphic_state.text_state.th = scale / Number::Real(100.0);
+    }
+
+    fn process_tl(&mut self, tl: Number) {
+        self.graphic_state.text_state.tl = tl;
+    }
+
+    fn process_ts(&mut self, rise: Number) {
+        self.graphic_state.text_state.trise = rise;
+    }
+
     fn process_td(&mut self, tx: Number, ty: Number) {
         self.text_object.tlm =
             Matrix::new(1.0, 0.0, 0.0, 1.0, f32::from(tx), f32::from(ty)) * self.text_object.tlm;
@@ -216,421 +773,498 @@ impl Content<'_> {
     }
 
     fn process_t_star(&mut self) {
-        self.process_td(Number::Integer(0), self.graphic_state.text_state.tl.clone());
+        self.process_td(
+            Number::Integer(0),
+            -self.graphic_state.text_state.tl.clone(),
+        );
+    }
+
+    fn process_double_quote(&mut self, tw: Number, tc: Number) {
+        self.process_tw(tw);
+        self.process_tc(tc);
+        self.process_t_star();
     }
 }
 
-impl Iterator for Content<'_> {
-    type Item = GraphicsInstruction;
+impl Content<'_> {
+    fn error(&self, message: String) -> Error {
+        self.operations.error(message)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut buf: Vec<Token> = vec![];
-        while let Some(t) = self.tokenizer.next() {
-            match t {
-                Token::LitteralString(_) => buf.push(t),
-                Token::Name(_) => buf.push(t),
-                Token::ArrayBegin => buf.push(t),
-                Token::ArrayEnd => buf.push(t),
-                Token::DictBegin => {
-                    // ignored for now
-                    for t in self.tokenizer.by_ref() {
-                        if t == Token::DictEnd {
-                            break;
+    // Numeric operand at position idx
+    fn number(&self, buf: &[Operand], idx: usize, operator: &str) -> Result<Number, Error> {
+        match buf.get(idx) {
+            Some(Operand::Number(n)) => Ok(n.clone()),
+            Some(t) => Err(self.error(format!(
+                "Operand {t:?} is not allowed with operator {operator}"
+            ))),
+            None => Err(self.error(format!("Missing operand for operator {operator}"))),
+        }
+    }
+
+    // Name operand at position idx
+    fn name(&self, buf: &[Operand], idx: usize, operator: &str) -> Result<Name, Error> {
+        match buf.get(idx) {
+            Some(Operand::Name(n)) => Ok(n.clone()),
+            Some(t) => Err(self.error(format!(
+                "Operand {t:?} is not allowed with operator {operator}"
+            ))),
+            None => Err(self.error(format!("Missing operand for operator {operator}"))),
+        }
+    }
+
+    // String operand at position idx
+    fn string(&self, buf: &[Operand], idx: usize, operator: &str) -> Result<Vec<u8>, Error> {
+        match buf.get(idx) {
+            Some(Operand::String(s) | Operand::HexString(s)) => Ok(s.clone()),
+            Some(t) => Err(self.error(format!(
+                "Operand {t:?} is not allowed with operator {operator}"
+            ))),
+            None => Err(self.error(format!("Missing operand for operator {operator}"))),
+        }
+    }
+
+    // Color components, with a trailing pattern name for scn and SCN
+    fn colors(
+        &self,
+        buf: &[Operand],
+        operator: &str,
+    ) -> Result<(Vec<Number>, Option<Name>), Error> {
+        let mut colors = vec![];
+        let mut pattern = None;
+        for t in buf {
+            match (t, &pattern) {
+                (Operand::Number(n), None) => colors.push(n.clone()),
+                (Operand::Name(name), None) if operator.ends_with(['n', 'N']) => {
+                    pattern = Some(name.clone())
+                }
+                (t, _) => {
+                    return Err(self.error(format!(
+                        "Operand {t:?} is not allowed with operator {operator}"
+                    )))
+                }
+            }
+        }
+        Ok((colors, pattern))
+    }
+
+    fn next_instruction(&mut self) -> Result<Option<GraphicsInstruction>, Error> {
+        while let Some(operation) = self.operations.next() {
+            let Operation {
+                operator,
+                operands: buf,
+                ..
+            } = operation?;
+            match operator.as_str() {
+                "q" => {
+                    self.process_q();
+                    return Ok(Some(GraphicsInstruction::LowerQ));
+                }
+                "Q" => {
+                    self.process_upper_q()?;
+                    return Ok(Some(GraphicsInstruction::UpperQ));
+                }
+                "cm" => {
+                    let a = self.number(&buf, 0, "cm")?;
+                    let b = self.number(&buf, 1, "cm")?;
+                    let c = self.number(&buf, 2, "cm")?;
+                    let d = self.number(&buf, 3, "cm")?;
+                    let e = self.number(&buf, 4, "cm")?;
+                    let f = self.number(&buf, 5, "cm")?;
+                    self.process_cm([
+                        a.clone(),
+                        b.clone(),
+                        c.clone(),
+                        d.clone(),
+                        e.clone(),
+                        f.clone(),
+                    ]);
+                    return Ok(Some(GraphicsInstruction::Cm(a, b, c, d, e, f)));
+                }
+                "w" => {
+                    let line_width = self.number(&buf, 0, "w")?;
+                    self.process_w(line_width.clone());
+                    return Ok(Some(GraphicsInstruction::LowerW(line_width)));
+                }
+                "J" => {
+                    let line_cap = self.number(&buf, 0, "J")?;
+                    self.process_upper_j(line_cap.clone());
+                    return Ok(Some(GraphicsInstruction::UpperJ(line_cap)));
+                }
+                "j" => {
+                    let line_join = self.number(&buf, 0, "j")?;
+                    self.process_lower_j(line_join.clone());
+                    return Ok(Some(GraphicsInstruction::LowerJ(line_join)));
+                }
+                "M" => {
+                    let miter_limit = self.number(&buf, 0, "M")?;
+                    self.process_upper_m(miter_limit.clone());
+                    return Ok(Some(GraphicsInstruction::UpperM(miter_limit)));
+                }
+                "ri" => {
+                    let intent = self.name(&buf, 0, "ri")?;
+                    self.process_ri(intent.clone());
+                    return Ok(Some(GraphicsInstruction::Ri(intent)));
+                }
+                "d" => {
+                    let dash_array = match buf.first() {
+                        Some(Operand::Array(array)) => {
+                            array
+                                .iter()
+                                .map(|o| match o {
+                                    Operand::Number(n) => Ok(n.clone()),
+                                    o => Err(self
+                                        .error(format!("Unexpected operand {o:?} in dash array"))),
+                                })
+                                .collect::<Result<DashArray, Error>>()?
                         }
-                    }
+                        Some(o) => {
+                            return Err(self.error(format!(
+                                "First operand {o:?} is not allowed for operator d"
+                            )))
+                        }
+                        None => {
+                            return Err(self.error(String::from("Missing operand for operator d")))
+                        }
+                    };
+                    let dash_phase = self.number(&buf, 1, "d")?;
+                    self.process_d(dash_array.clone());
+                    return Ok(Some(GraphicsInstruction::LowerD(dash_array, dash_phase)));
                 }
-                Token::HexString(_) => buf.push(t),
-                Token::Numeric(_) => buf.push(t),
-                Token::String(l) => match l.as_slice() {
-                    b"q" => {
-                        self.process_q();
-                        return Some(GraphicsInstruction::LowerQ);
-                    }
-                    b"Q" => {
-                        self.process_upper_q();
-                        return Some(GraphicsInstruction::UpperQ);
-                    }
-                    b"cm" => {
-                        let a = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let b = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let c = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let d = match &buf[3] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let e = match &buf[4] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let f = match &buf[5] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        self.process_cm([
-                            a.clone(),
-                            b.clone(),
-                            c.clone(),
-                            d.clone(),
-                            e.clone(),
-                            f.clone(),
-                        ]);
-                        return Some(GraphicsInstruction::Cm(a, b, c, d, e, f));
-                    }
-                    b"w" => {
-                        let line_width = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator J"),
-                        };
-                        self.process_w(line_width.clone());
-                        return Some(GraphicsInstruction::LowerW(line_width));
-                    }
-                    b"J" => {
-                        let line_cap = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator J"),
-                        };
-                        self.process_upper_j(line_cap.clone());
-                        return Some(GraphicsInstruction::UpperJ(line_cap));
+                "i" => {
+                    let flatness = self.number(&buf, 0, "i")?;
+                    self.process_i(flatness.clone());
+                    return Ok(Some(GraphicsInstruction::LowerI(flatness)));
+                }
+                "gs" => {
+                    let dict_name = self.name(&buf, 0, "gs")?;
+                    self.process_gs(dict_name.clone());
+                    return Ok(Some(GraphicsInstruction::Gs(dict_name)));
+                }
+                "m" => {
+                    let x = self.number(&buf, 0, "m")?;
+                    let y = self.number(&buf, 1, "m")?;
+                    self.process_m(x.clone(), y.clone());
+                    return Ok(Some(GraphicsInstruction::LowerM(x, y)));
+                }
+                "l" => {
+                    let x = self.number(&buf, 0, "l")?;
+                    let y = self.number(&buf, 1, "l")?;
+                    self.process_l(x.clone(), y.clone());
+                    return Ok(Some(GraphicsInstruction::LowerL(x, y)));
+                }
+                "c" => {
+                    let x1 = self.number(&buf, 0, "c")?;
+                    let y1 = self.number(&buf, 1, "c")?;
+                    let x2 = self.number(&buf, 2, "c")?;
+                    let y2 = self.number(&buf, 3, "c")?;
+                    let x3 = self.number(&buf, 4, "c")?;
+                    let y3 = self.number(&buf, 5, "c")?;
+                    self.process_c(
+                        x1.clone(),
+                        y1.clone(),
+                        x2.clone(),
+                        y2.clone(),
+                        x3.clone(),
+                        y3.clone(),
+                    );
+                    return Ok(Some(GraphicsInstruction::LowerC(x1, y1, x2, y2, x3, y3)));
+                }
+                "v" => {
+                    let x2 = self.number(&buf, 0, "v")?;
+                    let y2 = self.number(&buf, 1, "v")?;
+                    let x3 = self.number(&buf, 2, "v")?;
+                    let y3 = self.number(&buf, 3, "v")?;
+                    return Ok(Some(GraphicsInstruction::LowerV(x2, y2, x3, y3)));
+                }
+                "y" => {
+                    let x1 = self.number(&buf, 0, "y")?;
+                    let y1 = self.number(&buf, 1, "y")?;
+                    let x3 = self.number(&buf, 2, "y")?;
+                    let y3 = self.number(&buf, 3, "y")?;
+                    return Ok(Some(GraphicsInstruction::LowerY(x1, y1, x3, y3)));
+                }
+                "h" => {
+                    return Ok(Some(GraphicsInstruction::LowerH));
+                }
+                "re" => {
+                    let x = self.number(&buf, 0, "re")?;
+                    let y = self.number(&buf, 1, "re")?;
+                    let width = self.number(&buf, 2, "re")?;
+                    let height = self.number(&buf, 3, "re")?;
+                    self.process_re(x.clone(), y.clone(), width.clone(), height.clone());
+                    return Ok(Some(GraphicsInstruction::Re(x, y, width, height)));
+                }
+                "W" => return Ok(Some(GraphicsInstruction::W)),
+                "W*" => return Ok(Some(GraphicsInstruction::WStar)),
+                "S" => return Ok(Some(GraphicsInstruction::S)),
+                "s" => return Ok(Some(GraphicsInstruction::LowerS)),
+                "f" => return Ok(Some(GraphicsInstruction::LowerF)),
+                "F" => return Ok(Some(GraphicsInstruction::UpperF)),
+                "f*" => return Ok(Some(GraphicsInstruction::LowerFStar)),
+                "B" => return Ok(Some(GraphicsInstruction::UpperB)),
+                "B*" => return Ok(Some(GraphicsInstruction::UpperBStar)),
+                "b" => return Ok(Some(GraphicsInstruction::LowerB)),
+                "b*" => return Ok(Some(GraphicsInstruction::LowerBStar)),
+                "n" => return Ok(Some(GraphicsInstruction::N)),
+                "CS" => {
+                    let color_space = self.name(&buf, 0, "CS")?;
+                    return Ok(Some(GraphicsInstruction::CS(color_space)));
+                }
+                "cs" => {
+                    let color_space = self.name(&buf, 0, "cs")?;
+                    return Ok(Some(GraphicsInstruction::Cs(color_space)));
+                }
+                "SC" => {
+                    let (colors, _) = self.colors(&buf, "SC")?;
+                    return Ok(Some(GraphicsInstruction::SC(colors)));
+                }
+                "sc" => {
+                    let (colors, _) = self.colors(&buf, "sc")?;
+                    return Ok(Some(GraphicsInstruction::Sc(colors)));
+                }
+                "SCN" => {
+                    let (colors, pattern) = self.colors(&buf, "SCN")?;
+                    return Ok(Some(GraphicsInstruction::UpperScn(colors, pattern)));
+                }
+                "scn" => {
+                    let (colors, pattern) = self.colors(&buf, "scn")?;
+                    return Ok(Some(GraphicsInstruction::LowerScn(colors, pattern)));
+                }
+                "G" => {
+                    let gray = self.number(&buf, 0, "G")?;
+                    return Ok(Some(GraphicsInstruction::UpperG(gray)));
+                }
+                "g" => {
+                    let gray = self.number(&buf, 0, "g")?;
+                    return Ok(Some(GraphicsInstruction::LowerG(gray)));
+                }
+                "RG" => {
+                    let r = self.number(&buf, 0, "RG")?;
+                    let g = self.number(&buf, 1, "RG")?;
+                    let b = self.number(&buf, 2, "RG")?;
+                    return Ok(Some(GraphicsInstruction::RG(r, g, b)));
+                }
+                "rg" => {
+                    let r = self.number(&buf, 0, "rg")?;
+                    let g = self.number(&buf, 1, "rg")?;
+                    let b = self.number(&buf, 2, "rg")?;
+                    return Ok(Some(GraphicsInstruction::Rg(r, g, b)));
+                }
+                "K" => {
+                    let c = self.number(&buf, 0, "K")?;
+                    let m = self.number(&buf, 1, "K")?;
+                    let y = self.number(&buf, 2, "K")?;
+                    let k = self.number(&buf, 3, "K")?;
+                    return Ok(Some(GraphicsInstruction::UpperK(c, m, y, k)));
+                }
+                "k" => {
+                    let c = self.number(&buf, 0, "k")?;
+                    let m = self.number(&buf, 1, "k")?;
+                    let y = self.number(&buf, 2, "k")?;
+                    let k = self.number(&buf, 3, "k")?;
+                    return Ok(Some(GraphicsInstruction::LowerK(c, m, y, k)));
+                }
+                "sh" => {
+                    let shading = self.name(&buf, 0, "sh")?;
+                    return Ok(Some(GraphicsInstruction::Sh(shading)));
+                }
+                "BT" => {
+                    self.process_bt();
+                    return Ok(Some(GraphicsInstruction::BeginText));
+                }
+                "ET" => return Ok(Some(GraphicsInstruction::EndText)),
+                "TD" => {
+                    let tx = self.number(&buf, 0, "TD")?;
+                    let ty = self.number(&buf, 1, "TD")?;
+                    self.process_t_upper_d(tx.clone(), ty.clone());
+                    return Ok(Some(GraphicsInstruction::TD(tx, ty)));
+                }
+                "Td" => {
+                    if buf.len() != 2 {
+                        continue;
                     }
-                    b"d" => {
-                        let mut e = buf.iter();
-                        match e.next() {
-                            Some(Token::ArrayBegin) => (),
-                            Some(t) => panic!("First operand {t:?} is not allowed for operator d"),
-                            None => panic!("End of stream too early"),
-                        };
-                        let mut dash_array = DashArray::new();
-                        for t in e.by_ref() {
-                            match t {
-                                Token::Numeric(n) => dash_array.push(n.clone()),
-                                Token::ArrayEnd => break,
-                                t => panic!("Unexpected token {t:?} in dash array"),
-                            }
+                    let tx = self.number(&buf, 0, "Td")?;
+                    let ty = self.number(&buf, 1, "Td")?;
+                    self.process_td(tx.clone(), ty.clone());
+                    return Ok(Some(GraphicsInstruction::Td(tx, ty)));
+                }
+                "Tc" => {
+                    let char_space = self.number(&buf, 0, "Tc")?;
+                    self.process_tc(char_space.clone());
+                    return Ok(Some(GraphicsInstruction::Tc(char_space)));
+                }
+                "Tw" => {
+                    let word_space = self.number(&buf, 0, "Tw")?;
+                    self.process_tw(word_space.clone());
+                    return Ok(Some(GraphicsInstruction::Tw(word_space)));
+                }
+                "Tz" => {
+                    let scale = self.number(&buf, 0, "Tz")?;
+                    self.process_tz(scale.clone());
+                    return Ok(Some(GraphicsInstruction::Tz(scale)));
+                }
+                "TL" => {
+                    let leading = self.number(&buf, 0, "TL")?;
+                    self.process_tl(leading.clone());
+                    return Ok(Some(GraphicsInstruction::TL(leading)));
+                }
+                "Ts" => {
+                    let rise = self.number(&buf, 0, "Ts")?;
+                    self.process_ts(rise.clone());
+                    return Ok(Some(GraphicsInstruction::Ts(rise)));
+                }
+                "Tf" => {
+                    let font = self.name(&buf, 0, "Tf")?;
+                    let size = self.number(&buf, 1, "Tf")?;
+                    self.process_tf(font.clone(), size.clone());
+                    return Ok(Some(GraphicsInstruction::Tf(font, size)));
+                }
+                "Tr" => {
+                    let render = self.number(&buf, 0, "Tr")?;
+                    self.process_tr(render.clone());
+                    return Ok(Some(GraphicsInstruction::Tr(render)));
+                }
+                "Tm" => {
+                    let a = self.number(&buf, 0, "Tm")?;
+                    let b = self.number(&buf, 1, "Tm")?;
+                    let c = self.number(&buf, 2, "Tm")?;
+                    let d = self.number(&buf, 3, "Tm")?;
+                    let e = self.number(&buf, 4, "Tm")?;
+                    let f = self.number(&buf, 5, "Tm")?;
+                    self.process_tm(
+                        a.clone(),
+                        b.clone(),
+                        c.clone(),
+                        d.clone(),
+                        e.clone(),
+                        f.clone(),
+                    );
+                    return Ok(Some(GraphicsInstruction::Tm(a, b, c, d, e, f)));
+                }
+                "T*" => {
+                    self.process_t_star();
+                    return Ok(Some(GraphicsInstruction::TStar));
+                }
+                "Tj" => {
+                    let text = self.string(&buf, 0, "Tj")?;
+                    return Ok(Some(GraphicsInstruction::Tj(text)));
+                }
+                "'" => {
+                    let text = self.string(&buf, 0, "'")?;
+                    self.process_t_star();
+                    return Ok(Some(GraphicsInstruction::Quote(text)));
+                }
+                "\"" => {
+                    let word_space = self.number(&buf, 0, "\"")?;
+                    let char_space = self.number(&buf, 1, "\"")?;
+                    let text = self.string(&buf, 2, "\"")?;
+                    self.process_double_quote(word_space.clone(), char_space.clone());
+                    return Ok(Some(GraphicsInstruction::DoubleQuote(
+                        word_space, char_space, text,
+                    )));
+                }
+                "TJ" => {
+                    let array = match buf.first() {
+                        Some(Operand::Array(array)) => array,
+                        Some(o) => {
+                            return Err(self
+                                .error(format!("Operand {o:?} is not allowed with operator TJ")))
                         }
-                        let dash_phase = match e.next() {
-                            Some(Token::Numeric(n)) => n.clone(),
-                            Some(t) => panic!("First operand {t:?} is not allowed for operator d"),
-                            None => panic!("End of stream too early"),
-                        };
-                        self.process_d(dash_array.clone());
-                        return Some(GraphicsInstruction::LowerD(dash_array, dash_phase));
-                    }
-                    b"i" => {
-                        let flatness = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        self.process_i(flatness.clone());
-                        return Some(GraphicsInstruction::LowerI(flatness));
-                    }
-                    b"gs" => {
-                        let dict_name = match &buf[0] {
-                            Token::Name(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator gs"),
-                        };
-                        self.process_gs(dict_name);
-                        return Some(GraphicsInstruction::Gs);
-                    }
-                    b"m" => {
-                        let x = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let y = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        self.process_m(x.clone(), y.clone());
-                        return Some(GraphicsInstruction::LowerM(x, y));
-                    }
-                    b"l" => {
-                        let x = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let y = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        self.process_l(x.clone(), y.clone());
-                        return Some(GraphicsInstruction::LowerL(x, y));
-                    }
-                    b"c" => {
-                        let x1 = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let y1 = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let x2 = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let y2 = match &buf[3] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let x3 = match &buf[4] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let y3 = match &buf[5] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        self.process_c(
-                            x1.clone(),
-                            y1.clone(),
-                            x2.clone(),
-                            y2.clone(),
-                            x3.clone(),
-                            y3.clone(),
-                        );
-                        return Some(GraphicsInstruction::LowerC(x1, y1, x2, y2, x3, y3));
-                    }
-                    b"h" => {
-                        return Some(GraphicsInstruction::LowerH);
-                    }
-                    b"re" => {
-                        let x = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let y = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let width = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        let height = match &buf[3] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator re"),
-                        };
-                        self.process_re(x.clone(), y.clone(), width.clone(), height.clone());
-                        return Some(GraphicsInstruction::Re(x, y, width, height));
-                    }
-                    b"W" => return Some(GraphicsInstruction::W),
-                    b"W*" => return Some(GraphicsInstruction::WStar),
-                    b"S" => return Some(GraphicsInstruction::S),
-                    b"f" => return Some(GraphicsInstruction::LowerF),
-                    b"f*" => return Some(GraphicsInstruction::LowerFStar),
-                    b"n" => return Some(GraphicsInstruction::N),
-                    b"cs" => {
-                        let color_space = match &buf[0] {
-                            Token::Name(s) => s.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator cs"),
-                        };
-                        return Some(GraphicsInstruction::Cs(color_space));
-                    }
-                    b"sc" => {
-                        let colors = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator cs"),
-                        };
-                        return Some(GraphicsInstruction::Sc(colors));
-                    }
-                    b"G" => {
-                        let gray = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator G"),
-                        };
-                        return Some(GraphicsInstruction::UpperG(gray));
-                    }
-                    b"g" => {
-                        let gray = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator G"),
-                        };
-                        return Some(GraphicsInstruction::LowerG(gray));
-                    }
-                    b"RG" => {
-                        let r = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        let g = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        let b = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        return Some(GraphicsInstruction::RG(r, g, b));
-                    }
-                    b"rg" => {
-                        let r = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        let g = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        let b = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator rg"),
-                        };
-                        return Some(GraphicsInstruction::Rg(r, g, b));
-                    }
-                    b"BT" => {
-                        self.process_bt();
-                        return Some(GraphicsInstruction::BeginText);
-                    }
-                    b"ET" => return Some(GraphicsInstruction::EndText),
-                    b"TD" => {
-                        let tx = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        let ty = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        self.process_t_upper_d(tx.clone(), ty.clone());
-                        return Some(GraphicsInstruction::TD(tx, ty));
-                    }
-                    b"Td" => {
-                        if buf.len() != 2 {
-                            return self.next();
+                        None => {
+                            return Err(self.error(String::from("Missing operand for operator TJ")))
                         }
-                        let tx = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        let ty = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        self.process_td(tx.clone(), ty.clone());
-                        return Some(GraphicsInstruction::Td(tx, ty));
-                    }
-                    b"Tc" => {
-                        let char_space = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        self.process_tc(char_space.clone());
-                        return Some(GraphicsInstruction::Tc(char_space));
-                    }
-                    b"Tf" => {
-                        let font = match &buf[0] {
-                            Token::Name(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        let size = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator TD"),
-                        };
-                        self.process_tf(font.clone(), size.clone());
-                        return Some(GraphicsInstruction::Tf(font, size));
-                    }
-                    b"Tr" => {
-                        let render = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tr"),
-                        };
-                        self.process_tr(render.clone());
-                        return Some(GraphicsInstruction::Tr(render));
-                    }
-                    b"Tm" => {
-                        let a = match &buf[0] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let b = match &buf[1] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let c = match &buf[2] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let d = match &buf[3] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let e = match &buf[4] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        let f = match &buf[5] {
-                            Token::Numeric(n) => n.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Tm"),
-                        };
-                        self.process_tm(
-                            a.clone(),
-                            b.clone(),
-                            c.clone(),
-                            d.clone(),
-                            e.clone(),
-                            f.clone(),
-                        );
-                        return Some(GraphicsInstruction::Tm(a, b, c, d, e, f));
-                    }
-                    b"T*" => {
-                        self.process_t_star();
-                        return Some(GraphicsInstruction::TStar);
-                    }
-                    b"Tj" => {
-                        let text = match &buf[0] {
-                            Token::LitteralString(l) => l,
-                            t => panic!("Operand {t:?} is not allowed with operator Tj"),
-                        };
-                        return Some(GraphicsInstruction::Tj(text.to_vec()));
-                    }
-                    b"TJ" => {
-                        return Some(GraphicsInstruction::TJ(
-                            buf.iter()
-                                .filter(|t| {
-                                    matches!(
-                                        t,
-                                        Token::LitteralString(_)
-                                            | Token::HexString(_)
-                                            | Token::Numeric(_)
-                                    )
-                                })
-                                .map(|t| match t {
-                                    Token::LitteralString(s) => ArrayVal::Text(s.to_vec()),
-                                    Token::HexString(s) => ArrayVal::Text(s.to_vec()),
-                                    Token::Numeric(n) => ArrayVal::Pos(n.clone()),
-                                    t => panic!("Impossible {t:?}"),
-                                })
-                                .collect(),
-                        ))
+                    };
+                    return Ok(Some(GraphicsInstruction::TJ(
+                        array
+                            .iter()
+                            .filter_map(|o| match o {
+                                Operand::String(s) | Operand::HexString(s) => {
+                                    Some(ArrayVal::Text(s.to_vec()))
+                                }
+                                Operand::Number(n) => Some(ArrayVal::Pos(n.clone())),
+                                _ => None,
+                            })
+                            .collect(),
+                    )));
+                }
+                "Do" => {
+                    let name = self.name(&buf, 0, "Do")?;
+                    return Ok(Some(GraphicsInstruction::Do(name)));
+                }
+                "BI" => match buf.as_slice() {
+                    [Operand::Dictionary(parameters), Operand::String(data)] => {
+                        return Ok(Some(GraphicsInstruction::InlineImage(InlineImage::new(
+                            parameters,
+                            data.clone(),
+                        ))))
                     }
-                    b"Do" => {
-                        return Some(GraphicsInstruction::Do(match &buf[0] {
-                            Token::Name(s) => s.clone(),
-                            t => panic!("Operand {t:?} is not allowed with operator Do"),
-                        }))
+                    _ => {
+                        return Err(self
+                            .error(String::from("Inline image should have parameters and data")))
                     }
-                    b"BDC" => return Some(GraphicsInstruction::BDC),
-                    b"BMC" => return Some(GraphicsInstruction::BMC),
-                    b"EMC" => return Some(GraphicsInstruction::EMC),
-                    s => println!(
-                        "Content token operator {:?} is not known, operands {:?}",
-                        String::from_utf8(s.to_vec()),
-                        buf
-                    ),
                 },
-                t => panic!("Pdf token {t:?} has no mapping implemented to ContentStream"),
+                "d0" => {
+                    let wx = self.number(&buf, 0, "d0")?;
+                    let wy = self.number(&buf, 1, "d0")?;
+                    return Ok(Some(GraphicsInstruction::D0(wx, wy)));
+                }
+                "d1" => {
+                    let wx = self.number(&buf, 0, "d1")?;
+                    let wy = self.number(&buf, 1, "d1")?;
+                    let llx = self.number(&buf, 2, "d1")?;
+                    let lly = self.number(&buf, 3, "d1")?;
+                    let urx = self.number(&buf, 4, "d1")?;
+                    let ury = self.number(&buf, 5, "d1")?;
+                    return Ok(Some(GraphicsInstruction::D1(wx, wy, llx, lly, urx, ury)));
+                }
+                // the property list of DP and BDC is either a name or an inline dictionary
+                "MP" => {
+                    let tag = self.name(&buf, 0, "MP")?;
+                    return Ok(Some(GraphicsInstruction::Mp(tag)));
+                }
+                "DP" => {
+                    let tag = self.name(&buf, 0, "DP")?;
+                    let properties = self.name(&buf, 1, "DP").ok();
+                    return Ok(Some(GraphicsInstruction::Dp(tag, properties)));
+                }
+                "BMC" => {
+                    let tag = self.name(&buf, 0, "BMC")?;
+                    return Ok(Some(GraphicsInstruction::Bmc(tag)));
+                }
+                "BDC" => {
+                    let tag = self.name(&buf, 0, "BDC")?;
+                    let properties = self.name(&buf, 1, "BDC").ok();
+                    return Ok(Some(GraphicsInstruction::Bdc(tag, properties)));
+                }
+                "EMC" => return Ok(Some(GraphicsInstruction::Emc)),
+                "BX" => {
+                    self.compatibility += 1;
+                    return Ok(Some(GraphicsInstruction::Bx));
+                }
+                "EX" => {
+                    self.compatibility = self.compatibility.saturating_sub(1);
+                    return Ok(Some(GraphicsInstruction::Ex));
+                }
+                // unknown operators are ignored in compatibility sections only (page 152)
+                // their operands are consumed with them
+                _ if self.compatibility > 0 => (),
+                s => {
+                    return Err(self.error(format!(
+                        "Unknown operator {s:?} outside of a BX/EX compatibility section"
+                    )))
+                }
             }
         }
-        None
+        Ok(None)
+    }
+}
+
+impl Iterator for Content<'_> {
+    type Item = Result<GraphicsInstruction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_instruction().transpose()
     }
 }
 
@@ -644,7 +1278,7 @@ struct TextState {
     tf: Option<String>,  // text font
     tfs: Option<Number>, // text font size
     tmode: Number,       // text rendering mode
-                         // trise: Number,       // text rise
+    trise: Number,       // text rise
                          // tk: bool,            // text knockout
 }
 
@@ -658,7 +1292,7 @@ impl Default for TextState {
             tf: None,
             tfs: None,
             tmode: Number::Integer(0),
-            // trise: Number::Integer(0),
+            trise: Number::Integer(0),
             // tk: true,
         }
     }
@@ -674,10 +1308,10 @@ struct GraphicsState {
     text_state: TextState,
     line_width: Number,
     line_cap: Number,
-    // line_join: Number,
-    // miter_limit: Number,
+    line_join: Number,
+    miter_limit: Number,
     // TODO: dash_pattern,
-    // rendering_intent: String,
+    rendering_intent: Name,
     // stroke_adjustment: bool,
     // blend_mode: String,
     // TODO: softmask,
@@ -701,10 +1335,10 @@ impl Default for GraphicsState {
             // color_space: String::from("DeviceGray"),
             text_state: TextState::default(),
             line_width: Number::Real(1.0),
-            line_cap: Number::Integer(0), // square butt caps
-            // line_join: Number::Integer(0),
-            // miter_limit: Number::Real(10.0),
-            // rendering_intent: String::from("RelativeColorimetric"),
+            line_cap: Number::Integer(0),  // square butt caps
+            line_join: Number::Integer(0), // miter joins
+            miter_limit: Number::Real(10.0),
+            rendering_intent: String::from("RelativeColorimetric"),
             // stroke_adjustment: false,
             // blend_mode: String::from("Normal"),
             // alpha_constant: Number::Real(1.0),
@@ -716,32 +1350,201 @@ impl Default for GraphicsState {
     }
 }
 
+// Gaps between glyphs wider than this fraction of the space width separate words
+// Text is extracted with the current font, so gaps are compared with its own space width,
+// the layout only knows the size of painted glyphs and uses layout::LAYOUT_WORD_GAP instead
+const SPACE_GAP_RATIO: f32 = 0.4;
+
 pub struct TextContent<'a> {
-    resources: Box<Resources>,
+    resources: Rc<Resources>,
+    ancestors: Vec<Rc<Resources>>, // resources of the contents invoking this one
     content: Content<'a>,
+    forms: Vec<IndirectObject>, // forms being painted, used to detect cycles
+    glyph_end: Option<Matrix>,  // text matrix after the last painted glyph
+    glyphs: Vec<Glyph>,         // glyphs painted by get_text, in device space
 }
 
 impl<'a> TextContent<'a> {
     pub fn new(content_bytes: &'a [u8], resources: Box<Resources>) -> Self {
         Self {
-            resources,
+            resources: Rc::from(resources),
+            ancestors: vec![],
             content: Content::from(Tokenizer::new(content_bytes, 0)),
+            forms: vec![],
+            glyph_end: None,
+            glyphs: vec![],
+        }
+    }
+
+    // Glyphs painted so far, including those of forms
+    pub fn glyphs(&self) -> &[Glyph] {
+        &self.glyphs
+    }
+
+    // Text painted by a form XObject invoked with the Do operator (page 358)
+    fn form_text(
+        &self,
+        form: &FormXObject,
+        display_char: bool,
+    ) -> Result<(String, Vec<Glyph>), Error> {
+        if let Some(id) = form.id {
+            if self.forms.contains(&id) {
+                return Ok((String::new(), vec![]));
+            }
+        }
+        let content_bytes = form.get_data()?;
+        let mut content = Content::from(Tokenizer::new(&content_bytes, 0));
+        // the form matrix maps form space into the user space of the invoking content
+        content.graphic_state = self.content.graphic_state.clone();
+        content.graphic_state.ctm = form.matrix * content.graphic_state.ctm;
+        let mut text_content = TextContent {
+            // a form without resources uses the resources of the invoking content
+            resources: match (&form.resources, form.resources_id) {
+                (Some(resources), _) => resources.clone(),
+                (None, Some(id)) => self
+                    .ancestors
+                    .iter()
+                    .chain([&self.resources])
+                    .rev()
+                    .find(|resources| resources.id == Some(id))
+                    .unwrap_or(&self.resources)
+                    .clone(),
+                (None, None) => self.resources.clone(),
+            },
+            ancestors: self
+                .ancestors
+                .iter()
+                .chain([&self.resources])
+                .cloned()
+                .collect(),
+            content,
+            forms: self.forms.iter().copied().chain(form.id).collect(),
+            glyph_end: None,
+            glyphs: vec![],
+        };
+        let text = text_content.get_text(display_char)?;
+        Ok((text, text_content.glyphs))
+    }
+
+    // Current font selected in the text state
+    fn font<'b>(resources: &'b Resources, content: &Content) -> Result<&'b Font, Error> {
+        match content.graphic_state.text_state.tf {
+            Some(ref s) => match &resources.font {
+                Some(fontmap) => fontmap
+                    .0
+                    .get(s)
+                    .ok_or(Error::font("Font not found in page resources").with_font_name(s)),
+                None => Err(Error::font(format!(
+                    "Fontmap does not contains the font name {s:?}"
+                ))),
+            },
+            None => Err(content.error(String::from("Text state should have a font set"))),
+        }
+    }
+
+    // Word boundary before a glyph painted at the current text matrix
+    // The glyph should start on the baseline of the previous one, after a gap wider than a fraction of a space
+    fn word_gap(content: &Content, font: &Font, glyph_end: &Option<Matrix>) -> Result<bool, Error> {
+        let Some((dx, dy)) = glyph_end.and_then(|end| content.text_object.tm.origin_in(&end))
+        else {
+            return Ok(false);
+        };
+        let tfs = f32::from(content.font_size()?).abs();
+        let th = f32::from(content.graphic_state.text_state.th.clone()).abs();
+        let space = f32::from(font.estimate_space_width()) * tfs * th;
+        Ok(dy.abs() < tfs / 2.0 && dx > space * SPACE_GAP_RATIO)
+    }
+
+    // Paint the glyphs of a string, the text matrix is moved after each glyph (page 410)
+    // A space is inserted in the text where a gap separates the glyph from the previous one
+    fn show_text(
+        content: &mut Content,
+        font: &Font,
+        text: &[u8],
+        display_char: bool,
+        output: &mut String,
+        glyph_end: &mut Option<Matrix>,
+        glyphs: &mut Vec<Glyph>,
+    ) -> Result<(), Error> {
+        let tfs = content.font_size()?;
+        let text_state = &content.graphic_state.text_state;
+        let (tc, tw, th) = (
+            text_state.tc.clone(),
+            text_state.tw.clone(),
+            text_state.th.clone(),
+        );
+        for code in font.codes(text) {
+            // codes missing from the unicode map are read as chars, composite font codes cannot be
+            let unicode = match font.unicode(code) {
+                Some(text) => text,
+                None if font.is_composite() => String::from(char::REPLACEMENT_CHARACTER),
+                None => String::from(char::from(code as u8)),
+            };
+            if !display_char
+                && unicode != " "
+                && !output.is_empty()
+                && !output.ends_with(char::is_whitespace)
+                && Self::word_gap(content, font, glyph_end)?
+            {
+                output.push(' ');
+            }
+            // paint glyph, a line per char of ligatures
+            if display_char {
+                for char in unicode.chars() {
+                    *output += format!(
+                        "{:?}, {:?}, {:?}, {:}\n",
+                        char,
+                        font.subtype,
+                        font.base_font.as_deref().unwrap_or_default(),
+                        content.text_object.tm
+                    )
+                    .as_str();
+                }
+            } else {
+                output.push_str(&unicode);
+            }
+            // displacement vector, horizontal writing mode only
+            let w0: Number = match font.get_width(code) {
+                Ok(n) => n,
+                Err(_) => Number::Real(0.0), // assumption at the moment, probably need to leverage Font Encoding
+            };
+            // translation vector coordinates is (tx, 0)
+            let mut tx = w0 * tfs.clone() + tc.clone();
+            // word spacing applies to the single-byte code 32 only
+            if code == 32 && !font.is_composite() {
+                tx = tx + tw.clone();
+            }
+            tx = tx * th.clone();
+            // glyph origin and displacement in device space, raised by the text rise
+            let trm = content.text_object.tm * content.graphic_state.ctm;
+            let trise = f32::from(text_state.trise.clone());
+            let (x, y) = trm.transform(0.0, trise);
+            let (top_x, top_y) = trm.transform(0.0, trise + f32::from(tfs.clone()));
+            content.text_object.tm =
+                Matrix::new(1.0, 0.0, 0.0, 1.0, tx.into(), 0.0) * content.text_object.tm;
+            *glyph_end = Some(content.text_object.tm);
+            let (end, _) =
+                (content.text_object.tm * content.graphic_state.ctm).transform(0.0, trise);
+            glyphs.push(Glyph {
+                text: unicode,
+                x,
+                y,
+                width: end - x,
+                font_size: (top_x - x).hypot(top_y - y),
+            });
         }
+        Ok(())
     }
 
-    pub fn get_text(&mut self, display_char: bool) -> String {
+    pub fn get_text(&mut self, display_char: bool) -> Result<String, Error> {
         let mut output = String::new();
         let mut tm_prev = self.content.text_object.tm;
         while let Some(i) = self.content.next() {
-            match i {
-                GraphicsInstruction::Tj(text) => {
-                    let font = match self.content.graphic_state.text_state.tf {
-                        Some(ref s) => match &self.resources.font {
-                            Some(fontmap) => fontmap.0.get(s).unwrap(),
-                            None => panic!("Fontmap does not contains the font name {s:?}"),
-                        },
-                        None => panic!("Text state should have a font set"),
-                    };
+            match i? {
+                GraphicsInstruction::Tj(text)
+                | GraphicsInstruction::Quote(text)
+                | GraphicsInstruction::DoubleQuote(_, _, text) => {
+                    let font = Self::font(&self.resources, &self.content)?;
 
                     // detect a line feed if tm y coordinate has changed
                     if self.content.text_object.tm.get_ty() != tm_prev.get_ty() {
@@ -749,30 +1552,19 @@ impl<'a> TextContent<'a> {
                     }
                     tm_prev = self.content.text_object.tm;
 
-                    for c in text {
-                        if display_char {
-                            output += format!(
-                                "{:?}, {:?}, {:?}, {:}\n",
-                                c as char,
-                                font.subtype,
-                                font.base_font,
-                                self.content.text_object.tm
-                            )
-                            .as_str();
-                        } else {
-                            output.push(c as char);
-                        }
-                    }
+                    Self::show_text(
+                        &mut self.content,
+                        font,
+                        &text,
+                        display_char,
+                        &mut output,
+                        &mut self.glyph_end,
+                        &mut self.glyphs,
+                    )?;
                 }
                 GraphicsInstruction::TJ(text) => {
                     // current font
-                    let font = match self.content.graphic_state.text_state.tf {
-                        Some(ref s) => match &self.resources.font {
-                            Some(fontmap) => fontmap.0.get(s).unwrap(),
-                            None => panic!("Fontmap does not contains the font name {s:?}"),
-                        },
-                        None => panic!("Text state should have a font set"),
-                    };
+                    let font = Self::font(&self.resources, &self.content)?;
 
                     // detect a line feed if tm y coordinate has changed
                     if self.content.text_object.tm.get_ty() != tm_prev.get_ty() {
@@ -780,174 +1572,52 @@ impl<'a> TextContent<'a> {
                     }
                     tm_prev = self.content.text_object.tm;
 
-                    // estimate current space width (before scaling)
-                    // let w_space = font.estimate_space_width();
                     for c in text {
                         match c {
-                            ArrayVal::Text(t) => {
-                                // string characters in to unicode map
-                                match &font.to_unicode {
-                                    Some(to_unicode_cmap) => {
-                                        let mut hex_iter = t.iter();
-                                        while let Some(c) = hex_iter.next() {
-                                            let char_idx = match to_unicode_cmap.is_two_bytes {
-                                                true => {
-                                                    *c as usize * 256
-                                                        + *hex_iter.next().unwrap() as usize
-                                                }
-                                                false => usize::from(*c),
-                                            };
-                                            let char = match to_unicode_cmap.cmap.get(&char_idx) {
-                                                Some(c) => c,
-                                                None => panic!("CMap does not contain a char with idx {:?}, charmap {:?}", char_idx, to_unicode_cmap.cmap)
-                                            };
-                                            // paint glyph
-                                            if display_char {
-                                                output += format!(
-                                                    "{:?}, {:?}, {:?}, {:}\n",
-                                                    char,
-                                                    font.subtype,
-                                                    font.base_font,
-                                                    self.content.text_object.tm
-                                                )
-                                                .as_str();
-                                            } else {
-                                                output.push(*char);
-                                            }
-                                            // displacement vector
-                                            let w0: Number = match font.clone().get_width(*c) {
-                                                Ok(n) => n,
-                                                Err(_) => Number::Real(0.0), // assumption at the moment, probably need to leverage Font Encoding
-                                            };
-                                            // let w1 = Number::Integer(0); // temporary, need to be updated with writing mode (horizontal writing only)
-                                            let tfs = match &self.content.graphic_state.text_state.tfs {
-                                                Some(n) => n,
-                                                None => panic!("Font size should be set before painting a glyph")
-                                            };
-                                            let tc =
-                                                self.content.graphic_state.text_state.tc.clone();
-                                            let tw =
-                                                self.content.graphic_state.text_state.tw.clone();
-                                            let th =
-                                                self.content.graphic_state.text_state.th.clone();
-                                            // update text matrix (page 410)
-                                            // translation vector coordinates
-                                            // tj displacement factor is added according to the text writing mode (assumed 0 for now) -> page 408
-                                            let mut tx = w0.clone() * tfs.clone() + tc.clone();
-                                            // tw displacement for word space
-                                            if *c == b' ' {
-                                                tx = tx + tw.clone();
-                                            }
-                                            tx = tx * th;
-                                            let ty = Number::Real(0.0);
-                                            // let ty = (w1.clone()) // + -tj.clone() / Number::Real(1000.0)) -> to be added if a different writing mode is selected
-                                            //     * tfs.clone()
-                                            //     + tc.clone()
-                                            //     + tw.clone();
-                                            self.content.text_object.tm =
-                                                Matrix::new(
-                                                    1.0,
-                                                    0.0,
-                                                    0.0,
-                                                    1.0,
-                                                    tx.into(),
-                                                    ty.into(),
-                                                ) * self.content.text_object.tm;
-                                        }
-                                    }
-                                    // no unicode mapping -> read as char
-                                    None => {
-                                        for c in t {
-                                            if display_char {
-                                                output += format!(
-                                                    "{:?}, {:?}, {:?}, {:}\n",
-                                                    c as char,
-                                                    font.subtype,
-                                                    font.base_font,
-                                                    self.content.text_object.tm
-                                                )
-                                                .as_str();
-                                            } else {
-                                                output.push(c as char);
-                                            }
-                                            // displacement vector
-                                            let w0: Number = match font.clone().get_width(c) {
-                                                Ok(w) => w,
-                                                Err(_) => Number::Real(0.0),
-                                            };
-                                            // let w1 = Number::Integer(0); // temporary, need to be updated with writing mode (horizontal writing only)
-                                            let tfs = match &self.content.graphic_state.text_state.tfs {
-                                                Some(n) => n,
-                                                None => panic!("Font size should be set before painting a glyph")
-                                            };
-                                            let tc =
-                                                self.content.graphic_state.text_state.tc.clone();
-                                            let tw =
-                                                self.content.graphic_state.text_state.tw.clone();
-                                            let th =
-                                                self.content.graphic_state.text_state.th.clone();
-                                            // update text matrix (page 410)
-                                            // translation vector coordinates is (tx, ty)
-                                            let mut tx = w0.clone() * tfs.clone() + tc.clone();
-                                            // tw displacement for word space
-                                            if c == b' ' {
-                                                tx = tx + tw.clone();
-                                            }
-                                            tx = tx * th;
-                                            let ty = Number::Real(0.0);
-                                            // let ty = (w1.clone()) // + -tj.clone() / Number::Real(1000.0))
-                                            //     * tfs.clone()
-                                            //     + tc.clone()
-                                            //     + tw.clone();
-                                            self.content.text_object.tm =
-                                                Matrix::new(
-                                                    1.0,
-                                                    0.0,
-                                                    0.0,
-                                                    1.0,
-                                                    tx.into(),
-                                                    ty.into(),
-                                                ) * self.content.text_object.tm;
-                                        }
-                                    }
-                                };
-                            }
+                            ArrayVal::Text(t) => Self::show_text(
+                                &mut self.content,
+                                font,
+                                &t,
+                                display_char,
+                                &mut output,
+                                &mut self.glyph_end,
+                                &mut self.glyphs,
+                            )?,
                             // translation according to text writing direction (assumed horizontal for now)
                             ArrayVal::Pos(tj) => {
-                                let tfs = match &self.content.graphic_state.text_state.tfs {
-                                    Some(n) => n,
-                                    None => {
-                                        panic!("Font size should be set before painting a glyph")
-                                    }
-                                };
+                                let tfs = self.content.font_size()?;
                                 let th = self.content.graphic_state.text_state.th.clone();
                                 let tx = -tj / Number::Real(1000.0) * tfs.clone() * th.clone();
-                                // apply transformation
+                                // apply transformation, word gaps are detected on the next glyph
                                 self.content.text_object.tm =
                                     Matrix::new(1.0, 0.0, 0.0, 1.0, tx.clone().into(), 0.0)
                                         * self.content.text_object.tm;
-                                // whitespace detected
-                                // to be improved...
-                                // match tx {
-                                //     Number::Integer(i) => {
-                                //         if i > 0 && !output.ends_with(' ') {
-                                //             output.push(' ');
-                                //         }
-                                //     }
-                                //     Number::Real(f) => {
-                                //         if f > 0.0 && !output.ends_with(' ') {
-                                //             output.push(' ');
-                                //         }
-                                //     }
-                                // }
                             }
                         }
                     }
                 }
+                GraphicsInstruction::Do(name) => {
+                    let xobject = self
+                        .resources
+                        .xobject
+                        .as_ref()
+                        .and_then(|xobjects| xobjects.0.get(&name));
+                    // images carry no text, unknown names may be forms skipped to break a cycle
+                    if let Some(XObject::Form(form)) = xobject {
+                        let (text, glyphs) = self.form_text(form, display_char)?;
+                        self.glyphs.extend(glyphs);
+                        if !text.is_empty() {
+                            if !output.is_empty() && !output.ends_with('\n') {
+                                output += "\n";
+                            }
+                            output += &text;
+                        }
+                    }
+                }
                 _ => (),
             }
         }
-        output.trim_start_matches(['\n', ' ']).to_string()
+        Ok(output.trim_start_matches(['\n', ' ']).to_string())
     }
 }
 
@@ -962,26 +1632,26 @@ mod tests {
     fn test_tokens() {
         let raw = b"BT\n70 50 TD\n/F1 12 Tf\n(Hello, world!) Tj\nET".as_slice();
         let mut stream = Content::from(raw);
-        assert_eq!(stream.next(), Some(GraphicsInstruction::BeginText));
+        assert_eq!(stream.next(), Some(Ok(GraphicsInstruction::BeginText)));
         assert_eq!(
             stream.next(),
-            Some(GraphicsInstruction::TD(
+            Some(Ok(GraphicsInstruction::TD(
                 Number::Integer(70),
                 Number::Integer(50)
-            ))
+            )))
         );
         assert_eq!(
             stream.next(),
-            Some(GraphicsInstruction::Tf(
+            Some(Ok(GraphicsInstruction::Tf(
                 "F1".to_string(),
                 Number::Integer(12)
-            ))
+            )))
         );
         assert_eq!(
             stream.next(),
-            Some(GraphicsInstruction::Tj(b"Hello, world!".to_vec()))
+            Some(Ok(GraphicsInstruction::Tj(b"Hello, world!".to_vec())))
         );
-        assert_eq!(stream.next(), Some(GraphicsInstruction::EndText));
+        assert_eq!(stream.next(), Some(Ok(GraphicsInstruction::EndText)));
         assert_eq!(stream.next(), None);
     }
 
@@ -991,7 +1661,7 @@ mod tests {
         let mut stream = Content::from(raw);
         assert_eq!(
             stream.next(),
-            Some(GraphicsInstruction::TJ(vec![
+            Some(Ok(GraphicsInstruction::TJ(vec![
                 ArrayVal::Text(vec![24]),
                 ArrayVal::Pos(Number::Integer(14)),
                 ArrayVal::Text(vec![13]),
@@ -1013,7 +1683,7 @@ mod tests {
                 ArrayVal::Text(vec![2]),
                 ArrayVal::Pos(Number::Integer(1)),
                 ArrayVal::Text(vec![9, 6]),
-            ]))
+            ])))
         );
     }
 
@@ -1021,16 +1691,19 @@ mod tests {
     fn test_tokenizer_dict() {
         let raw = b" /P <</MCID 0>> BDC q\n0.00000887 0 595.25 842 re".as_slice();
         let mut text_stream = Content::from(raw);
-        assert_eq!(text_stream.next(), Some(GraphicsInstruction::BDC));
-        assert_eq!(text_stream.next(), Some(GraphicsInstruction::LowerQ));
         assert_eq!(
             text_stream.next(),
-            Some(GraphicsInstruction::Re(
+            Some(Ok(GraphicsInstruction::Bdc(String::from("P"), None)))
+        );
+        assert_eq!(text_stream.next(), Some(Ok(GraphicsInstruction::LowerQ)));
+        assert_eq!(
+            text_stream.next(),
+            Some(Ok(GraphicsInstruction::Re(
                 Number::Real(0.00000887),
                 Number::Integer(0),
                 Number::Real(595.25),
                 Number::Integer(842)
-            ))
+            )))
         );
     }
 
@@ -1038,24 +1711,24 @@ mod tests {
     fn test_tokenizer_complex() {
         let raw = b"BT\n/F33 8.9664 Tf 54 713.7733 Td[(v0)-525(:=)-525(ld)-525(state[748])-2625(//)-525(load)-525(primes)-525(from)-525(the)-525(trace)-525(activation)-525(record)]TJ".as_slice();
         let mut text_stream = Content::from(raw);
-        assert_eq!(text_stream.next(), Some(GraphicsInstruction::BeginText));
+        assert_eq!(text_stream.next(), Some(Ok(GraphicsInstruction::BeginText)));
         assert_eq!(
             text_stream.next(),
-            Some(GraphicsInstruction::Tf(
+            Some(Ok(GraphicsInstruction::Tf(
                 "F33".to_string(),
                 Number::Real(8.9664)
-            ))
+            )))
         );
         assert_eq!(
             text_stream.next(),
-            Some(GraphicsInstruction::Td(
+            Some(Ok(GraphicsInstruction::Td(
                 Number::Integer(54),
                 Number::Real(713.7733)
-            ))
+            )))
         );
         assert_eq!(
             text_stream.next(),
-            Some(GraphicsInstruction::TJ(vec![
+            Some(Ok(GraphicsInstruction::TJ(vec![
                 ArrayVal::Text(b"v0".to_vec()),
                 ArrayVal::Pos(Number::Integer(-525)),
                 ArrayVal::Text(b":=".to_vec()),
@@ -1079,8 +1752,237 @@ mod tests {
                 ArrayVal::Text(b"activation".to_vec()),
                 ArrayVal::Pos(Number::Integer(-525)),
                 ArrayVal::Text(b"record".to_vec()),
-            ]))
+            ])))
         );
         assert_eq!(text_stream.next(), None);
     }
+
+    #[test]
+    fn test_operators() {
+        let raw = b"1 j 4 M /Perceptual ri /Cs1 CS 0.5 0 1 SC /P0 scn 0 0 0 1 k 1 2 3 4 v B* /Sh1 sh /OC /MC0 BDC EMC 1000 0 d0".as_slice();
+        let mut content = Content::from(raw);
+        let expected = vec![
+            GraphicsInstruction::LowerJ(Number::Integer(1)),
+            GraphicsInstruction::UpperM(Number::Integer(4)),
+            GraphicsInstruction::Ri(String::from("Perceptual")),
+            GraphicsInstruction::CS(String::from("Cs1")),
+            GraphicsInstruction::SC(vec![
+                Number::Real(0.5),
+                Number::Integer(0),
+                Number::Integer(1),
+            ]),
+            GraphicsInstruction::LowerScn(vec![], Some(String::from("P0"))),
+            GraphicsInstruction::LowerK(
+                Number::Integer(0),
+                Number::Integer(0),
+                Number::Integer(0),
+                Number::Integer(1),
+            ),
+            GraphicsInstruction::LowerV(
+                Number::Integer(1),
+                Number::Integer(2),
+                Number::Integer(3),
+                Number::Integer(4),
+            ),
+            GraphicsInstruction::UpperBStar,
+            GraphicsInstruction::Sh(String::from("Sh1")),
+            GraphicsInstruction::Bdc(String::from("OC"), Some(String::from("MC0"))),
+            GraphicsInstruction::Emc,
+            GraphicsInstruction::D0(Number::Integer(1000), Number::Integer(0)),
+        ];
+        for instruction in expected {
+            assert_eq!(content.next(), Some(Ok(instruction)));
+        }
+        assert_eq!(content.next(), None);
+        assert_eq!(content.graphic_state.line_join, Number::Integer(1));
+        assert_eq!(content.graphic_state.rendering_intent, "Perceptual");
+    }
+
+    #[test]
+    fn test_compatibility_sections() {
+        let raw = b"BX 1 2 unknown BX /A foo EX EX 0 g".as_slice();
+        let mut content = Content::from(raw);
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::Bx)));
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::Bx)));
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::Ex)));
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::Ex)));
+        assert_eq!(
+            content.next(),
+            Some(Ok(GraphicsInstruction::LowerG(Number::Integer(0))))
+        );
+        // unknown operators are errors outside of compatibility sections
+        let mut content = Content::from(b"1 2 unknown".as_slice());
+        assert!(matches!(content.next(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_text_state_operators() {
+        let raw = b"BT 14 TL 50 Tz 2 Ts 10 700 Td (a) ' 3 1 (b) \"".as_slice();
+        let mut content = Content::from(raw);
+        for i in content.by_ref().take(5) {
+            i.unwrap();
+        }
+        assert_eq!(content.graphic_state.text_state.th, Number::Real(0.5));
+        assert_eq!(content.graphic_state.text_state.trise, Number::Integer(2));
+        // ' moves to the next line before showing the text
+        assert_eq!(
+            content.next(),
+            Some(Ok(GraphicsInstruction::Quote(b"a".to_vec())))
+        );
+        assert_eq!(content.text_object.tm.get_ty(), 686.0);
+        assert_eq!(
+            content.next(),
+            Some(Ok(GraphicsInstruction::DoubleQuote(
+                Number::Integer(3),
+                Number::Integer(1),
+                b"b".to_vec()
+            )))
+        );
+        assert_eq!(content.text_object.tm.get_ty(), 672.0);
+        assert_eq!(content.graphic_state.text_state.tw, Number::Integer(3));
+        assert_eq!(content.graphic_state.text_state.tc, Number::Integer(1));
+    }
+
+    #[test]
+    fn test_content_stream() {
+        let raw = b"% comment\n/P <</MCID 0 /Alt (a\\)b)>> BDC\nq 0.5 0 0 0.5 0 0 cm\n[(A) -250 <0102>] TJ /Name#20x Do Q EMC".as_slice();
+        let stream = ContentStream::try_from(raw).unwrap();
+        let operators: Vec<&str> = stream
+            .operations
+            .iter()
+            .map(|o| o.operator.as_str())
+            .collect();
+        assert_eq!(operators, vec!["BDC", "q", "cm", "TJ", "Do", "Q", "EMC"]);
+        assert_eq!(
+            stream.operations[0],
+            Operation {
+                operator: String::from("BDC"),
+                operands: vec![
+                    Operand::Name(String::from("P")),
+                    Operand::Dictionary(vec![
+                        (String::from("MCID"), Operand::Number(Number::Integer(0))),
+                        (String::from("Alt"), Operand::String(b"a)b".to_vec())),
+                    ])
+                ],
+                offset: 10,
+            }
+        );
+        // offsets of the first operand, or of the operator without operands
+        assert_eq!(stream.operations[1].offset, 41);
+        assert_eq!(stream.operations[2].offset, 43);
+        assert_eq!(
+            stream.operations[3].operands,
+            vec![Operand::Array(vec![
+                Operand::String(b"A".to_vec()),
+                Operand::Number(Number::Integer(-250)),
+                Operand::HexString(vec![1, 2]),
+            ])]
+        );
+        // serialized operations are parsed back to the same operations
+        let bytes = stream.to_bytes();
+        assert_eq!(
+            String::from_utf8_lossy(&bytes),
+            "/P <</MCID 0/Alt (a\\)b)>> BDC\nq\n0.5 0 0 0.5 0 0 cm\n[(A) -250 <0102>] TJ\n/Name#20x Do\nQ\nEMC\n"
+        );
+        let reparsed = ContentStream::try_from(bytes.as_slice()).unwrap();
+        for (a, b) in reparsed.operations.iter().zip(&stream.operations) {
+            assert_eq!((&a.operator, &a.operands), (&b.operator, &b.operands));
+        }
+        let built = ContentStream {
+            operations: vec![Operation::new(
+                "Tf",
+                vec![
+                    Operand::Name(String::from("F1")),
+                    Operand::Number(Number::Real(0.25)),
+                ],
+            )],
+        };
+        assert_eq!(built.to_bytes(), b"/F1 0.25 Tf\n");
+        assert!(ContentStream::try_from(b"[1 2".as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_inline_images() {
+        // 2x2 RGB image whose data contains EI
+        let raw =
+            b"q BI /W 2 /H 2 /BPC 8 /CS /RGB ID \x00EI \xff\x01\x02\x03\x04\x05\x06\x07\nEI Q"
+                .as_slice();
+        let mut content = Content::from(raw);
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::LowerQ)));
+        let image = match content.next() {
+            Some(Ok(GraphicsInstruction::InlineImage(image))) => image,
+            i => panic!("{i:?}"),
+        };
+        assert_eq!(
+            image.get("ColorSpace"),
+            Some(&Operand::Name(String::from("DeviceRGB")))
+        );
+        assert_eq!(
+            (image.width(), image.height(), image.bits_per_component()),
+            (Some(2), Some(2), Some(8))
+        );
+        assert_eq!(image.data.len(), 12);
+        assert_eq!(&image.data[..4], b"\x00EI ");
+        assert_eq!(content.next(), Some(Ok(GraphicsInstruction::UpperQ)));
+        assert_eq!(content.next(), None);
+
+        // filtered data ends before an EI followed by operators
+        let raw = b"BI /W 8 /H 1 /F [/AHx] /IM true ID\nEI> 00FF>\nEI\nBT ET".as_slice();
+        let stream = ContentStream::try_from(raw).unwrap();
+        let operators: Vec<&str> = stream
+            .operations
+            .iter()
+            .map(|o| o.operator.as_str())
+            .collect();
+        assert_eq!(operators, vec!["BI", "BT", "ET"]);
+        let image = match stream.operations[0].operands.as_slice() {
+            [Operand::Dictionary(parameters), Operand::String(data)] => {
+                InlineImage::new(parameters, data.clone())
+            }
+            o => panic!("{o:?}"),
+        };
+        assert_eq!(image.filters(), vec![String::from("ASCIIHexDecode")]);
+        assert_eq!(image.bits_per_component(), Some(1));
+        assert_eq!(image.data, b"EI> 00FF>");
+        // inline images are written back with their data
+        let bytes = stream.to_bytes();
+        assert_eq!(
+            bytes,
+            b"BI /W 8 /H 1 /F [/AHx] /IM true ID EI> 00FF>\nEI\nBT\nET\n"
+        );
+        assert_eq!(ContentStream::try_from(bytes.as_slice()).unwrap(), {
+            let mut reparsed = stream.clone();
+            reparsed.operations[1].offset = 48;
+            reparsed.operations[2].offset = 51;
+            reparsed
+        });
+        assert!(ContentStream::try_from(b"BI /W 1 ID \x00\x01".as_slice()).is_err());
+        let empty = ContentStream::try_from(b"BI /W 0 /F /DCT ID EI".as_slice()).unwrap();
+        assert_eq!(empty.operations[0].operands[1], Operand::String(vec![]));
+        // lengths overflowing or beyond the end of the stream are ignored, the data ends at EI
+        for raw in [
+            b"BI /W 2147483647 /H 2147483647 /BPC 16 /CS /CMYK ID \x00\x01 EI Q".as_slice(),
+            b"BI /W 2147483647 /H 2 /BPC 8 /IM true ID \x00\x01 EI Q",
+        ] {
+            let stream = ContentStream::try_from(raw).unwrap();
+            assert_eq!(
+                stream.operations[0].operands[1],
+                Operand::String(vec![0, 1])
+            );
+            assert_eq!(stream.operations[1].operator, "Q");
+        }
+    }
+
+    #[test]
+    fn test_cm_concatenation() {
+        let raw = b"2 0 0 2 0 0 cm 1 0 0 1 5 10 cm".as_slice();
+        let mut content = Content::from(raw);
+        for i in content.by_ref() {
+            i.unwrap();
+        }
+        assert_eq!(
+            content.graphic_state.ctm,
+            Matrix::new(2.0, 0.0, 0.0, 2.0, 10.0, 20.0)
+        );
+    }
 }
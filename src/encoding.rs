@@ -0,0 +1,1097 @@
+use crate::{object::Name, Error};
+use std::collections::HashMap;
+
+// Base encoding of a simple font (page 266)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BaseEncoding {
+    Standard,
+    MacRoman,
+    WinAnsi,
+    PdfDoc,
+}
+
+impl BaseEncoding {
+    pub fn glyph_name(&self, code: u8) -> Option<&'static str> {
+        let name = match self {
+            BaseEncoding::Standard => STANDARD[code as usize],
+            BaseEncoding::MacRoman => MAC_ROMAN[code as usize],
+            BaseEncoding::WinAnsi => WIN_ANSI[code as usize],
+            BaseEncoding::PdfDoc => PDF_DOC[code as usize],
+        };
+        (!name.is_empty()).then_some(name)
+    }
+}
+
+impl TryFrom<&str> for BaseEncoding {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "StandardEncoding" => Ok(BaseEncoding::Standard),
+            "MacRomanEncoding" => Ok(BaseEncoding::MacRoman),
+            "WinAnsiEncoding" => Ok(BaseEncoding::WinAnsi),
+            "PDFDocEncoding" => Ok(BaseEncoding::PdfDoc),
+            e => Err(Error::font(format!("Encoding {e:?} is not supported"))),
+        }
+    }
+}
+
+// Glyph names of the codes of a simple font, a base encoding modified by a Differences array
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Encoding {
+    pub base: Option<BaseEncoding>,
+    pub differences: HashMap<u8, Name>,
+}
+
+impl Encoding {
+    pub fn glyph_name(&self, code: u8) -> Option<&str> {
+        match self.differences.get(&code) {
+            Some(name) => Some(name),
+            None => self.base.and_then(|base| base.glyph_name(code)),
+        }
+    }
+
+    pub fn unicode(&self, code: u8) -> Option<String> {
+        self.glyph_name(code).and_then(glyph_unicode)
+    }
+}
+
+// Unicode text of a glyph name, following the Adobe Glyph List specification
+// A suffix after a period is ignored and each component of a ligature name such as f_f_i is mapped
+// Names absent from the bundled list can be given as uniXXXX, with one or more groups of 4 digits, or uXXXX to uXXXXXX
+pub fn glyph_unicode(name: &str) -> Option<String> {
+    let name = name.split('.').next()?;
+    let text = name
+        .split('_')
+        .filter_map(component_unicode)
+        .collect::<String>();
+    (!text.is_empty()).then_some(text)
+}
+
+fn component_unicode(name: &str) -> Option<String> {
+    if let Ok(i) = GLYPH_LIST.binary_search_by(|(n, _)| (*n).cmp(name)) {
+        return Some(String::from(GLYPH_LIST[i].1));
+    }
+    let hex = |digits: &[u8]| match digits.iter().all(u8::is_ascii_hexdigit) {
+        true => u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16)
+            .ok()
+            .and_then(char::from_u32),
+        false => None,
+    };
+    match (name.strip_prefix("uni"), name.strip_prefix('u')) {
+        (Some(digits), _) if !digits.is_empty() && digits.len() % 4 == 0 => {
+            digits.as_bytes().chunks(4).map(hex).collect()
+        }
+        (_, Some(digits)) if (4..=6).contains(&digits.len()) => {
+            hex(digits.as_bytes()).map(String::from)
+        }
+        _ => None,
+    }
+}
+
+// StandardEncoding, the built-in encoding of the standard Latin fonts
+#[rustfmt::skip]
+const STANDARD: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quoteright",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "quoteleft", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "exclamdown", "cent", "sterling", "fraction", "yen", "florin", "section",
+    "currency", "quotesingle", "quotedblleft", "guillemotleft", "guilsinglleft", "guilsinglright", "fi", "fl",
+    "", "endash", "dagger", "daggerdbl", "periodcentered", "", "paragraph", "bullet",
+    "quotesinglbase", "quotedblbase", "quotedblright", "guillemotright", "ellipsis", "perthousand", "", "questiondown",
+    "", "grave", "acute", "circumflex", "tilde", "macron", "breve", "dotaccent",
+    "dieresis", "", "ring", "cedilla", "", "hungarumlaut", "ogonek", "caron",
+    "emdash", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "AE", "", "ordfeminine", "", "", "", "",
+    "Lslash", "Oslash", "OE", "ordmasculine", "", "", "", "",
+    "", "ae", "", "", "", "dotlessi", "", "",
+    "lslash", "oslash", "oe", "germandbls", "", "", "", "",
+];
+
+// MacRomanEncoding
+#[rustfmt::skip]
+const MAC_ROMAN: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis", "aacute",
+    "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute", "egrave",
+    "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde", "oacute",
+    "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex", "udieresis",
+    "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph", "germandbls",
+    "registered", "copyright", "trademark", "acute", "dieresis", "", "AE", "Oslash",
+    "", "plusminus", "", "", "yen", "mu", "", "",
+    "", "", "", "ordfeminine", "ordmasculine", "", "ae", "oslash",
+    "questiondown", "exclamdown", "logicalnot", "", "florin", "", "", "guillemotleft",
+    "guillemotright", "ellipsis", "space", "Agrave", "Atilde", "Otilde", "OE", "oe",
+    "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft", "quoteright", "divide", "",
+    "ydieresis", "Ydieresis", "fraction", "currency", "guilsinglleft", "guilsinglright", "fi", "fl",
+    "daggerdbl", "periodcentered", "quotesinglbase", "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute",
+    "Edieresis", "Egrave", "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex",
+    "", "Ograve", "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde",
+    "macron", "breve", "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron",
+];
+
+// WinAnsiEncoding, code 240 is a non-breaking space and 255 a soft hyphen
+#[rustfmt::skip]
+const WIN_ANSI: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "Euro", "", "quotesinglbase", "florin", "quotedblbase", "ellipsis", "dagger", "daggerdbl",
+    "circumflex", "perthousand", "Scaron", "guilsinglleft", "OE", "", "Zcaron", "",
+    "", "quoteleft", "quoteright", "quotedblleft", "quotedblright", "bullet", "endash", "emdash",
+    "tilde", "trademark", "scaron", "guilsinglright", "oe", "", "zcaron", "Ydieresis",
+    "space", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "dieresis", "copyright", "ordfeminine", "guillemotleft", "logicalnot", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "ordmasculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adieresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Edieresis", "Igrave", "Iacute", "Icircumflex", "Idieresis",
+    "Eth", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odieresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udieresis", "Yacute", "Thorn", "germandbls",
+    "agrave", "aacute", "acircumflex", "atilde", "adieresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "edieresis", "igrave", "iacute", "icircumflex", "idieresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odieresis", "divide",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udieresis", "yacute", "thorn", "ydieresis",
+];
+
+// PDFDocEncoding
+#[rustfmt::skip]
+const PDF_DOC: [&str; 256] = [
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "", "", "", "", "", "", "", "",
+    "breve", "caron", "circumflex", "dotaccent", "hungarumlaut", "ogonek", "ring", "tilde",
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "quotesingle",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question",
+    "at", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore",
+    "grave", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "",
+    "bullet", "dagger", "daggerdbl", "ellipsis", "emdash", "endash", "florin", "fraction",
+    "guilsinglleft", "guilsinglright", "minus", "perthousand", "quotedblbase", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "quotesinglbase", "trademark", "fi", "fl", "Lslash", "OE", "Scaron",
+    "Ydieresis", "Zcaron", "dotlessi", "lslash", "oe", "scaron", "zcaron", "",
+    "Euro", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "dieresis", "copyright", "ordfeminine", "guillemotleft", "logicalnot", "", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "ordmasculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adieresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Edieresis", "Igrave", "Iacute", "Icircumflex", "Idieresis",
+    "Eth", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odieresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udieresis", "Yacute", "Thorn", "germandbls",
+    "agrave", "aacute", "acircumflex", "atilde", "adieresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "edieresis", "igrave", "iacute", "icircumflex", "idieresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odieresis", "divide",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udieresis", "yacute", "thorn", "ydieresis",
+];
+
+// Unicode values of the glyph names of the Adobe Glyph List For New Fonts, with the Cyrillic names
+// (afii and descriptive) and the accented Greek names of the Adobe Glyph List
+// Other names of the Adobe Glyph List are not bundled
+#[rustfmt::skip]
+static GLYPH_LIST: [(&str, char); 791] = [
+    ("A", '\u{0041}'),
+    ("AE", '\u{00c6}'),
+    ("AEacute", '\u{01fc}'),
+    ("Aacute", '\u{00c1}'),
+    ("Abreve", '\u{0102}'),
+    ("Acircumflex", '\u{00c2}'),
+    ("Acyrillic", '\u{0410}'),
+    ("Adieresis", '\u{00c4}'),
+    ("Agrave", '\u{00c0}'),
+    ("Alpha", '\u{0391}'),
+    ("Alphatonos", '\u{0386}'),
+    ("Amacron", '\u{0100}'),
+    ("Aogonek", '\u{0104}'),
+    ("Aring", '\u{00c5}'),
+    ("Aringacute", '\u{01fa}'),
+    ("Atilde", '\u{00c3}'),
+    ("B", '\u{0042}'),
+    ("Becyrillic", '\u{0411}'),
+    ("Beta", '\u{0392}'),
+    ("C", '\u{0043}'),
+    ("Cacute", '\u{0106}'),
+    ("Ccaron", '\u{010c}'),
+    ("Ccedilla", '\u{00c7}'),
+    ("Ccircumflex", '\u{0108}'),
+    ("Cdotaccent", '\u{010a}'),
+    ("Checyrillic", '\u{0427}'),
+    ("Chi", '\u{03a7}'),
+    ("D", '\u{0044}'),
+    ("Dcaron", '\u{010e}'),
+    ("Dcroat", '\u{0110}'),
+    ("Decyrillic", '\u{0414}'),
+    ("Delta", '\u{2206}'),
+    ("Deltagreek", '\u{0394}'),
+    ("E", '\u{0045}'),
+    ("Eacute", '\u{00c9}'),
+    ("Ebreve", '\u{0114}'),
+    ("Ecaron", '\u{011a}'),
+    ("Ecircumflex", '\u{00ca}'),
+    ("Edieresis", '\u{00cb}'),
+    ("Edotaccent", '\u{0116}'),
+    ("Efcyrillic", '\u{0424}'),
+    ("Egrave", '\u{00c8}'),
+    ("Elcyrillic", '\u{041b}'),
+    ("Emacron", '\u{0112}'),
+    ("Emcyrillic", '\u{041c}'),
+    ("Encyrillic", '\u{041d}'),
+    ("Eng", '\u{014a}'),
+    ("Eogonek", '\u{0118}'),
+    ("Epsilon", '\u{0395}'),
+    ("Epsilontonos", '\u{0388}'),
+    ("Ercyrillic", '\u{0420}'),
+    ("Ereversedcyrillic", '\u{042d}'),
+    ("Escyrillic", '\u{0421}'),
+    ("Eta", '\u{0397}'),
+    ("Etatonos", '\u{0389}'),
+    ("Eth", '\u{00d0}'),
+    ("Euro", '\u{20ac}'),
+    ("F", '\u{0046}'),
+    ("G", '\u{0047}'),
+    ("Gamma", '\u{0393}'),
+    ("Gbreve", '\u{011e}'),
+    ("Gcaron", '\u{01e6}'),
+    ("Gcircumflex", '\u{011c}'),
+    ("Gcommaaccent", '\u{0122}'),
+    ("Gdotaccent", '\u{0120}'),
+    ("Gecyrillic", '\u{0413}'),
+    ("H", '\u{0048}'),
+    ("H18533", '\u{25cf}'),
+    ("H18543", '\u{25aa}'),
+    ("H18551", '\u{25ab}'),
+    ("H22073", '\u{25a1}'),
+    ("Hardsigncyrillic", '\u{042a}'),
+    ("Hbar", '\u{0126}'),
+    ("Hcircumflex", '\u{0124}'),
+    ("I", '\u{0049}'),
+    ("IAcyrillic", '\u{042f}'),
+    ("IJ", '\u{0132}'),
+    ("IUcyrillic", '\u{042e}'),
+    ("Iacute", '\u{00cd}'),
+    ("Ibreve", '\u{012c}'),
+    ("Icircumflex", '\u{00ce}'),
+    ("Idieresis", '\u{00cf}'),
+    ("Idotaccent", '\u{0130}'),
+    ("Iecyrillic", '\u{0415}'),
+    ("Ifraktur", '\u{2111}'),
+    ("Igrave", '\u{00cc}'),
+    ("Iicyrillic", '\u{0418}'),
+    ("Iishortcyrillic", '\u{0419}'),
+    ("Imacron", '\u{012a}'),
+    ("Iocyrillic", '\u{0401}'),
+    ("Iogonek", '\u{012e}'),
+    ("Iota", '\u{0399}'),
+    ("Iotadieresis", '\u{03aa}'),
+    ("Iotatonos", '\u{038a}'),
+    ("Itilde", '\u{0128}'),
+    ("J", '\u{004a}'),
+    ("Jcircumflex", '\u{0134}'),
+    ("K", '\u{004b}'),
+    ("Kacyrillic", '\u{041a}'),
+    ("Kappa", '\u{039a}'),
+    ("Kcommaaccent", '\u{0136}'),
+    ("Khacyrillic", '\u{0425}'),
+    ("L", '\u{004c}'),
+    ("Lacute", '\u{0139}'),
+    ("Lambda", '\u{039b}'),
+    ("Lcaron", '\u{013d}'),
+    ("Lcommaaccent", '\u{013b}'),
+    ("Ldot", '\u{013f}'),
+    ("Lslash", '\u{0141}'),
+    ("M", '\u{004d}'),
+    ("Mu", '\u{039c}'),
+    ("N", '\u{004e}'),
+    ("Nacute", '\u{0143}'),
+    ("Ncaron", '\u{0147}'),
+    ("Ncommaaccent", '\u{0145}'),
+    ("Ntilde", '\u{00d1}'),
+    ("Nu", '\u{039d}'),
+    ("O", '\u{004f}'),
+    ("OE", '\u{0152}'),
+    ("Oacute", '\u{00d3}'),
+    ("Obreve", '\u{014e}'),
+    ("Ocircumflex", '\u{00d4}'),
+    ("Ocyrillic", '\u{041e}'),
+    ("Odieresis", '\u{00d6}'),
+    ("Ograve", '\u{00d2}'),
+    ("Ohm", '\u{2126}'),
+    ("Ohorn", '\u{01a0}'),
+    ("Ohungarumlaut", '\u{0150}'),
+    ("Omacron", '\u{014c}'),
+    ("Omega", '\u{2126}'),
+    ("Omegagreek", '\u{03a9}'),
+    ("Omegatonos", '\u{038f}'),
+    ("Omicron", '\u{039f}'),
+    ("Omicrontonos", '\u{038c}'),
+    ("Oslash", '\u{00d8}'),
+    ("Oslashacute", '\u{01fe}'),
+    ("Otilde", '\u{00d5}'),
+    ("P", '\u{0050}'),
+    ("Pecyrillic", '\u{041f}'),
+    ("Phi", '\u{03a6}'),
+    ("Pi", '\u{03a0}'),
+    ("Psi", '\u{03a8}'),
+    ("Q", '\u{0051}'),
+    ("R", '\u{0052}'),
+    ("Racute", '\u{0154}'),
+    ("Rcaron", '\u{0158}'),
+    ("Rcommaaccent", '\u{0156}'),
+    ("Rfraktur", '\u{211c}'),
+    ("Rho", '\u{03a1}'),
+    ("S", '\u{0053}'),
+    ("SF010000", '\u{250c}'),
+    ("SF020000", '\u{2514}'),
+    ("SF030000", '\u{2510}'),
+    ("SF040000", '\u{2518}'),
+    ("SF050000", '\u{253c}'),
+    ("SF060000", '\u{252c}'),
+    ("SF070000", '\u{2534}'),
+    ("SF080000", '\u{251c}'),
+    ("SF090000", '\u{2524}'),
+    ("SF100000", '\u{2500}'),
+    ("SF110000", '\u{2502}'),
+    ("Sacute", '\u{015a}'),
+    ("Scaron", '\u{0160}'),
+    ("Scedilla", '\u{015e}'),
+    ("Scircumflex", '\u{015c}'),
+    ("Scommaaccent", '\u{0218}'),
+    ("Shacyrillic", '\u{0428}'),
+    ("Shchacyrillic", '\u{0429}'),
+    ("Sigma", '\u{03a3}'),
+    ("Softsigncyrillic", '\u{042c}'),
+    ("T", '\u{0054}'),
+    ("Tau", '\u{03a4}'),
+    ("Tbar", '\u{0166}'),
+    ("Tcaron", '\u{0164}'),
+    ("Tcedilla", '\u{0162}'),
+    ("Tcommaaccent", '\u{0162}'),
+    ("Tecyrillic", '\u{0422}'),
+    ("Theta", '\u{0398}'),
+    ("Thorn", '\u{00de}'),
+    ("Tsecyrillic", '\u{0426}'),
+    ("U", '\u{0055}'),
+    ("Uacute", '\u{00da}'),
+    ("Ubreve", '\u{016c}'),
+    ("Ucircumflex", '\u{00db}'),
+    ("Ucyrillic", '\u{0423}'),
+    ("Udieresis", '\u{00dc}'),
+    ("Ugrave", '\u{00d9}'),
+    ("Uhorn", '\u{01af}'),
+    ("Uhungarumlaut", '\u{0170}'),
+    ("Umacron", '\u{016a}'),
+    ("Uogonek", '\u{0172}'),
+    ("Upsilon", '\u{03a5}'),
+    ("Upsilon1", '\u{03d2}'),
+    ("Upsilondieresis", '\u{03ab}'),
+    ("Upsilontonos", '\u{038e}'),
+    ("Uring", '\u{016e}'),
+    ("Utilde", '\u{0168}'),
+    ("V", '\u{0056}'),
+    ("Vecyrillic", '\u{0412}'),
+    ("W", '\u{0057}'),
+    ("Wacute", '\u{1e82}'),
+    ("Wcircumflex", '\u{0174}'),
+    ("Wdieresis", '\u{1e84}'),
+    ("Wgrave", '\u{1e80}'),
+    ("X", '\u{0058}'),
+    ("Xi", '\u{039e}'),
+    ("Y", '\u{0059}'),
+    ("Yacute", '\u{00dd}'),
+    ("Ycircumflex", '\u{0176}'),
+    ("Ydieresis", '\u{0178}'),
+    ("Yericyrillic", '\u{042b}'),
+    ("Ygrave", '\u{1ef2}'),
+    ("Z", '\u{005a}'),
+    ("Zacute", '\u{0179}'),
+    ("Zcaron", '\u{017d}'),
+    ("Zdotaccent", '\u{017b}'),
+    ("Zecyrillic", '\u{0417}'),
+    ("Zeta", '\u{0396}'),
+    ("Zhecyrillic", '\u{0416}'),
+    ("a", '\u{0061}'),
+    ("aacute", '\u{00e1}'),
+    ("abreve", '\u{0103}'),
+    ("acircumflex", '\u{00e2}'),
+    ("acute", '\u{00b4}'),
+    ("acutecomb", '\u{0301}'),
+    ("acyrillic", '\u{0430}'),
+    ("adieresis", '\u{00e4}'),
+    ("ae", '\u{00e6}'),
+    ("aeacute", '\u{01fd}'),
+    ("afii00208", '\u{2015}'),
+    ("afii08941", '\u{20a4}'),
+    ("afii10017", '\u{0410}'),
+    ("afii10018", '\u{0411}'),
+    ("afii10019", '\u{0412}'),
+    ("afii10020", '\u{0413}'),
+    ("afii10021", '\u{0414}'),
+    ("afii10022", '\u{0415}'),
+    ("afii10023", '\u{0401}'),
+    ("afii10024", '\u{0416}'),
+    ("afii10025", '\u{0417}'),
+    ("afii10026", '\u{0418}'),
+    ("afii10027", '\u{0419}'),
+    ("afii10028", '\u{041a}'),
+    ("afii10029", '\u{041b}'),
+    ("afii10030", '\u{041c}'),
+    ("afii10031", '\u{041d}'),
+    ("afii10032", '\u{041e}'),
+    ("afii10033", '\u{041f}'),
+    ("afii10034", '\u{0420}'),
+    ("afii10035", '\u{0421}'),
+    ("afii10036", '\u{0422}'),
+    ("afii10037", '\u{0423}'),
+    ("afii10038", '\u{0424}'),
+    ("afii10039", '\u{0425}'),
+    ("afii10040", '\u{0426}'),
+    ("afii10041", '\u{0427}'),
+    ("afii10042", '\u{0428}'),
+    ("afii10043", '\u{0429}'),
+    ("afii10044", '\u{042a}'),
+    ("afii10045", '\u{042b}'),
+    ("afii10046", '\u{042c}'),
+    ("afii10047", '\u{042d}'),
+    ("afii10048", '\u{042e}'),
+    ("afii10049", '\u{042f}'),
+    ("afii10050", '\u{0490}'),
+    ("afii10051", '\u{0402}'),
+    ("afii10052", '\u{0403}'),
+    ("afii10053", '\u{0404}'),
+    ("afii10054", '\u{0405}'),
+    ("afii10055", '\u{0406}'),
+    ("afii10056", '\u{0407}'),
+    ("afii10057", '\u{0408}'),
+    ("afii10058", '\u{0409}'),
+    ("afii10059", '\u{040a}'),
+    ("afii10060", '\u{040b}'),
+    ("afii10061", '\u{040c}'),
+    ("afii10062", '\u{040e}'),
+    ("afii10065", '\u{0430}'),
+    ("afii10066", '\u{0431}'),
+    ("afii10067", '\u{0432}'),
+    ("afii10068", '\u{0433}'),
+    ("afii10069", '\u{0434}'),
+    ("afii10070", '\u{0435}'),
+    ("afii10071", '\u{0451}'),
+    ("afii10072", '\u{0436}'),
+    ("afii10073", '\u{0437}'),
+    ("afii10074", '\u{0438}'),
+    ("afii10075", '\u{0439}'),
+    ("afii10076", '\u{043a}'),
+    ("afii10077", '\u{043b}'),
+    ("afii10078", '\u{043c}'),
+    ("afii10079", '\u{043d}'),
+    ("afii10080", '\u{043e}'),
+    ("afii10081", '\u{043f}'),
+    ("afii10082", '\u{0440}'),
+    ("afii10083", '\u{0441}'),
+    ("afii10084", '\u{0442}'),
+    ("afii10085", '\u{0443}'),
+    ("afii10086", '\u{0444}'),
+    ("afii10087", '\u{0445}'),
+    ("afii10088", '\u{0446}'),
+    ("afii10089", '\u{0447}'),
+    ("afii10090", '\u{0448}'),
+    ("afii10091", '\u{0449}'),
+    ("afii10092", '\u{044a}'),
+    ("afii10093", '\u{044b}'),
+    ("afii10094", '\u{044c}'),
+    ("afii10095", '\u{044d}'),
+    ("afii10096", '\u{044e}'),
+    ("afii10097", '\u{044f}'),
+    ("afii10098", '\u{0491}'),
+    ("afii10099", '\u{0452}'),
+    ("afii10100", '\u{0453}'),
+    ("afii10101", '\u{0454}'),
+    ("afii10102", '\u{0455}'),
+    ("afii10103", '\u{0456}'),
+    ("afii10104", '\u{0457}'),
+    ("afii10105", '\u{0458}'),
+    ("afii10106", '\u{0459}'),
+    ("afii10107", '\u{045a}'),
+    ("afii10108", '\u{045b}'),
+    ("afii10109", '\u{045c}'),
+    ("afii10110", '\u{045e}'),
+    ("afii10145", '\u{040f}'),
+    ("afii10146", '\u{0462}'),
+    ("afii10147", '\u{0472}'),
+    ("afii10148", '\u{0474}'),
+    ("afii10193", '\u{045f}'),
+    ("afii10194", '\u{0463}'),
+    ("afii10195", '\u{0473}'),
+    ("afii10196", '\u{0475}'),
+    ("afii10846", '\u{04d9}'),
+    ("afii299", '\u{200e}'),
+    ("afii300", '\u{200f}'),
+    ("afii301", '\u{200d}'),
+    ("afii57929", '\u{02bc}'),
+    ("afii61248", '\u{2105}'),
+    ("afii61289", '\u{2113}'),
+    ("afii61352", '\u{2116}'),
+    ("afii61664", '\u{200c}'),
+    ("afii64937", '\u{02bd}'),
+    ("agrave", '\u{00e0}'),
+    ("aleph", '\u{2135}'),
+    ("alpha", '\u{03b1}'),
+    ("alphatonos", '\u{03ac}'),
+    ("amacron", '\u{0101}'),
+    ("ampersand", '\u{0026}'),
+    ("angle", '\u{2220}'),
+    ("angleleft", '\u{2329}'),
+    ("angleright", '\u{232a}'),
+    ("anoteleia", '\u{0387}'),
+    ("aogonek", '\u{0105}'),
+    ("approxequal", '\u{2248}'),
+    ("aring", '\u{00e5}'),
+    ("aringacute", '\u{01fb}'),
+    ("arrowboth", '\u{2194}'),
+    ("arrowdblboth", '\u{21d4}'),
+    ("arrowdbldown", '\u{21d3}'),
+    ("arrowdblleft", '\u{21d0}'),
+    ("arrowdblright", '\u{21d2}'),
+    ("arrowdblup", '\u{21d1}'),
+    ("arrowdown", '\u{2193}'),
+    ("arrowleft", '\u{2190}'),
+    ("arrowright", '\u{2192}'),
+    ("arrowup", '\u{2191}'),
+    ("arrowupdn", '\u{2195}'),
+    ("arrowupdnbse", '\u{21a8}'),
+    ("asciicircum", '\u{005e}'),
+    ("asciitilde", '\u{007e}'),
+    ("asterisk", '\u{002a}'),
+    ("asteriskmath", '\u{2217}'),
+    ("at", '\u{0040}'),
+    ("atilde", '\u{00e3}'),
+    ("b", '\u{0062}'),
+    ("backslash", '\u{005c}'),
+    ("bar", '\u{007c}'),
+    ("becyrillic", '\u{0431}'),
+    ("beta", '\u{03b2}'),
+    ("block", '\u{2588}'),
+    ("braceleft", '\u{007b}'),
+    ("braceright", '\u{007d}'),
+    ("bracketleft", '\u{005b}'),
+    ("bracketright", '\u{005d}'),
+    ("breve", '\u{02d8}'),
+    ("brokenbar", '\u{00a6}'),
+    ("bullet", '\u{2022}'),
+    ("c", '\u{0063}'),
+    ("cacute", '\u{0107}'),
+    ("caron", '\u{02c7}'),
+    ("carriagereturn", '\u{21b5}'),
+    ("ccaron", '\u{010d}'),
+    ("ccedilla", '\u{00e7}'),
+    ("ccircumflex", '\u{0109}'),
+    ("cdotaccent", '\u{010b}'),
+    ("cedilla", '\u{00b8}'),
+    ("cent", '\u{00a2}'),
+    ("checyrillic", '\u{0447}'),
+    ("chi", '\u{03c7}'),
+    ("circle", '\u{25cb}'),
+    ("circlemultiply", '\u{2297}'),
+    ("circleplus", '\u{2295}'),
+    ("circumflex", '\u{02c6}'),
+    ("club", '\u{2663}'),
+    ("colon", '\u{003a}'),
+    ("colonmonetary", '\u{20a1}'),
+    ("comma", '\u{002c}'),
+    ("congruent", '\u{2245}'),
+    ("copyright", '\u{00a9}'),
+    ("currency", '\u{00a4}'),
+    ("d", '\u{0064}'),
+    ("dagger", '\u{2020}'),
+    ("daggerdbl", '\u{2021}'),
+    ("dcaron", '\u{010f}'),
+    ("dcroat", '\u{0111}'),
+    ("decyrillic", '\u{0434}'),
+    ("degree", '\u{00b0}'),
+    ("delta", '\u{03b4}'),
+    ("diamond", '\u{2666}'),
+    ("dieresis", '\u{00a8}'),
+    ("dieresistonos", '\u{0385}'),
+    ("divide", '\u{00f7}'),
+    ("dkshade", '\u{2593}'),
+    ("dnblock", '\u{2584}'),
+    ("dollar", '\u{0024}'),
+    ("dong", '\u{20ab}'),
+    ("dotaccent", '\u{02d9}'),
+    ("dotbelowcomb", '\u{0323}'),
+    ("dotlessi", '\u{0131}'),
+    ("dotlessj", '\u{0237}'),
+    ("dotmath", '\u{22c5}'),
+    ("e", '\u{0065}'),
+    ("eacute", '\u{00e9}'),
+    ("ebreve", '\u{0115}'),
+    ("ecaron", '\u{011b}'),
+    ("ecircumflex", '\u{00ea}'),
+    ("edieresis", '\u{00eb}'),
+    ("edotaccent", '\u{0117}'),
+    ("efcyrillic", '\u{0444}'),
+    ("egrave", '\u{00e8}'),
+    ("eight", '\u{0038}'),
+    ("eightinferior", '\u{2088}'),
+    ("eightsuperior", '\u{2078}'),
+    ("elcyrillic", '\u{043b}'),
+    ("element", '\u{2208}'),
+    ("ellipsis", '\u{2026}'),
+    ("emacron", '\u{0113}'),
+    ("emcyrillic", '\u{043c}'),
+    ("emdash", '\u{2014}'),
+    ("emptyset", '\u{2205}'),
+    ("encyrillic", '\u{043d}'),
+    ("endash", '\u{2013}'),
+    ("eng", '\u{014b}'),
+    ("eogonek", '\u{0119}'),
+    ("epsilon", '\u{03b5}'),
+    ("epsilontonos", '\u{03ad}'),
+    ("equal", '\u{003d}'),
+    ("equivalence", '\u{2261}'),
+    ("ercyrillic", '\u{0440}'),
+    ("ereversedcyrillic", '\u{044d}'),
+    ("escyrillic", '\u{0441}'),
+    ("estimated", '\u{212e}'),
+    ("eta", '\u{03b7}'),
+    ("etatonos", '\u{03ae}'),
+    ("eth", '\u{00f0}'),
+    ("exclam", '\u{0021}'),
+    ("exclamdbl", '\u{203c}'),
+    ("exclamdown", '\u{00a1}'),
+    ("existential", '\u{2203}'),
+    ("f", '\u{0066}'),
+    ("female", '\u{2640}'),
+    ("ff", '\u{fb00}'),
+    ("ffi", '\u{fb03}'),
+    ("ffl", '\u{fb04}'),
+    ("fi", '\u{fb01}'),
+    ("figuredash", '\u{2012}'),
+    ("filledbox", '\u{25a0}'),
+    ("filledrect", '\u{25ac}'),
+    ("five", '\u{0035}'),
+    ("fiveeighths", '\u{215d}'),
+    ("fiveinferior", '\u{2085}'),
+    ("fivesuperior", '\u{2075}'),
+    ("fl", '\u{fb02}'),
+    ("florin", '\u{0192}'),
+    ("four", '\u{0034}'),
+    ("fourinferior", '\u{2084}'),
+    ("foursuperior", '\u{2074}'),
+    ("fraction", '\u{2044}'),
+    ("franc", '\u{20a3}'),
+    ("g", '\u{0067}'),
+    ("gamma", '\u{03b3}'),
+    ("gbreve", '\u{011f}'),
+    ("gcaron", '\u{01e7}'),
+    ("gcircumflex", '\u{011d}'),
+    ("gcommaaccent", '\u{0123}'),
+    ("gdotaccent", '\u{0121}'),
+    ("gecyrillic", '\u{0433}'),
+    ("germandbls", '\u{00df}'),
+    ("gradient", '\u{2207}'),
+    ("grave", '\u{0060}'),
+    ("gravecomb", '\u{0300}'),
+    ("greater", '\u{003e}'),
+    ("greaterequal", '\u{2265}'),
+    ("guillemotleft", '\u{00ab}'),
+    ("guillemotright", '\u{00bb}'),
+    ("guilsinglleft", '\u{2039}'),
+    ("guilsinglright", '\u{203a}'),
+    ("h", '\u{0068}'),
+    ("hardsigncyrillic", '\u{044a}'),
+    ("hbar", '\u{0127}'),
+    ("hcircumflex", '\u{0125}'),
+    ("heart", '\u{2665}'),
+    ("hookabovecomb", '\u{0309}'),
+    ("house", '\u{2302}'),
+    ("hungarumlaut", '\u{02dd}'),
+    ("hyphen", '\u{002d}'),
+    ("i", '\u{0069}'),
+    ("iacute", '\u{00ed}'),
+    ("iacyrillic", '\u{044f}'),
+    ("ibreve", '\u{012d}'),
+    ("icircumflex", '\u{00ee}'),
+    ("idieresis", '\u{00ef}'),
+    ("iecyrillic", '\u{0435}'),
+    ("igrave", '\u{00ec}'),
+    ("iicyrillic", '\u{0438}'),
+    ("iishortcyrillic", '\u{0439}'),
+    ("ij", '\u{0133}'),
+    ("imacron", '\u{012b}'),
+    ("increment", '\u{2206}'),
+    ("infinity", '\u{221e}'),
+    ("integral", '\u{222b}'),
+    ("integralbt", '\u{2321}'),
+    ("integraltp", '\u{2320}'),
+    ("intersection", '\u{2229}'),
+    ("invbullet", '\u{25d8}'),
+    ("invcircle", '\u{25d9}'),
+    ("invsmileface", '\u{263b}'),
+    ("iocyrillic", '\u{0451}'),
+    ("iogonek", '\u{012f}'),
+    ("iota", '\u{03b9}'),
+    ("iotadieresis", '\u{03ca}'),
+    ("iotadieresistonos", '\u{0390}'),
+    ("iotatonos", '\u{03af}'),
+    ("itilde", '\u{0129}'),
+    ("iucyrillic", '\u{044e}'),
+    ("j", '\u{006a}'),
+    ("jcircumflex", '\u{0135}'),
+    ("k", '\u{006b}'),
+    ("kacyrillic", '\u{043a}'),
+    ("kappa", '\u{03ba}'),
+    ("kcommaaccent", '\u{0137}'),
+    ("kgreenlandic", '\u{0138}'),
+    ("khacyrillic", '\u{0445}'),
+    ("l", '\u{006c}'),
+    ("lacute", '\u{013a}'),
+    ("lambda", '\u{03bb}'),
+    ("lcaron", '\u{013e}'),
+    ("lcommaaccent", '\u{013c}'),
+    ("ldot", '\u{0140}'),
+    ("less", '\u{003c}'),
+    ("lessequal", '\u{2264}'),
+    ("lfblock", '\u{258c}'),
+    ("lira", '\u{20a4}'),
+    ("logicaland", '\u{2227}'),
+    ("logicalnot", '\u{00ac}'),
+    ("logicalor", '\u{2228}'),
+    ("longs", '\u{017f}'),
+    ("lozenge", '\u{25ca}'),
+    ("lslash", '\u{0142}'),
+    ("ltshade", '\u{2591}'),
+    ("m", '\u{006d}'),
+    ("macron", '\u{00af}'),
+    ("male", '\u{2642}'),
+    ("micro", '\u{00b5}'),
+    ("minus", '\u{2212}'),
+    ("minute", '\u{2032}'),
+    ("mu", '\u{00b5}'),
+    ("mugreek", '\u{03bc}'),
+    ("multiply", '\u{00d7}'),
+    ("musicalnote", '\u{266a}'),
+    ("musicalnotedbl", '\u{266b}'),
+    ("n", '\u{006e}'),
+    ("nacute", '\u{0144}'),
+    ("napostrophe", '\u{0149}'),
+    ("nbspace", '\u{00a0}'),
+    ("ncaron", '\u{0148}'),
+    ("ncommaaccent", '\u{0146}'),
+    ("nine", '\u{0039}'),
+    ("nineinferior", '\u{2089}'),
+    ("ninesuperior", '\u{2079}'),
+    ("notelement", '\u{2209}'),
+    ("notequal", '\u{2260}'),
+    ("notsubset", '\u{2284}'),
+    ("nsuperior", '\u{207f}'),
+    ("ntilde", '\u{00f1}'),
+    ("nu", '\u{03bd}'),
+    ("numbersign", '\u{0023}'),
+    ("o", '\u{006f}'),
+    ("oacute", '\u{00f3}'),
+    ("obreve", '\u{014f}'),
+    ("ocircumflex", '\u{00f4}'),
+    ("ocyrillic", '\u{043e}'),
+    ("odieresis", '\u{00f6}'),
+    ("oe", '\u{0153}'),
+    ("ogonek", '\u{02db}'),
+    ("ograve", '\u{00f2}'),
+    ("ohorn", '\u{01a1}'),
+    ("ohungarumlaut", '\u{0151}'),
+    ("omacron", '\u{014d}'),
+    ("omega", '\u{03c9}'),
+    ("omega1", '\u{03d6}'),
+    ("omegatonos", '\u{03ce}'),
+    ("omicron", '\u{03bf}'),
+    ("omicrontonos", '\u{03cc}'),
+    ("one", '\u{0031}'),
+    ("onedotenleader", '\u{2024}'),
+    ("oneeighth", '\u{215b}'),
+    ("onehalf", '\u{00bd}'),
+    ("oneinferior", '\u{2081}'),
+    ("onequarter", '\u{00bc}'),
+    ("onesuperior", '\u{00b9}'),
+    ("onethird", '\u{2153}'),
+    ("openbullet", '\u{25e6}'),
+    ("ordfeminine", '\u{00aa}'),
+    ("ordmasculine", '\u{00ba}'),
+    ("orthogonal", '\u{221f}'),
+    ("oslash", '\u{00f8}'),
+    ("oslashacute", '\u{01ff}'),
+    ("otilde", '\u{00f5}'),
+    ("overscore", '\u{00af}'),
+    ("p", '\u{0070}'),
+    ("paragraph", '\u{00b6}'),
+    ("parenleft", '\u{0028}'),
+    ("parenleftinferior", '\u{208d}'),
+    ("parenleftsuperior", '\u{207d}'),
+    ("parenright", '\u{0029}'),
+    ("parenrightinferior", '\u{208e}'),
+    ("parenrightsuperior", '\u{207e}'),
+    ("partialdiff", '\u{2202}'),
+    ("pecyrillic", '\u{043f}'),
+    ("percent", '\u{0025}'),
+    ("period", '\u{002e}'),
+    ("periodcentered", '\u{00b7}'),
+    ("perpendicular", '\u{22a5}'),
+    ("perthousand", '\u{2030}'),
+    ("peseta", '\u{20a7}'),
+    ("phi", '\u{03c6}'),
+    ("phi1", '\u{03d5}'),
+    ("pi", '\u{03c0}'),
+    ("plus", '\u{002b}'),
+    ("plusminus", '\u{00b1}'),
+    ("prescription", '\u{211e}'),
+    ("product", '\u{220f}'),
+    ("propersubset", '\u{2282}'),
+    ("propersuperset", '\u{2283}'),
+    ("proportional", '\u{221d}'),
+    ("psi", '\u{03c8}'),
+    ("q", '\u{0071}'),
+    ("question", '\u{003f}'),
+    ("questiondown", '\u{00bf}'),
+    ("quotedbl", '\u{0022}'),
+    ("quotedblbase", '\u{201e}'),
+    ("quotedblleft", '\u{201c}'),
+    ("quotedblright", '\u{201d}'),
+    ("quoteleft", '\u{2018}'),
+    ("quotereversed", '\u{201b}'),
+    ("quoteright", '\u{2019}'),
+    ("quotesinglbase", '\u{201a}'),
+    ("quotesingle", '\u{0027}'),
+    ("r", '\u{0072}'),
+    ("racute", '\u{0155}'),
+    ("radical", '\u{221a}'),
+    ("rcaron", '\u{0159}'),
+    ("rcommaaccent", '\u{0157}'),
+    ("reflexsubset", '\u{2286}'),
+    ("reflexsuperset", '\u{2287}'),
+    ("registered", '\u{00ae}'),
+    ("revlogicalnot", '\u{2310}'),
+    ("rho", '\u{03c1}'),
+    ("ring", '\u{02da}'),
+    ("rtblock", '\u{2590}'),
+    ("s", '\u{0073}'),
+    ("sacute", '\u{015b}'),
+    ("scaron", '\u{0161}'),
+    ("scedilla", '\u{015f}'),
+    ("scircumflex", '\u{015d}'),
+    ("scommaaccent", '\u{0219}'),
+    ("second", '\u{2033}'),
+    ("section", '\u{00a7}'),
+    ("semicolon", '\u{003b}'),
+    ("seven", '\u{0037}'),
+    ("seveneighths", '\u{215e}'),
+    ("seveninferior", '\u{2087}'),
+    ("sevensuperior", '\u{2077}'),
+    ("sfthyphen", '\u{00ad}'),
+    ("shacyrillic", '\u{0448}'),
+    ("shade", '\u{2592}'),
+    ("shchacyrillic", '\u{0449}'),
+    ("sigma", '\u{03c3}'),
+    ("sigma1", '\u{03c2}'),
+    ("similar", '\u{223c}'),
+    ("six", '\u{0036}'),
+    ("sixinferior", '\u{2086}'),
+    ("sixsuperior", '\u{2076}'),
+    ("slash", '\u{002f}'),
+    ("smileface", '\u{263a}'),
+    ("softsigncyrillic", '\u{044c}'),
+    ("space", '\u{0020}'),
+    ("spade", '\u{2660}'),
+    ("sterling", '\u{00a3}'),
+    ("suchthat", '\u{220b}'),
+    ("summation", '\u{2211}'),
+    ("sun", '\u{263c}'),
+    ("t", '\u{0074}'),
+    ("tau", '\u{03c4}'),
+    ("tbar", '\u{0167}'),
+    ("tcaron", '\u{0165}'),
+    ("tcedilla", '\u{0163}'),
+    ("tcommaaccent", '\u{0163}'),
+    ("tecyrillic", '\u{0442}'),
+    ("therefore", '\u{2234}'),
+    ("theta", '\u{03b8}'),
+    ("theta1", '\u{03d1}'),
+    ("thorn", '\u{00fe}'),
+    ("three", '\u{0033}'),
+    ("threeeighths", '\u{215c}'),
+    ("threeinferior", '\u{2083}'),
+    ("threequarters", '\u{00be}'),
+    ("threesuperior", '\u{00b3}'),
+    ("tilde", '\u{02dc}'),
+    ("tildecomb", '\u{0303}'),
+    ("tonos", '\u{0384}'),
+    ("trademark", '\u{2122}'),
+    ("triagdn", '\u{25bc}'),
+    ("triaglf", '\u{25c4}'),
+    ("triagrt", '\u{25ba}'),
+    ("triagup", '\u{25b2}'),
+    ("tsecyrillic", '\u{0446}'),
+    ("two", '\u{0032}'),
+    ("twodotenleader", '\u{2025}'),
+    ("twoinferior", '\u{2082}'),
+    ("twosuperior", '\u{00b2}'),
+    ("twothirds", '\u{2154}'),
+    ("u", '\u{0075}'),
+    ("uacute", '\u{00fa}'),
+    ("ubreve", '\u{016d}'),
+    ("ucircumflex", '\u{00fb}'),
+    ("ucyrillic", '\u{0443}'),
+    ("udieresis", '\u{00fc}'),
+    ("ugrave", '\u{00f9}'),
+    ("uhorn", '\u{01b0}'),
+    ("uhungarumlaut", '\u{0171}'),
+    ("umacron", '\u{016b}'),
+    ("underscore", '\u{005f}'),
+    ("underscoredbl", '\u{2017}'),
+    ("union", '\u{222a}'),
+    ("universal", '\u{2200}'),
+    ("uogonek", '\u{0173}'),
+    ("upblock", '\u{2580}'),
+    ("upsilon", '\u{03c5}'),
+    ("upsilondieresis", '\u{03cb}'),
+    ("upsilondieresistonos", '\u{03b0}'),
+    ("upsilontonos", '\u{03cd}'),
+    ("uring", '\u{016f}'),
+    ("utilde", '\u{0169}'),
+    ("v", '\u{0076}'),
+    ("vecyrillic", '\u{0432}'),
+    ("w", '\u{0077}'),
+    ("wacute", '\u{1e83}'),
+    ("wcircumflex", '\u{0175}'),
+    ("wdieresis", '\u{1e85}'),
+    ("weierstrass", '\u{2118}'),
+    ("wgrave", '\u{1e81}'),
+    ("x", '\u{0078}'),
+    ("xi", '\u{03be}'),
+    ("y", '\u{0079}'),
+    ("yacute", '\u{00fd}'),
+    ("ycircumflex", '\u{0177}'),
+    ("ydieresis", '\u{00ff}'),
+    ("yen", '\u{00a5}'),
+    ("yericyrillic", '\u{044b}'),
+    ("ygrave", '\u{1ef3}'),
+    ("z", '\u{007a}'),
+    ("zacute", '\u{017a}'),
+    ("zcaron", '\u{017e}'),
+    ("zdotaccent", '\u{017c}'),
+    ("zecyrillic", '\u{0437}'),
+    ("zero", '\u{0030}'),
+    ("zeroinferior", '\u{2080}'),
+    ("zerosuperior", '\u{2070}'),
+    ("zeta", '\u{03b6}'),
+    ("zhecyrillic", '\u{0436}'),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_encodings() {
+        assert_eq!(BaseEncoding::Standard.glyph_name(0x27), Some("quoteright"));
+        assert_eq!(BaseEncoding::WinAnsi.glyph_name(0x27), Some("quotesingle"));
+        assert_eq!(BaseEncoding::WinAnsi.glyph_name(0x80), Some("Euro"));
+        assert_eq!(BaseEncoding::MacRoman.glyph_name(0x80), Some("Adieresis"));
+        assert_eq!(BaseEncoding::PdfDoc.glyph_name(0x93), Some("fi"));
+        assert_eq!(BaseEncoding::Standard.glyph_name(0x80), None);
+        assert!(BaseEncoding::try_from("MacExpertEncoding").is_err());
+    }
+
+    #[test]
+    fn test_glyph_unicode() {
+        let unicode = |name| glyph_unicode(name);
+        assert_eq!(unicode("fi").as_deref(), Some("\u{fb01}"));
+        assert_eq!(unicode("quoteright").as_deref(), Some("’"));
+        assert_eq!(unicode("uni20AC").as_deref(), Some("€"));
+        assert_eq!(unicode("uni00410042").as_deref(), Some("AB"));
+        assert_eq!(unicode("u1F600").as_deref(), Some("😀"));
+        assert_eq!(unicode("a.sc").as_deref(), Some("a"));
+        assert_eq!(unicode("f_f_i").as_deref(), Some("ffi"));
+        assert_eq!(unicode("T_h.liga").as_deref(), Some("Th"));
+        assert_eq!(unicode("uni0066_uni0069").as_deref(), Some("fi"));
+        // unknown components are dropped
+        assert_eq!(unicode("f_g42_i").as_deref(), Some("fi"));
+        assert_eq!(unicode("afii10017").as_deref(), Some("А"));
+        assert_eq!(unicode("afii10071").as_deref(), Some("ё"));
+        assert_eq!(unicode("afii10110").as_deref(), Some("ў"));
+        assert_eq!(unicode("zhecyrillic").as_deref(), Some("ж"));
+        assert_eq!(unicode("alphatonos").as_deref(), Some("ά"));
+        assert_eq!(unicode("upsilondieresistonos").as_deref(), Some("ΰ"));
+        assert_eq!(unicode("uniD800"), None);
+        assert_eq!(unicode("uni0041D800"), None);
+        assert_eq!(unicode("g42"), None);
+    }
+
+    #[test]
+    fn test_glyph_list_sorted() {
+        assert!(GLYPH_LIST.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_differences() {
+        let encoding = Encoding {
+            base: Some(BaseEncoding::WinAnsi),
+            differences: HashMap::from([
+                (0x41, String::from("Alpha")),
+                (0x80, String::from("uni2022")),
+            ]),
+        };
+        assert_eq!(encoding.unicode(0x41).as_deref(), Some("Α"));
+        assert_eq!(encoding.unicode(0x42).as_deref(), Some("B"));
+        assert_eq!(encoding.unicode(0x80).as_deref(), Some("•"));
+        assert_eq!(encoding.unicode(0x81), None);
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{
+    algebra::Number,
+    crypto::{md5, rc4, sha256, sha384, sha512, Aes},
+    object::{Dictionary, IndirectObject, Object, Stream},
+    Error,
+};
+
+// Padding string used to complete passwords to 32 bytes (page 125)
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xbf, 0x4e, 0x5e, 0x4e, 0x75, 0x8a, 0x41, 0x64, 0x00, 0x4e, 0x56, 0xff, 0xfa, 0x01, 0x08,
+    0x2e, 0x2e, 0x00, 0xb6, 0xd0, 0x68, 0x3e, 0x80, 0x2f, 0x0c, 0xa9, 0xfe, 0x64, 0x53, 0x69, 0x7a,
+];
+
+// Encryption algorithm applied to strings or streams, set by crypt filters (page 130)
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum CryptMethod {
+    Identity,
+    Rc4,
+    AesV2,
+    AesV3,
+}
+
+impl CryptMethod {
+    // Crypt filter name is Identity or a key of the CF dictionary
+    // The key length of the filter is returned in bytes when given
+    fn new(encrypt: &Dictionary, key: &str) -> Result<(Self, Option<usize>), Error> {
+        let name = match encrypt.get(key) {
+            Some(Object::Name(name)) => name,
+            None => return Ok((CryptMethod::Identity, None)),
+            Some(o) => {
+                return Err(Error::encryption(format!(
+                    "{key} should be a name, found {o:?}"
+                )))
+            }
+        };
+        if name == "Identity" {
+            return Ok((CryptMethod::Identity, None));
+        }
+        let crypt_filter = match encrypt.get("CF") {
+            Some(Object::Dictionary(filters)) => match filters.get(name) {
+                Some(Object::Dictionary(filter)) => filter,
+                _ => return Err(Error::encryption(format!("Crypt filter {name} not found"))),
+            },
+            _ => return Err(Error::encryption("CF should be a dictionary")),
+        };
+        let method = match crypt_filter.get("CFM") {
+            Some(Object::Name(method)) => match method.as_str() {
+                "None" => CryptMethod::Identity,
+                "V2" => CryptMethod::Rc4,
+                "AESV2" => CryptMethod::AesV2,
+                "AESV3" => CryptMethod::AesV3,
+                m => {
+                    return Err(Error::encryption(format!(
+                        "Crypt filter method {m} is not supported"
+                    )))
+                }
+            },
+            None => CryptMethod::Identity,
+            Some(o) => {
+                return Err(Error::encryption(format!(
+                    "CFM should be a name, found {o:?}"
+                )))
+            }
+        };
+        // Length is given in bits, but writers commonly give it in bytes
+        let length = match crypt_filter.get("Length") {
+            Some(Object::Numeric(Number::Integer(n))) if *n > 32 => Some(*n as usize / 8),
+            Some(Object::Numeric(Number::Integer(n))) => Some((*n).max(0) as usize),
+            None => None,
+            Some(o) => {
+                return Err(Error::encryption(format!(
+                    "Crypt filter Length should be an integer, found {o:?}"
+                )))
+            }
+        };
+        Ok((method, length))
+    }
+
+    // Key lengths in bytes allowed by the method (pages 124 and 131)
+    fn allows_key_length(&self, length: usize) -> bool {
+        match self {
+            CryptMethod::Identity => true,
+            CryptMethod::Rc4 => (5..=16).contains(&length),
+            CryptMethod::AesV2 => length == 16,
+            CryptMethod::AesV3 => length == 32,
+        }
+    }
+}
+
+// Standard security handler (page 122)
+// Strings and streams of indirect objects are decrypted with a key computed from a password
+#[derive(Debug, PartialEq, Clone)]
+pub struct SecurityHandler {
+    key: Vec<u8>,
+    string_method: CryptMethod,
+    stream_method: CryptMethod,
+    encrypt_metadata: bool,
+}
+
+// Values of the encryption dictionary used to check passwords
+struct EncryptionParameters<'a> {
+    revision: usize,
+    key_length: usize,
+    owner: Vec<u8>,
+    user: Vec<u8>,
+    permissions: i32,
+    file_id: &'a [u8],
+    encrypt_metadata: bool,
+}
+
+impl SecurityHandler {
+    // Authenticate the password as owner or user password and compute the file encryption key
+    // file_id is the first element of the trailer ID array
+    pub fn new(encrypt: &Dictionary, file_id: &[u8], password: &[u8]) -> Result<Self, Error> {
+        match encrypt.get("Filter") {
+            Some(Object::Name(name)) if name == "Standard" => (),
+            o => {
+                return Err(Error::encryption(format!(
+                    "Only the Standard security handler is supported, found {o:?}"
+                )))
+            }
+        }
+        let integer = |key: &str, default: Option<i64>| match encrypt.get(key) {
+            Some(Object::Numeric(Number::Integer(n))) => Ok(*n as i64),
+            None => default.ok_or(Error::encryption(format!("{key} is required"))),
+            Some(o) => Err(Error::encryption(format!(
+                "{key} should be an integer, found {o:?}"
+            ))),
+        };
+        let string = |key: &str| match encrypt.get(key) {
+            Some(Object::String(s) | Object::HexString(s)) => Ok(s.clone()),
+            o => Err(Error::encryption(format!(
+                "{key} should be a string, found {o:?}"
+            ))),
+        };
+        let version = integer("V", Some(0))?;
+        let encrypt_metadata =
+            !matches!(encrypt.get("EncryptMetadata"), Some(Object::Boolean(false)));
+        let ((string_method, string_length), (stream_method, stream_length)) = match version {
+            1 | 2 => ((CryptMethod::Rc4, None), (CryptMethod::Rc4, None)),
+            4 | 5 => (
+                CryptMethod::new(encrypt, "StrF")?,
+                CryptMethod::new(encrypt, "StmF")?,
+            ),
+            v => {
+                return Err(Error::encryption(format!(
+                    "Encryption algorithm version {v} is not supported"
+                )))
+            }
+        };
+        let key_length = match version {
+            1 => 5,
+            5 => 32,
+            // Length is given in bits, crypt filters of version 4 give their own length
+            _ => match stream_length.or(string_length) {
+                Some(length) if version == 4 => length,
+                _ => {
+                    let bits = integer("Length", Some(if version == 4 { 128 } else { 40 }))?;
+                    bits.max(0) as usize / 8
+                }
+            },
+        };
+        let revision = integer("R", None)? as usize;
+        // keys of revisions 2 to 4 are RC4 keys used to check the password
+        if !string_method.allows_key_length(key_length)
+            || !stream_method.allows_key_length(key_length)
+            || ((2..=4).contains(&revision) && !(5..=16).contains(&key_length))
+        {
+            return Err(Error::encryption(format!(
+                "Key length of {key_length} bytes is not valid for {string_method:?} strings and {stream_method:?} streams"
+            )));
+        }
+        let parameters = EncryptionParameters {
+            revision,
+            key_length,
+            owner: string("O")?,
+            user: string("U")?,
+            permissions: integer("P", None)? as i32,
+            file_id,
+            encrypt_metadata,
+        };
+        let key = match parameters.revision {
+            2..=4 => SecurityHandler::owner_rc4_key(&parameters, password)
+                .or_else(|| SecurityHandler::user_rc4_key(&parameters, password)),
+            5 | 6 => {
+                let owner_key = string("OE")?;
+                let user_key = string("UE")?;
+                SecurityHandler::aes256_key(&parameters, password, &owner_key, &user_key)
+            }
+            r => {
+                return Err(Error::encryption(format!(
+                    "Standard security handler revision {r} is not supported"
+                )))
+            }
+        };
+        match key {
+            Some(key) => Ok(SecurityHandler {
+                key,
+                string_method,
+                stream_method,
+                encrypt_metadata,
+            }),
+            None => Err(Error::encryption("Incorrect password")),
+        }
+    }
+
+    fn padded_password(password: &[u8]) -> Vec<u8> {
+        password
+            .iter()
+            .chain(PASSWORD_PADDING.iter())
+            .take(32)
+            .copied()
+            .collect()
+    }
+
+    // Algorithm 2: file encryption key computed from the user password
+    fn rc4_key(parameters: &EncryptionParameters, password: &[u8]) -> Vec<u8> {
+        let mut input = SecurityHandler::padded_password(password);
+        input.extend(&parameters.owner);
+        input.extend(parameters.permissions.to_le_bytes());
+        input.extend(parameters.file_id);
+        if parameters.revision >= 4 && !parameters.encrypt_metadata {
+            input.extend([0xff; 4]);
+        }
+        let length = parameters.key_length.min(16);
+        let mut hash = md5(&input);
+        if parameters.revision >= 3 {
+            for _ in 0..50 {
+                hash = md5(&hash[..length]);
+            }
+        }
+        hash[..length].to_vec()
+    }
+
+    // Algorithms 4 and 5: check the U entry computed with the user password
+    fn user_rc4_key(parameters: &EncryptionParameters, password: &[u8]) -> Option<Vec<u8>> {
+        let key = SecurityHandler::rc4_key(parameters, password);
+        let valid = match parameters.revision {
+            2 => rc4(&key, &PASSWORD_PADDING) == parameters.user,
+            _ => {
+                let mut hash = rc4(
+                    &key,
+                    &md5(&[&PASSWORD_PADDING, parameters.file_id].concat()),
+                );
+                for i in 1..=19 {
+                    hash = rc4(&key.iter().map(|k| k ^ i).collect::<Vec<u8>>(), &hash);
+                }
+                parameters.user.get(..16) == Some(&hash)
+            }
+        };
+        valid.then_some(key)
+    }
+
+    // Algorithm 7: the user password is decrypted from the O entry with the owner password
+    fn owner_rc4_key(parameters: &EncryptionParameters, password: &[u8]) -> Option<Vec<u8>> {
+        let length = parameters.key_length.min(16);
+        let mut hash = md5(&SecurityHandler::padded_password(password));
+        if parameters.revision >= 3 {
+            for _ in 0..50 {
+                hash = md5(&hash);
+            }
+        }
+        let key = &hash[..length];
+        let user_password = match parameters.revision {
+            2 => rc4(key, &parameters.owner),
+            _ => (0..=19).rev().fold(parameters.owner.clone(), |data, i| {
+                rc4(&key.iter().map(|k| k ^ i).collect::<Vec<u8>>(), &data)
+            }),
+        };
+        SecurityHandler::user_rc4_key(parameters, &user_password)
+    }
+
+    // Algorithm 2.A: AES-256 file encryption key decrypted from OE or UE
+    fn aes256_key(
+        parameters: &EncryptionParameters,
+        password: &[u8],
+        owner_key: &[u8],
+        user_key: &[u8],
+    ) -> Option<Vec<u8>> {
+        // passwords are truncated to 127 bytes
+        let password = &password[..password.len().min(127)];
+        let (owner, user) = (parameters.owner.get(..48)?, parameters.user.get(..48)?);
+        let hash = |salt: &[u8], user: &[u8]| match parameters.revision {
+            5 => sha256(&[password, salt, user].concat()).to_vec(),
+            _ => SecurityHandler::hash_r6(password, salt, user),
+        };
+        let (intermediate_key, encrypted_key) = if hash(&owner[32..40], user) == owner[..32] {
+            (hash(&owner[40..48], user), owner_key)
+        } else if hash(&user[32..40], &[]) == user[..32] {
+            (hash(&user[40..48], &[]), user_key)
+        } else {
+            return None;
+        };
+        let key = Aes::new(&intermediate_key).decrypt_cbc(&[0; 16], encrypted_key.get(..32)?);
+        Some(key)
+    }
+
+    // Algorithm 2.B: hash of revision 6, with a variable number of rounds
+    fn hash_r6(password: &[u8], salt: &[u8], user: &[u8]) -> Vec<u8> {
+        let mut k = sha256(&[password, salt, user].concat()).to_vec();
+        let mut round = 0;
+        loop {
+            round += 1;
+            let k1 = [password, &k, user].concat().repeat(64);
+            let e = Aes::new(&k[..16]).encrypt_cbc(k[16..32].try_into().unwrap(), &k1);
+            // remainder of the first 16 bytes taken as a big-endian number, 256 modulo 3 is 1
+            k = match e[..16].iter().map(|b| *b as u32).sum::<u32>() % 3 {
+                0 => sha256(&e).to_vec(),
+                1 => sha384(&e).to_vec(),
+                _ => sha512(&e).to_vec(),
+            };
+            if round >= 64 && *e.last().unwrap() as usize + 32 <= round {
+                break;
+            }
+        }
+        k.truncate(32);
+        k
+    }
+
+    // Algorithm 1: key of an indirect object, AES-256 uses the file key directly
+    fn object_key(&self, method: CryptMethod, id: IndirectObject) -> Vec<u8> {
+        if method == CryptMethod::AesV3 {
+            return self.key.clone();
+        }
+        let mut input = self.key.clone();
+        input.extend(&id.0.to_le_bytes()[..3]);
+        input.extend(&id.1.to_le_bytes()[..2]);
+        if method == CryptMethod::AesV2 {
+            input.extend(b"sAlT");
+        }
+        md5(&input)[..(self.key.len() + 5).min(16)].to_vec()
+    }
+
+    fn decrypt(&self, method: CryptMethod, data: &[u8], id: IndirectObject) -> Vec<u8> {
+        match method {
+            CryptMethod::Identity => data.to_vec(),
+            CryptMethod::Rc4 => rc4(&self.object_key(method, id), data),
+            // data starts with the initialization vector and is padded following PKCS#5
+            CryptMethod::AesV2 | CryptMethod::AesV3 => match data.get(..16) {
+                Some(iv) => {
+                    let key = self.object_key(method, id);
+                    let mut output =
+                        Aes::new(&key).decrypt_cbc(iv.try_into().unwrap(), &data[16..]);
+                    match output.last() {
+                        Some(&padding) if (1..=16).contains(&padding) => {
+                            output.truncate(output.len().saturating_sub(padding as usize))
+                        }
+                        _ => (),
+                    }
+                    output
+                }
+                None => vec![],
+            },
+        }
+    }
+
+    // Decrypt the strings and streams of an indirect object
+    pub fn decrypt_object<'a>(&self, object: Object<'a>, id: IndirectObject) -> Object<'a> {
+        let decrypt_dict = |dict: Dictionary<'a>| -> Dictionary<'a> {
+            dict.into_iter()
+                .map(|(key, value)| (key, self.decrypt_object(value, id)))
+                .collect()
+        };
+        match object {
+            Object::String(s) => Object::String(self.decrypt(self.string_method, &s, id)),
+            Object::HexString(s) => Object::HexString(self.decrypt(self.string_method, &s, id)),
+            Object::Array(array) => Object::Array(
+                array
+                    .into_iter()
+                    .map(|o| self.decrypt_object(o, id))
+                    .collect(),
+            ),
+            Object::Dictionary(dict) => Object::Dictionary(decrypt_dict(dict)),
+            Object::Stream(stream) => {
+                let stream_type = match stream.header.get("Type") {
+                    Some(Object::Name(t)) => t.clone(),
+                    _ => String::new(),
+                };
+                // cross-reference streams and, if requested, metadata streams are not encrypted
+                let bytes = match stream_type.as_str() {
+                    "XRef" => stream.bytes,
+                    "Metadata" if !self.encrypt_metadata => stream.bytes,
+                    _ => self.decrypt(self.stream_method, &stream.bytes, id),
+                };
+                Object::Stream(Stream {
+                    header: decrypt_dict(stream.header),
+                    bytes,
+                    max_decoded_size: stream.max_decoded_size,
+                })
+            }
+            o => o,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::xref::{XRef, XRefTable};
+
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rc4_40_bits() {
+        let bytes = b"6 0 obj\n<< /Filter /Standard /V 1 /R 2 /P -44\n/O <c92422687facee686e373f10b5c7d04738053152f7e2ee30e11c69ec442576ab>\n/U <42919a8b4123b90cb624b855af79106ac44edb9776567d82b3cbe2b17e7ba903> >>\nendobj";
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let encrypt = match Object::new(bytes, 0, xref).unwrap() {
+            Object::Dictionary(dict) => dict,
+            o => panic!("{o:?}"),
+        };
+        let file_id = hex("9f3e6a21c04b7d58e1a2b3c4d5e6f708");
+        for password in [b"".as_slice(), b"owner"] {
+            let handler = SecurityHandler::new(&encrypt, &file_id, password).unwrap();
+            let title = Object::String(hex("29ebbb4fdcbf6bcbe01da91e"));
+            assert_eq!(
+                handler.decrypt_object(title, (7, 0)),
+                Object::String(b"Secret title".to_vec())
+            );
+        }
+        assert_eq!(
+            SecurityHandler::new(&encrypt, &file_id, b"wrong"),
+            Err(Error::encryption("Incorrect password"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        let handler = |bytes: &[u8]| {
+            let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+            let encrypt = match Object::new(bytes, 0, xref).unwrap() {
+                Object::Dictionary(dict) => dict,
+                o => panic!("{o:?}"),
+            };
+            SecurityHandler::new(&encrypt, &[], b"")
+        };
+        let o = "<c92422687facee686e373f10b5c7d04738053152f7e2ee30e11c69ec442576ab>";
+        let u = "<42919a8b4123b90cb624b855af79106ac44edb9776567d82b3cbe2b17e7ba903>";
+        // empty RC4 key
+        let rc4 = format!(
+            "1 0 obj\n<< /Filter /Standard /V 2 /R 3 /Length 0 /P -44 /O {o} /U {u} >>\nendobj"
+        );
+        assert_eq!(
+            handler(rc4.as_bytes()),
+            Err(Error::encryption(
+                "Key length of 0 bytes is not valid for Rc4 strings and Rc4 streams"
+            ))
+        );
+        // 5 bytes AES key, given by the encryption dictionary or by the crypt filter
+        for (length, filter_length) in [("/Length 40", ""), ("", "/Length 40")] {
+            let aes = format!("1 0 obj\n<< /Filter /Standard /V 4 /R 4 {length} /P -44 /O {o} /U {u}\n/CF << /StdCF << /CFM /AESV2 {filter_length} >> >> /StmF /StdCF /StrF /StdCF >>\nendobj");
+            assert_eq!(
+                handler(aes.as_bytes()),
+                Err(Error::encryption(
+                    "Key length of 5 bytes is not valid for AesV2 strings and AesV2 streams"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_aes_256_revision_5() {
+        let bytes = b"1 0 obj\n<< /Filter /Standard /V 5 /R 5 /Length 256 /P -44\n/CF << /StdCF << /CFM /AESV3 /Length 32 >> >> /StmF /StdCF /StrF /StdCF\n/O <ded8f0bdaa70a00a4a698c99f42c391466e5be02eecca82bd7d0a1e4d421379503030303030303030404040404040404>\n/U <11f9c78082a5dc812a13967141cd9c043e9d2acccb1cc056164aff673c7aa20801010101010101010202020202020202>\n/OE <56dc2977f2dc48aeef6d61875e0c2f501c96648c2913e22fd3451576286e159f>\n/UE <d406c3d9dea2cf252ce528f6ad4fe3f927ba8035471f7a63935c981e83f9f18f> >>\nendobj";
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let encrypt = match Object::new(bytes, 0, xref).unwrap() {
+            Object::Dictionary(dict) => dict,
+            o => panic!("{o:?}"),
+        };
+        for password in [b"user".as_slice(), b"owner"] {
+            let handler = SecurityHandler::new(&encrypt, &[], password).unwrap();
+            let title = Object::String(hex(
+                "09090909090909090909090909090909b1c215865baeb05ed06a71a20a2c07b9",
+            ));
+            assert_eq!(
+                handler.decrypt_object(title, (2, 0)),
+                Object::String(b"Secret title".to_vec())
+            );
+        }
+        for password in [b"".as_slice(), b"wrong"] {
+            assert_eq!(
+                SecurityHandler::new(&encrypt, &[], password),
+                Err(Error::encryption("Incorrect password"))
+            );
+        }
+    }
+}
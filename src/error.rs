@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use crate::object::{IndirectObject, Name};
+
+// Errors raised while reading a PDF document
+// Offsets are byte positions in the file, or in the decoded stream for content streams
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    // Unexpected bytes or end of input while splitting bytes into tokens
+    Tokenizer {
+        offset: usize,
+        message: String,
+    },
+    // Cross-reference table, cross-reference stream or trailer could not be read
+    XRef {
+        offset: Option<usize>,
+        message: String,
+    },
+    // Object is missing, malformed or of an unexpected type
+    Object {
+        id: Option<IndirectObject>,
+        offset: Option<usize>,
+        message: String,
+    },
+    // Stream data could not be decoded
+    Filter {
+        filter: Name,
+        message: String,
+    },
+    // Font dictionary or character map is malformed
+    Font {
+        name: Option<Name>,
+        message: String,
+    },
+    // Content stream operator could not be interpreted
+    ContentStream {
+        offset: usize,
+        message: String,
+    },
+    // Document is encrypted with an unsupported security handler or the password is incorrect
+    Encryption {
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn object(message: impl Into<String>) -> Self {
+        Error::Object {
+            id: None,
+            offset: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn xref(offset: Option<usize>, message: impl Into<String>) -> Self {
+        Error::XRef {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    pub fn font(message: impl Into<String>) -> Self {
+        Error::Font {
+            name: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn encryption(message: impl Into<String>) -> Self {
+        Error::Encryption {
+            message: message.into(),
+        }
+    }
+
+    // Attach the indirect object being read to an object error
+    pub fn with_id(self, object_id: IndirectObject) -> Self {
+        match self {
+            Error::Object {
+                id: None,
+                offset,
+                message,
+            } => Error::Object {
+                id: Some(object_id),
+                offset,
+                message,
+            },
+            e => e,
+        }
+    }
+
+    // Attach the font resource name to a font error
+    pub fn with_font_name(self, font_name: &str) -> Self {
+        match self {
+            Error::Font {
+                name: None,
+                message,
+            } => Error::Font {
+                name: Some(font_name.to_string()),
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Tokenizer { offset, message } => {
+                write!(f, "Tokenizer error at byte {offset}: {message}")
+            }
+            Error::XRef { offset, message } => match offset {
+                Some(offset) => write!(f, "Cross-reference error at byte {offset}: {message}"),
+                None => write!(f, "Cross-reference error: {message}"),
+            },
+            Error::Object {
+                id,
+                offset,
+                message,
+            } => {
+                write!(f, "Object error")?;
+                if let Some((obj, gen)) = id {
+                    write!(f, " in object {obj} {gen}")?;
+                }
+                if let Some(offset) = offset {
+                    write!(f, " at byte {offset}")?;
+                }
+                write!(f, ": {message}")
+            }
+            Error::Filter { filter, message } => write!(f, "{filter} filter error: {message}"),
+            Error::Font { name, message } => match name {
+                Some(name) => write!(f, "Font error in font {name}: {message}"),
+                None => write!(f, "Font error: {message}"),
+            },
+            Error::ContentStream { offset, message } => {
+                write!(f, "Content stream error at byte {offset}: {message}")
+            }
+            Error::Encryption { message } => write!(f, "Encryption error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,14 +1,745 @@
-use flate2::read::ZlibDecoder;
-use std::io::Read;
-
-pub fn flate_decode(bytes: &[u8]) -> Vec<u8> {
-    let mut d = ZlibDecoder::new(bytes);
-    // 10 times the size of the compressed stream --> improvement is required
-    let buf = &mut vec![0; bytes.len() * 10];
-    match d.read(buf) {
-        Ok(_) => buf.to_vec(),
-        Err(e) => {
-            panic!("Error: {:?}", e);
+use flate2::{Decompress, FlushDecompress, Status};
+
+use crate::{
+    algebra::Number,
+    object::{Dictionary, Name, Object},
+    Error,
+};
+
+// Standard filters (page 65)
+#[derive(Debug, PartialEq, Clone)]
+pub enum Filter {
+    FlateDecode,
+    LZWDecode,
+    ASCIIHexDecode,
+    ASCII85Decode,
+    RunLengthDecode,
+}
+
+impl TryFrom<Name> for Filter {
+    type Error = Error;
+
+    fn try_from(value: Name) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "FlateDecode" | "Fl" => Ok(Filter::FlateDecode),
+            "LZWDecode" | "LZW" => Ok(Filter::LZWDecode),
+            "ASCIIHexDecode" | "AHx" => Ok(Filter::ASCIIHexDecode),
+            "ASCII85Decode" | "A85" => Ok(Filter::ASCII85Decode),
+            "RunLengthDecode" | "RL" => Ok(Filter::RunLengthDecode),
+            _ => Err(Error::Filter {
+                filter: value,
+                message: String::from("Filter is currently not supported"),
+            }),
+        }
+    }
+}
+
+impl Filter {
+    // Decoded data larger than max_size is rejected to protect against decompression bombs
+    pub fn decode(
+        &self,
+        bytes: &[u8],
+        parms: &DecodeParms,
+        max_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let (name, output) = match self {
+            Filter::FlateDecode => (
+                "FlateDecode",
+                predictor_decode(flate_decode(bytes, max_size)?, parms, max_size)?,
+            ),
+            Filter::LZWDecode => (
+                "LZWDecode",
+                predictor_decode(
+                    lzw_decode(bytes, parms.early_change, max_size)?,
+                    parms,
+                    max_size,
+                )?,
+            ),
+            Filter::ASCIIHexDecode => ("ASCIIHexDecode", ascii_hex_decode(bytes)?),
+            Filter::ASCII85Decode => ("ASCII85Decode", ascii85_decode(bytes)?),
+            Filter::RunLengthDecode => ("RunLengthDecode", run_length_decode(bytes, max_size)?),
+        };
+        check_size(name, output.len(), max_size)?;
+        Ok(output)
+    }
+}
+
+// Optional parameters used by filters (page 72)
+#[derive(Debug, PartialEq, Clone)]
+pub struct DecodeParms {
+    predictor: usize,
+    colors: usize,
+    bits_per_component: usize,
+    columns: usize,
+    early_change: bool,
+}
+
+impl Default for DecodeParms {
+    fn default() -> Self {
+        DecodeParms {
+            predictor: 1,
+            colors: 1,
+            bits_per_component: 8,
+            columns: 1,
+            early_change: true,
+        }
+    }
+}
+
+impl DecodeParms {
+    fn integer(value: &Dictionary, key: &str, default: usize) -> Result<usize, Error> {
+        match value.get(key) {
+            Some(Object::Numeric(Number::Integer(n))) if *n >= 0 => Ok(*n as usize),
+            None => Ok(default),
+            Some(o) => Err(Error::object(format!(
+                "DecodeParms {key} should be a positive integer, found {o:?}"
+            ))),
+        }
+    }
+
+    // number of bytes of a sample row, excluding the PNG filter type byte
+    // parameters come from the document and may describe rows too long to be allocated
+    fn row_length(&self, max_size: usize) -> Result<usize, Error> {
+        match self
+            .colors
+            .checked_mul(self.bits_per_component)
+            .and_then(|bits| bits.checked_mul(self.columns))
+        {
+            Some(bits) if bits.div_ceil(8) <= max_size => Ok(bits.div_ceil(8)),
+            _ => Err(filter_error(
+                "Predictor",
+                format!(
+                    "Row of {} columns of {} colors of {} bits is too long",
+                    self.columns, self.colors, self.bits_per_component
+                ),
+            )),
+        }
+    }
+
+    // number of bytes of a complete pixel, rounded up to 1, once row_length has checked the parameters
+    fn bytes_per_pixel(&self) -> usize {
+        (self.colors * self.bits_per_component).div_ceil(8)
+    }
+}
+
+impl TryFrom<&Dictionary<'_>> for DecodeParms {
+    type Error = Error;
+
+    fn try_from(value: &Dictionary) -> Result<Self, Self::Error> {
+        let parms = DecodeParms {
+            predictor: DecodeParms::integer(value, "Predictor", 1)?,
+            colors: DecodeParms::integer(value, "Colors", 1)?,
+            bits_per_component: DecodeParms::integer(value, "BitsPerComponent", 8)?,
+            columns: DecodeParms::integer(value, "Columns", 1)?,
+            early_change: DecodeParms::integer(value, "EarlyChange", 1)? != 0,
+        };
+        if parms.colors == 0 || parms.columns == 0 {
+            return Err(Error::object(
+                "DecodeParms Colors and Columns should be at least 1",
+            ));
+        }
+        if ![1, 2, 4, 8, 16].contains(&parms.bits_per_component) {
+            return Err(Error::object(format!(
+                "DecodeParms BitsPerComponent should be 1, 2, 4, 8 or 16, found {}",
+                parms.bits_per_component
+            )));
+        }
+        Ok(parms)
+    }
+}
+
+// Filters declared by a stream dictionary with their parameters, in the order they are applied
+// DecodeParms is a single dictionary or an array matching the filters, null entries meaning default values
+pub fn stream_filters(header: &Dictionary) -> Result<Vec<(Filter, DecodeParms)>, Error> {
+    let filters = match header.get("Filter").map(Object::resolve).transpose()? {
+        Some(Object::Name(name)) => vec![Filter::try_from(name)?],
+        Some(Object::Array(names)) => names
+            .into_iter()
+            .map(|name| match name {
+                Object::Name(name) => Filter::try_from(name),
+                o => Err(Error::object(format!(
+                    "Filter should be a name, found {o:?}"
+                ))),
+            })
+            .collect::<Result<Vec<Filter>, Error>>()?,
+        None => vec![],
+        Some(o) => {
+            return Err(Error::object(format!(
+                "Filter should be a name or an array, found {o:?}"
+            )))
+        }
+    };
+    let parms = match header.get("DecodeParms").map(Object::resolve).transpose()? {
+        Some(Object::Array(parms)) => parms
+            .iter()
+            .map(|p| match p.resolve()? {
+                Object::Dictionary(d) => DecodeParms::try_from(&d),
+                _ => Ok(DecodeParms::default()),
+            })
+            .collect::<Result<Vec<DecodeParms>, Error>>()?,
+        Some(Object::Dictionary(d)) => vec![DecodeParms::try_from(&d)?],
+        _ => vec![],
+    };
+    Ok(filters
+        .into_iter()
+        .enumerate()
+        .map(|(i, f)| (f, parms.get(i).cloned().unwrap_or_default()))
+        .collect())
+}
+
+// Apply a chain of filters to the raw bytes of a stream, each output being at most max_size bytes
+pub fn decode(
+    bytes: &[u8],
+    filters: &[(Filter, DecodeParms)],
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    filters
+        .iter()
+        .try_fold(bytes.to_vec(), |data, (filter, parms)| {
+            filter.decode(&data, parms, max_size)
+        })
+}
+
+fn filter_error(filter: &str, message: impl Into<String>) -> Error {
+    Error::Filter {
+        filter: String::from(filter),
+        message: message.into(),
+    }
+}
+
+// Default upper bound of the size of a decoded stream (256 MiB)
+pub const DEFAULT_MAX_DECODED_SIZE: usize = 256 * 1024 * 1024;
+
+fn check_size(filter: &str, size: usize, max_size: usize) -> Result<(), Error> {
+    match size > max_size {
+        true => Err(filter_error(
+            filter,
+            format!("Decoded data exceeds the maximum size of {max_size} bytes"),
+        )),
+        false => Ok(()),
+    }
+}
+
+// Output buffer growth step while inflating
+const FLATE_CHUNK_SIZE: usize = 64 * 1024;
+
+pub fn flate_decode(bytes: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut decompress = Decompress::new(true);
+    let mut output = Vec::new();
+    loop {
+        if output.len() == output.capacity() {
+            output.reserve(FLATE_CHUNK_SIZE);
+        }
+        let (total_in, total_out) = (decompress.total_in(), decompress.total_out());
+        let input = &bytes[total_in as usize..];
+        match decompress.decompress_vec(input, &mut output, FlushDecompress::None) {
+            Ok(Status::StreamEnd) => break,
+            // truncated data: no progress can be made, keep the bytes decoded so far
+            Ok(_) if decompress.total_in() == total_in && decompress.total_out() == total_out => {
+                break
+            }
+            Ok(_) => (),
+            // corrupt data: keep the bytes decoded before the error
+            Err(_) if !output.is_empty() => break,
+            Err(e) => return Err(filter_error("FlateDecode", e.to_string())),
+        }
+        check_size("FlateDecode", output.len(), max_size)?;
+    }
+    Ok(output)
+}
+
+const LZW_CLEAR_TABLE: usize = 256;
+const LZW_EOD: usize = 257;
+
+pub fn lzw_decode(bytes: &[u8], early_change: bool, max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    let mut table: Vec<Vec<u8>> = (0..=255).map(|b| vec![b]).collect();
+    // placeholders for the clear-table and EOD codes
+    table.extend([vec![], vec![]]);
+    let mut code_length = 9;
+    let mut previous: Option<Vec<u8>> = None;
+    let (mut buffer, mut buffered_bits) = (0u32, 0);
+    let mut input = bytes.iter();
+    loop {
+        while buffered_bits < code_length {
+            match input.next() {
+                Some(b) => {
+                    buffer = (buffer << 8) | *b as u32;
+                    buffered_bits += 8;
+                }
+                // a missing EOD marker is tolerated
+                None => return Ok(output),
+            }
+        }
+        buffered_bits -= code_length;
+        let code = ((buffer >> buffered_bits) & ((1 << code_length) - 1)) as usize;
+        match code {
+            LZW_CLEAR_TABLE => {
+                table.truncate(LZW_EOD + 1);
+                code_length = 9;
+                previous = None;
+            }
+            LZW_EOD => return Ok(output),
+            code => {
+                let entry = match (table.get(code), &previous) {
+                    (Some(entry), _) => entry.clone(),
+                    // code defined by the current step: previous sequence followed by its first byte
+                    (None, Some(p)) if code == table.len() => {
+                        let mut entry = p.clone();
+                        entry.push(p[0]);
+                        entry
+                    }
+                    _ => return Err(filter_error("LZWDecode", format!("Invalid code {code}"))),
+                };
+                output.extend(&entry);
+                check_size("LZWDecode", output.len(), max_size)?;
+                if let Some(mut p) = previous {
+                    p.push(entry[0]);
+                    table.push(p);
+                }
+                previous = Some(entry);
+                if table.len() + early_change as usize >= 1 << code_length && code_length < 12 {
+                    code_length += 1;
+                }
+            }
+        }
+    }
+}
+
+// Undo the prediction applied before LZW or Flate encoding (page 76)
+fn predictor_decode(
+    bytes: Vec<u8>,
+    parms: &DecodeParms,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    match parms.predictor {
+        1 => Ok(bytes),
+        2 => tiff_predictor_decode(bytes, parms, max_size),
+        10..=15 => png_predictor_decode(&bytes, parms, max_size),
+        p => Err(filter_error("Predictor", format!("Unknown predictor {p}"))),
+    }
+}
+
+// TIFF predictor 2: each component is the difference with the same component of the previous pixel
+fn tiff_predictor_decode(
+    mut bytes: Vec<u8>,
+    parms: &DecodeParms,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let colors = parms.colors;
+    for row in bytes.chunks_mut(parms.row_length(max_size)?) {
+        match parms.bits_per_component {
+            16 => {
+                for i in (2 * colors..row.len() - 1).step_by(2) {
+                    let left = u16::from_be_bytes([row[i - 2 * colors], row[i + 1 - 2 * colors]]);
+                    let value = u16::from_be_bytes([row[i], row[i + 1]]).wrapping_add(left);
+                    row[i..i + 2].copy_from_slice(&value.to_be_bytes());
+                }
+            }
+            bpc => {
+                // components are packed in bytes, high-order bits first
+                let mask = ((1u16 << bpc) - 1) as u8;
+                let component =
+                    |row: &[u8], i: usize| (row[i * bpc / 8] >> (8 - bpc - i * bpc % 8)) & mask;
+                for i in colors..row.len() * 8 / bpc {
+                    let value = component(row, i).wrapping_add(component(row, i - colors)) & mask;
+                    let shift = 8 - bpc - i * bpc % 8;
+                    row[i * bpc / 8] = (row[i * bpc / 8] & !(mask << shift)) | (value << shift);
+                }
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+// PNG predictors: each row starts with a byte giving the PNG filter type of the row
+fn png_predictor_decode(
+    bytes: &[u8],
+    parms: &DecodeParms,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let row_length = parms.row_length(max_size)?;
+    let bpp = parms.bytes_per_pixel();
+    let mut output = Vec::with_capacity(bytes.len());
+    // decoded rows are at most as long as the data
+    let mut previous = vec![0; row_length.min(bytes.len())];
+    for chunk in bytes.chunks(row_length + 1) {
+        let mut row = chunk[1..].to_vec();
+        for i in 0..row.len() {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+            let up = previous[i];
+            row[i] = row[i].wrapping_add(match chunk[0] {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                t => {
+                    return Err(filter_error(
+                        "Predictor",
+                        format!("Unknown PNG filter type {t}"),
+                    ))
+                }
+            });
         }
+        previous[..row.len()].copy_from_slice(&row);
+        output.extend(row);
+    }
+    Ok(output)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let p = left as i16 + up as i16 - up_left as i16;
+    let (pa, pb, pc) = (
+        (p - left as i16).abs(),
+        (p - up as i16).abs(),
+        (p - up_left as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        left
+    } else if pb <= pc {
+        up
+    } else {
+        up_left
+    }
+}
+
+pub fn ascii_hex_decode(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut digits = Vec::new();
+    for &c in bytes {
+        match c {
+            b'>' => break,
+            b'0'..=b'9' => digits.push(c - b'0'),
+            b'a'..=b'f' => digits.push(c - b'a' + 10),
+            b'A'..=b'F' => digits.push(c - b'A' + 10),
+            c if c.is_ascii_whitespace() || c == b'\0' => (),
+            c => {
+                return Err(filter_error(
+                    "ASCIIHexDecode",
+                    format!("Invalid hexadecimal digit {:?}", c as char),
+                ))
+            }
+        }
+    }
+    // an odd number of digits is completed by a trailing 0
+    Ok(digits
+        .chunks(2)
+        .map(|d| d[0] << 4 | d.get(1).unwrap_or(&0))
+        .collect())
+}
+
+pub fn ascii85_decode(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let bytes = bytes.strip_prefix(b"<~").unwrap_or(bytes);
+    let mut output = Vec::new();
+    let mut group = Vec::with_capacity(5);
+    for &c in bytes {
+        match c {
+            b'~' => break,
+            b'z' if group.is_empty() => output.extend([0; 4]),
+            b'!'..=b'u' => {
+                group.push(c - b'!');
+                if group.len() == 5 {
+                    output.extend(ascii85_group(&group)?);
+                    group.clear();
+                }
+            }
+            c if c.is_ascii_whitespace() || c == b'\0' => (),
+            c => {
+                return Err(filter_error(
+                    "ASCII85Decode",
+                    format!("Invalid character {:?}", c as char),
+                ))
+            }
+        }
+    }
+    // final partial group of n characters is padded with 'u' and gives n - 1 bytes
+    match group.len() {
+        0 => (),
+        1 => {
+            return Err(filter_error(
+                "ASCII85Decode",
+                "Final group has one character",
+            ))
+        }
+        n => {
+            group.resize(5, b'u' - b'!');
+            output.extend(&ascii85_group(&group)?[..n - 1]);
+        }
+    }
+    Ok(output)
+}
+
+fn ascii85_group(group: &[u8]) -> Result<[u8; 4], Error> {
+    let value = group
+        .iter()
+        .fold(0u64, |acc, digit| acc * 85 + *digit as u64);
+    match u32::try_from(value) {
+        Ok(value) => Ok(value.to_be_bytes()),
+        Err(_) => Err(filter_error(
+            "ASCII85Decode",
+            "Group value exceeds 2^32 - 1",
+        )),
+    }
+}
+
+pub fn run_length_decode(bytes: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    let mut i = 0;
+    while let Some(&length) = bytes.get(i) {
+        match length {
+            128 => break,
+            0..=127 => {
+                let run = bytes
+                    .get(i + 1..i + 2 + length as usize)
+                    .ok_or_else(|| filter_error("RunLengthDecode", "Unexpected end of data"))?;
+                output.extend(run);
+                i += 2 + length as usize;
+            }
+            _ => {
+                let b = bytes
+                    .get(i + 1)
+                    .ok_or_else(|| filter_error("RunLengthDecode", "Unexpected end of data"))?;
+                output.extend(std::iter::repeat_n(*b, 257 - length as usize));
+                i += 2;
+            }
+        }
+        check_size("RunLengthDecode", output.len(), max_size)?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lzw_decode() {
+        // example from the specification (page 73)
+        let bytes = [0x80, 0x0B, 0x60, 0x50, 0x22, 0x0C, 0x0C, 0x85, 0x01];
+        assert_eq!(
+            lzw_decode(&bytes, true, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            b"-----A---B"
+        );
+    }
+
+    #[test]
+    fn test_lzw_decode_invalid_code() {
+        // first code refers to a table entry which is not yet defined
+        assert!(lzw_decode(&[0xFF, 0x80], true, DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_ascii_hex_decode() {
+        assert_eq!(ascii_hex_decode(b"48 65 6c6C\n6f>").unwrap(), b"Hello");
+        assert_eq!(ascii_hex_decode(b"7>").unwrap(), [0x70]);
+        assert!(ascii_hex_decode(b"4G>").is_err());
+    }
+
+    #[test]
+    fn test_ascii85_decode() {
+        assert_eq!(
+            ascii85_decode(b"87cURD_*#TD\nfTZ)+T~>").unwrap(),
+            b"Hello, world!"
+        );
+        assert_eq!(ascii85_decode(b"<~z@:E^~>").unwrap(), b"\0\0\0\0abc");
+        assert!(ascii85_decode(b"uuuuu~>").is_err());
+    }
+
+    #[test]
+    fn test_run_length_decode() {
+        assert_eq!(
+            run_length_decode(
+                &[2, b'a', b'b', b'c', 254, b'x', 128, b'z'],
+                DEFAULT_MAX_DECODED_SIZE
+            )
+            .unwrap(),
+            b"abcxxx"
+        );
+        assert!(run_length_decode(&[4, b'a'], DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_filter_chain() {
+        let parms = DecodeParms::default();
+        let data = Filter::ASCIIHexDecode
+            .decode(
+                b"789c0b492d2e010003dd01a1>",
+                &parms,
+                DEFAULT_MAX_DECODED_SIZE,
+            )
+            .unwrap();
+        let data = Filter::FlateDecode
+            .decode(&data, &parms, DEFAULT_MAX_DECODED_SIZE)
+            .unwrap();
+        assert_eq!(data, b"Test");
+    }
+
+    fn zlib(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        std::io::Write::write_all(&mut encoder, bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_flate_decode_highly_compressed() {
+        let data = vec![b'a'; 1 << 20];
+        let encoded = zlib(&data);
+        assert!(encoded.len() * 10 < data.len());
+        assert_eq!(
+            flate_decode(&encoded, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_flate_decode_truncated() {
+        let data: Vec<u8> = (0..100_000u32).flat_map(|i| i.to_be_bytes()).collect();
+        let encoded = zlib(&data);
+        let decoded =
+            flate_decode(&encoded[..encoded.len() / 2], DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert!(!decoded.is_empty());
+        assert!(data.starts_with(&decoded));
+        assert!(flate_decode(b"not zlib data", DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_flate_decode_max_size() {
+        let encoded = zlib(&vec![0; 1 << 20]);
+        assert!(flate_decode(&encoded, 1 << 20).is_ok());
+        assert!(matches!(
+            flate_decode(&encoded, 1000),
+            Err(Error::Filter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_max_size() {
+        let parms = DecodeParms::default();
+        // each run of 128 bytes is encoded by 2 bytes
+        let run_length = [[129, b'a'].repeat(100), vec![128]].concat();
+        assert_eq!(
+            Filter::RunLengthDecode
+                .decode(&run_length, &parms, 12_800)
+                .unwrap()
+                .len(),
+            12_800
+        );
+        assert!(run_length_decode(&run_length, 12_799).is_err());
+        // each code decodes a longer sequence of the same byte
+        let lzw = [0x80, 0x0B, 0x60, 0x50, 0x22, 0x0C, 0x0C, 0x85, 0x01];
+        assert!(lzw_decode(&lzw, true, 9).is_err());
+        assert!(Filter::LZWDecode.decode(&lzw, &parms, 9).is_err());
+        assert!(Filter::ASCIIHexDecode
+            .decode(b"616263>", &parms, 2)
+            .is_err());
+        assert!(Filter::ASCII85Decode.decode(b"zz~>", &parms, 7).is_err());
+        // rows of predictors are bounded by the maximum size
+        let parms = DecodeParms {
+            predictor: 12,
+            columns: 100,
+            ..Default::default()
+        };
+        assert!(predictor_decode(vec![0; 8], &parms, 99).is_err());
+        // the limit applies to every filter of a chain
+        let filters = [
+            (Filter::ASCIIHexDecode, DecodeParms::default()),
+            (Filter::FlateDecode, DecodeParms::default()),
+        ];
+        let data = b"789c0b492d2e010003dd01a1>";
+        assert_eq!(decode(data, &filters, 12).unwrap(), b"Test");
+        assert!(decode(data, &filters, 11).is_err());
+        assert!(decode(data, &filters, 3).is_err());
+    }
+
+    #[test]
+    fn test_png_predictor() {
+        let parms = DecodeParms {
+            predictor: 12,
+            columns: 4,
+            ..Default::default()
+        };
+        // None, Sub, Up, Average and Paeth rows
+        let bytes = [
+            0, 1, 2, 3, 4, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 3, 2, 2, 2, 2, 4, 0, 0, 0, 0,
+        ];
+        assert_eq!(
+            png_predictor_decode(&bytes, &parms, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            [1, 2, 3, 4, 1, 2, 3, 4, 2, 3, 4, 5, 3, 5, 6, 7, 3, 5, 6, 7]
+        );
+        assert!(png_predictor_decode(&[5, 0, 0, 0, 0], &parms, DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_tiff_predictor() {
+        let parms = DecodeParms {
+            predictor: 2,
+            colors: 2,
+            columns: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            tiff_predictor_decode(
+                vec![10, 20, 1, 2, 1, 2, 5, 5, 1, 1, 1, 1],
+                &parms,
+                DEFAULT_MAX_DECODED_SIZE
+            )
+            .unwrap(),
+            [10, 20, 11, 22, 12, 24, 5, 5, 6, 6, 7, 7]
+        );
+        // sums of 8-bit samples wrap around
+        assert_eq!(
+            tiff_predictor_decode(
+                vec![200, 0x80, 100, 0x90, 0xFF, 0x01],
+                &parms,
+                DEFAULT_MAX_DECODED_SIZE
+            )
+            .unwrap(),
+            [200, 0x80, 44, 0x10, 43, 0x11]
+        );
+        let parms = DecodeParms {
+            predictor: 2,
+            bits_per_component: 4,
+            columns: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            tiff_predictor_decode(vec![0x31, 0x2F], &parms, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            [0x34, 0x65]
+        );
+        // 4-bit components wrap around as well
+        assert_eq!(
+            tiff_predictor_decode(vec![0xF8, 0x88], &parms, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            [0xF7, 0xF7]
+        );
+    }
+
+    #[test]
+    fn test_predictor_row_length() {
+        let parms = DecodeParms {
+            predictor: 12,
+            colors: usize::MAX / 2,
+            columns: 3,
+            ..Default::default()
+        };
+        assert!(parms.row_length(DEFAULT_MAX_DECODED_SIZE).is_err());
+        assert!(predictor_decode(vec![0; 8], &parms, DEFAULT_MAX_DECODED_SIZE).is_err());
+        let parms = DecodeParms {
+            predictor: 2,
+            columns: 1 << 40,
+            ..Default::default()
+        };
+        assert!(predictor_decode(vec![0; 8], &parms, DEFAULT_MAX_DECODED_SIZE).is_err());
+        // rows longer than the data are decoded up to the end of the data
+        let parms = DecodeParms {
+            predictor: 12,
+            columns: 1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            predictor_decode(vec![1, 1, 1], &parms, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            [1, 2]
+        );
     }
 }
@@ -1,82 +1,319 @@
-use crate::xref::XRef;
+use crate::{xref::XRef, Error};
 
 use super::object::{Dictionary, Object};
-use std::{fmt::Display, rc::Rc};
+use std::{collections::BTreeMap, fmt::Display, rc::Rc};
 
-#[derive(Debug, PartialEq)]
+// Document information dictionary (page 844)
+#[derive(Debug, PartialEq, Default)]
 pub struct Info {
-    title: Option<String>,
-    author: Option<String>,
-    creator: Option<String>,
-    producer: Option<String>,
-    creation_date: Option<String>, // TODO: convert to DateTime
-    mod_date: Option<String>,      // TODO: convert to DateTime
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<PdfDate>,
+    pub mod_date: Option<PdfDate>,
+    pub trapped: Option<String>,
+    // Keys that are not defined by the specification, sorted by name
+    pub custom: BTreeMap<String, String>,
 }
 
 impl Display for Info {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Title: {:?}\nAuthor: {:?}\nCreator: {:?}\nProducer: {:?}\nCreationDate: {:?}\nModDate: {:?}",
-            self.title, self.author, self.creator, self.producer, self.creation_date, self.mod_date)
+        let date = |date: &Option<PdfDate>| date.as_ref().map(PdfDate::to_rfc3339);
+        let entries = [
+            ("Title", self.title.clone()),
+            ("Author", self.author.clone()),
+            ("Subject", self.subject.clone()),
+            ("Keywords", self.keywords.clone()),
+            ("Creator", self.creator.clone()),
+            ("Producer", self.producer.clone()),
+            ("CreationDate", date(&self.creation_date)),
+            ("ModDate", date(&self.mod_date)),
+            ("Trapped", self.trapped.clone()),
+        ];
+        let lines = entries
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| format!("{key}: {v}")))
+            .chain(self.custom.iter().map(|(key, v)| format!("{key}: {v}")))
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
     }
 }
 
 impl Info {
-    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        match Object::new(bytes, curr_idx, xref) {
-            Object::Dictionary(dict) => Self::from(dict),
-            _ => panic!("Trailer should be a dictionary"),
+    pub fn new(bytes: &[u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        match Object::new(bytes, curr_idx, xref)? {
+            Object::Dictionary(dict) => Self::try_from(dict),
+            _ => Err(Error::object("Info should be a dictionary")),
         }
     }
 }
 
-impl From<Dictionary<'_>> for Info {
-    fn from(value: Dictionary) -> Self {
-        Info {
-            title: match value.get("Title") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                None => None,
-                _ => panic!("Title should be a string"),
-            },
-            author: match value.get("Author") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                None => None,
-                _ => panic!("Author should be a string"),
-            },
-            creator: match value.get("Creator") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                Some(Object::HexString(s)) => Some(match std::str::from_utf8(s) {
-                    Ok(s) => String::from(s),
-                    Err(..) => String::new(), // in case of unable to read hexstring
-                }),
-                None => None,
-                _ => panic!("Creator should be a string"),
-            },
-            producer: match value.get("Producer") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                Some(Object::HexString(s)) => Some(match std::str::from_utf8(s) {
-                    Ok(s) => String::from(s),
-                    Err(..) => String::new(), // in case of unable to read hexstring
-                }),
-                None => None,
-                _ => panic!("Producer should be a string"),
-            },
-            creation_date: match value.get("CreationDate") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                None => None,
-                _ => panic!("CreationDate should be a string"),
-            },
-            mod_date: match value.get("ModDate") {
-                Some(Object::String(s)) => Some(String::from(s)),
-                None => None,
-                _ => panic!("ModDate should be a string"),
+impl TryFrom<Dictionary<'_>> for Info {
+    type Error = Error;
+
+    fn try_from(value: Dictionary) -> Result<Self, Self::Error> {
+        // entries that have another type or cannot be resolved are ignored, like custom entries
+        let text = |key: &str| match value.get(key).map(Object::resolve) {
+            Some(Ok(Object::String(s) | Object::HexString(s))) => Some(text_string(&s)),
+            _ => None,
+        };
+        let mut custom = BTreeMap::new();
+        for (key, object) in value.iter() {
+            if STANDARD_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            // custom entries that are not text strings or cannot be resolved are ignored
+            if let Ok(Object::String(s) | Object::HexString(s)) = object.resolve() {
+                custom.insert(key.clone(), text_string(&s));
+            }
+        }
+        Ok(Info {
+            title: text("Title"),
+            author: text("Author"),
+            subject: text("Subject"),
+            keywords: text("Keywords"),
+            creator: text("Creator"),
+            producer: text("Producer"),
+            // malformed dates are ignored rather than failing the whole dictionary
+            creation_date: text("CreationDate").and_then(|d| PdfDate::try_from(d.as_str()).ok()),
+            mod_date: text("ModDate").and_then(|d| PdfDate::try_from(d.as_str()).ok()),
+            trapped: match value.get("Trapped").map(Object::resolve) {
+                Some(Ok(Object::Name(name))) => Some(name),
+                // some producers write a boolean instead of a name
+                Some(Ok(Object::Boolean(b))) => {
+                    Some(String::from(if b { "True" } else { "False" }))
+                }
+                _ => None,
             },
+            custom,
+        })
+    }
+}
+
+const STANDARD_KEYS: [&str; 9] = [
+    "Title",
+    "Author",
+    "Subject",
+    "Keywords",
+    "Creator",
+    "Producer",
+    "CreationDate",
+    "ModDate",
+    "Trapped",
+];
+
+// Date string D:YYYYMMDDHHmmSSOHH'mm' (page 160)
+// Fields after the year are optional, offset is given in minutes east of UTC when known
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PdfDate {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub offset: Option<i16>,
+}
+
+impl PdfDate {
+    // Seconds since 1970-01-01T00:00:00Z, dates without offset are assumed to be UTC
+    pub fn timestamp(&self) -> i64 {
+        // days from civil algorithm, years start in March so that leap days come last
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146097 + day_of_era - 719468;
+        days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+            - self.offset.unwrap_or(0) as i64 * 60
+    }
+
+    // Unknown offsets are written -00:00 as allowed by RFC 3339
+    pub fn to_rfc3339(&self) -> String {
+        let offset = match self.offset {
+            Some(0) => String::from("Z"),
+            Some(o) => format!(
+                "{}{:02}:{:02}",
+                if o < 0 { '-' } else { '+' },
+                o.abs() / 60,
+                o.abs() % 60
+            ),
+            None => String::from("-00:00"),
+        };
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{offset}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl Display for PdfDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_rfc3339())
+    }
+}
+
+impl TryFrom<&str> for PdfDate {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let invalid = |message: &str| Error::object(format!("Invalid date {value:?}: {message}"));
+        // the D: prefix is frequently omitted
+        let date = value.trim();
+        let date = date.strip_prefix("D:").unwrap_or(date);
+        let digits_end = date
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(date.len());
+        let (digits, rest) = date.split_at(digits_end);
+        // some producers wrote the year 2000 as "19" followed by 100
+        let (year, digits) = match digits.len() % 2 {
+            1 if digits.starts_with("191") => {
+                (1900 + digits[2..5].parse::<i32>().unwrap(), &digits[5..])
+            }
+            0 if digits.len() >= 4 => (digits[..4].parse::<i32>().unwrap(), &digits[4..]),
+            _ => return Err(invalid("year should have 4 digits")),
+        };
+        let field = |index: usize, default: u8, range: std::ops::RangeInclusive<u8>, name: &str| {
+            match digits.get(2 * index..2 * index + 2) {
+                Some(d) => match d.parse::<u8>() {
+                    Ok(n) if range.contains(&n) => Ok(n),
+                    _ => Err(invalid(&format!("{name} is out of range"))),
+                },
+                None => Ok(default),
+            }
+        };
+        if digits.len() > 10 {
+            return Err(invalid("too many digits"));
         }
+        let month = field(0, 1, 1..=12, "month")?;
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        let mut date = PdfDate {
+            year,
+            month,
+            day: field(1, 1, 1..=days, "day")?,
+            hour: field(2, 0, 0..=23, "hour")?,
+            minute: field(3, 0, 0..=59, "minute")?,
+            second: field(4, 0, 0..=59, "second")?,
+            offset: None,
+        };
+        let mut rest = rest.chars();
+        date.offset = match rest.next() {
+            None => None,
+            // Z may be followed by a zero offset such as Z00'00'
+            Some('Z' | 'z') => Some(0),
+            Some(sign @ ('+' | '-')) => {
+                // HH'mm', with apostrophes or colon separators sometimes missing
+                let offset = rest
+                    .filter(|c| c.is_ascii_digit())
+                    .map(|c| c as u8 - b'0')
+                    .collect::<Vec<u8>>();
+                let (hours, minutes) = match offset.as_slice() {
+                    [h1, h2] => (h1 * 10 + h2, 0),
+                    [h1, h2, m1, m2] => (h1 * 10 + h2, m1 * 10 + m2),
+                    _ => return Err(invalid("offset should be HH'mm'")),
+                };
+                if hours > 23 || minutes > 59 {
+                    return Err(invalid("offset is out of range"));
+                }
+                let offset = hours as i16 * 60 + minutes as i16;
+                Some(if sign == '-' { -offset } else { offset })
+            }
+            Some(c) => return Err(invalid(&format!("unexpected character {c:?}"))),
+        };
+        Ok(date)
+    }
+}
+
+// Decode a text string, encoded either in UTF-16BE with a byte order mark or in PDFDocEncoding (page 158)
+pub fn text_string(bytes: &[u8]) -> String {
+    match bytes {
+        [0xfe, 0xff, utf16 @ ..] => {
+            let units = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+            let mut text = String::new();
+            let mut escape = false;
+            for c in char::decode_utf16(units) {
+                match c {
+                    // language and country codes are enclosed in escape characters
+                    Ok('\u{1b}') => escape = !escape,
+                    Ok(_) if escape => (),
+                    Ok(c) => text.push(c),
+                    Err(_) => text.push(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            text
+        }
+        // UTF-8 text strings were introduced in PDF 2.0
+        [0xef, 0xbb, 0xbf, utf8 @ ..] => String::from_utf8_lossy(utf8).into_owned(),
+        _ => bytes.iter().map(|&b| pdf_doc_encoding(b)).collect(),
+    }
+}
+
+// PDFDocEncoding matches Latin-1 except for a few ranges (table D.2, page 1002)
+fn pdf_doc_encoding(byte: u8) -> char {
+    match byte {
+        0x18 => '\u{02d8}', // breve
+        0x19 => '\u{02c7}', // caron
+        0x1a => '\u{02c6}', // circumflex
+        0x1b => '\u{02d9}', // dotaccent
+        0x1c => '\u{02dd}', // hungarumlaut
+        0x1d => '\u{02db}', // ogonek
+        0x1e => '\u{02da}', // ring
+        0x1f => '\u{02dc}', // tilde
+        0x80 => '\u{2022}', // bullet
+        0x81 => '\u{2020}', // dagger
+        0x82 => '\u{2021}', // daggerdbl
+        0x83 => '\u{2026}', // ellipsis
+        0x84 => '\u{2014}', // emdash
+        0x85 => '\u{2013}', // endash
+        0x86 => '\u{0192}', // florin
+        0x87 => '\u{2044}', // fraction
+        0x88 => '\u{2039}', // guilsinglleft
+        0x89 => '\u{203a}', // guilsinglright
+        0x8a => '\u{2212}', // minus
+        0x8b => '\u{2030}', // perthousand
+        0x8c => '\u{201e}', // quotedblbase
+        0x8d => '\u{201c}', // quotedblleft
+        0x8e => '\u{201d}', // quotedblright
+        0x8f => '\u{2018}', // quoteleft
+        0x90 => '\u{2019}', // quoteright
+        0x91 => '\u{201a}', // quotesinglbase
+        0x92 => '\u{2122}', // trademark
+        0x93 => '\u{fb01}', // fi
+        0x94 => '\u{fb02}', // fl
+        0x95 => '\u{0141}', // Lslash
+        0x96 => '\u{0152}', // OE
+        0x97 => '\u{0160}', // Scaron
+        0x98 => '\u{0178}', // Ydieresis
+        0x99 => '\u{017d}', // Zcaron
+        0x9a => '\u{0131}', // dotlessi
+        0x9b => '\u{0142}', // lslash
+        0x9c => '\u{0153}', // oe
+        0x9d => '\u{0161}', // scaron
+        0x9e => '\u{017e}', // zcaron
+        0xa0 => '\u{20ac}', // Euro
+        // undefined codes
+        0x9f | 0xad => char::REPLACEMENT_CHARACTER,
+        b => b as char,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::xref::XRefTable;
+    use crate::xref::{Entry, XRefTable};
 
     use super::*;
 
@@ -84,7 +321,7 @@ mod tests {
     fn test_info_dict_1() {
         let bytes = b"1 0 obj\n<< /Title (sample) /Author (Philip Hutchison) /Creator (Pages) /Producer (Mac OS X 10.5.4 Quartz PDFContext)\n/CreationDate (D:20080701052447Z00'00') /ModDate (D:20080701052447Z00'00')\n>>\nendobj";
         let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
-        let info = Info::new(bytes.as_slice(), 0, xref);
+        let info = Info::new(bytes.as_slice(), 0, xref).unwrap();
         assert_eq!(
             info,
             Info {
@@ -92,9 +329,124 @@ mod tests {
                 author: Some(String::from("Philip Hutchison")),
                 creator: Some(String::from("Pages")),
                 producer: Some(String::from("Mac OS X 10.5.4 Quartz PDFContext")),
-                creation_date: Some(String::from("D:20080701052447Z00'00'")),
-                mod_date: Some(String::from("D:20080701052447Z00'00'"))
+                creation_date: PdfDate::try_from("D:20080701052447Z").ok(),
+                mod_date: PdfDate::try_from("D:20080701052447Z").ok(),
+                ..Default::default()
             }
         );
     }
+
+    #[test]
+    fn test_info_dict_encodings() {
+        let bytes = b"1 0 obj\n<< /Title <FEFF004800e9006c006c006f> /Author (Fran\\347ois \\204 \\223n) /Subject <48656c6c6f>\n/Keywords <FEFF001B656E001BD83DDE00> /Trapped /False /Company (ACME) /Pages 3 /Missing 9 0 R >>\nendobj";
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let info = Info::new(bytes.as_slice(), 0, xref).unwrap();
+        assert_eq!(
+            info,
+            Info {
+                title: Some(String::from("Héllo")),
+                author: Some(String::from("François — ﬁn")),
+                subject: Some(String::from("Hello")),
+                keywords: Some(String::from("😀")),
+                trapped: Some(String::from("False")),
+                custom: BTreeMap::from([(String::from("Company"), String::from("ACME"))]),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            info.to_string(),
+            "Title: Héllo\nAuthor: François — ﬁn\nSubject: Hello\nKeywords: 😀\nTrapped: False\nCompany: ACME"
+        );
+    }
+
+    #[test]
+    fn test_info_dict_invalid_entries() {
+        let bytes = b"1 0 obj\n<< /Title (Title) /Author 12 /Subject [(a)] /Creator 9 0 R /Producer 2 0 R /CreationDate /D /Trapped (True) >>\nendobj\n\
+            2 0 obj\n<< /Length 2 0 R >>\nstream\nendstream\nendobj\n";
+        let entry = |offset| Entry::InUse {
+            offset,
+            generation: 0,
+        };
+        let xref = Rc::new(XRef::XRefTable(XRefTable::from_entries([
+            (1, entry(0)),
+            (2, entry(126)),
+        ])));
+        // standard entries with another type or unresolvable are skipped, the others are kept
+        let info = Info::new(bytes.as_slice(), 0, xref).unwrap();
+        assert_eq!(
+            info,
+            Info {
+                title: Some(String::from("Title")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_pdf_date() {
+        let date = PdfDate::try_from("D:20080701052447+02'30'").unwrap();
+        assert_eq!(
+            date,
+            PdfDate {
+                year: 2008,
+                month: 7,
+                day: 1,
+                hour: 5,
+                minute: 24,
+                second: 47,
+                offset: Some(150),
+            }
+        );
+        assert_eq!(date.timestamp(), 1214880887);
+        assert_eq!(date.to_rfc3339(), "2008-07-01T05:24:47+02:30");
+
+        let date = PdfDate::try_from("D:2008").unwrap();
+        assert_eq!(date.to_rfc3339(), "2008-01-01T00:00:00-00:00");
+        assert_eq!(date.timestamp(), 1199145600);
+
+        let date = PdfDate::try_from("D:20240229235959-0800").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-02-29T23:59:59-08:00");
+        assert_eq!(date.timestamp(), 1709279999);
+
+        assert_eq!(
+            PdfDate::try_from("D:196912312359").unwrap().timestamp(),
+            -60
+        );
+        assert_eq!(
+            PdfDate::try_from("D:20080701052447Z00'00'").unwrap().offset,
+            Some(0)
+        );
+        assert_eq!(
+            PdfDate::try_from("20220403193102+02'00").unwrap().offset,
+            Some(120)
+        );
+        assert_eq!(PdfDate::try_from("D:191000102120000").unwrap().year, 2000);
+        assert_eq!(PdfDate::try_from("D:20000229").unwrap().day, 29);
+        assert_eq!(PdfDate::try_from("D:20081231").unwrap().day, 31);
+    }
+
+    #[test]
+    fn test_pdf_date_invalid() {
+        for date in [
+            "",
+            "D:08",
+            "D:20081301",
+            "D:20240231",
+            "D:20230229",
+            "D:21000229",
+            "D:20080431",
+            "D:2008070125",
+            "D:20080701+1",
+            "D:2008x",
+        ] {
+            assert!(PdfDate::try_from(date).is_err(), "{date}");
+        }
+    }
+
+    #[test]
+    fn test_text_string() {
+        assert_eq!(text_string(b"\xfe\xff\xd8\x00"), "\u{fffd}");
+        assert_eq!(text_string(b"\xef\xbb\xbfna\xc3\xafve"), "naïve");
+        assert_eq!(text_string(b"\x18\xa0\xe9"), "˘€é");
+    }
 }
@@ -0,0 +1,143 @@
+// Glyph painted on a page, positions are given in device space
+#[derive(Debug, PartialEq, Clone)]
+pub struct Glyph {
+    pub text: String,   // unicode text, several chars for ligatures
+    pub x: f32,         // origin on the baseline
+    pub y: f32,         // origin on the baseline
+    pub width: f32,     // horizontal displacement
+    pub font_size: f32, // height of the font
+}
+
+// Gaps between glyphs wider than this fraction of the font size separate words
+// Glyphs of any font are laid out together, so gaps are compared with their size rather than
+// with a space width, content::SPACE_GAP_RATIO gives 0.1 to 0.13 em for usual spaces of 0.25 to 0.33 em
+const LAYOUT_WORD_GAP: f32 = 0.15;
+
+// Lower median of values, None when empty
+fn median(mut values: Vec<f32>) -> Option<f32> {
+    values.sort_by(f32::total_cmp);
+    values.get(values.len().saturating_sub(1) / 2).copied()
+}
+
+// Text of glyphs placed on a character grid, similar to pdftotext -layout
+// Words start on columns given by the median glyph width and lines by the baselines of the glyphs,
+// blank lines are kept where baselines are further apart than the usual line spacing
+pub fn layout(glyphs: &[Glyph]) -> String {
+    let mut glyphs: Vec<&Glyph> = glyphs
+        .iter()
+        .filter(|g| !g.text.chars().all(char::is_whitespace))
+        .collect();
+    let (Some(cell), Some(font_size)) = (
+        median(
+            glyphs
+                .iter()
+                .map(|g| g.width)
+                .filter(|w| *w > 0.0)
+                .collect(),
+        ),
+        median(glyphs.iter().map(|g| g.font_size).collect()),
+    ) else {
+        return String::new();
+    };
+    let left = glyphs.iter().map(|g| g.x).fold(f32::INFINITY, f32::min);
+
+    // glyphs are grouped in lines from the top of the page, the y axis goes up
+    glyphs.sort_by(|a, b| b.y.total_cmp(&a.y));
+    let mut lines: Vec<(f32, Vec<&Glyph>)> = vec![];
+    for glyph in glyphs {
+        match lines.last_mut() {
+            Some((y, line)) if *y - glyph.y < font_size / 2.0 => line.push(glyph),
+            _ => lines.push((glyph.y, vec![glyph])),
+        }
+    }
+    let spacing = median(lines.windows(2).map(|l| l[0].0 - l[1].0).collect())
+        .unwrap_or(font_size * 1.2)
+        .max(font_size / 2.0);
+
+    let mut output = String::new();
+    let mut previous_y: Option<f32> = None;
+    for (y, mut line) in lines {
+        if let Some(previous_y) = previous_y {
+            let blank_lines = ((previous_y - y) / spacing).round() as usize;
+            output.push_str(&"\n".repeat(blank_lines.max(1)));
+        }
+        previous_y = Some(y);
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+        let mut text = String::new();
+        let mut column = 0;
+        let mut previous: Option<&Glyph> = None;
+        for glyph in line {
+            if let Some(previous) = previous {
+                // glyphs painted twice with a small offset, as fake bold text, are kept once
+                if previous.text == glyph.text && glyph.x - previous.x < previous.width / 4.0 {
+                    continue;
+                }
+            }
+            // words start on the column of their position, their glyphs follow each other
+            let word_start = previous.is_none_or(|previous| {
+                glyph.x - (previous.x + previous.width) > glyph.font_size * LAYOUT_WORD_GAP
+            });
+            if word_start {
+                let target = (((glyph.x - left) / cell).round().max(0.0) as usize)
+                    .max(column + usize::from(previous.is_some()));
+                text.extend(std::iter::repeat_n(' ', target - column));
+                column = target;
+            }
+            text.push_str(&glyph.text);
+            column += glyph.text.chars().count();
+            previous = Some(glyph);
+        }
+        output.push_str(text.trim_end());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, x: f32, y: f32) -> Vec<Glyph> {
+        text.chars()
+            .enumerate()
+            .map(|(i, char)| Glyph {
+                text: String::from(char),
+                x: x + i as f32 * 6.0,
+                y,
+                width: 6.0,
+                font_size: 10.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_columns() {
+        let glyphs = [
+            word("Name", 72.0, 700.0),
+            word("Amount", 192.0, 700.0),
+            word("Rent", 72.0, 688.0),
+            word("950", 210.0, 688.0),
+            // painted out of order, after a blank line
+            word("Total", 72.0, 664.0),
+            word("1200", 204.0, 664.0),
+        ]
+        .concat();
+        assert_eq!(
+            layout(&glyphs),
+            "Name                Amount\nRent                   950\n\nTotal                 1200"
+        );
+    }
+
+    #[test]
+    fn test_words() {
+        // the gap between the words is narrower than a grid cell
+        let glyphs = [word("ab", 0.0, 0.0), word("cd", 14.0, 0.5)].concat();
+        assert_eq!(layout(&glyphs), "ab cd");
+        // wide glyphs do not split words, glyphs painted twice are kept once
+        let mut glyphs = word("wmw", 0.0, 0.0);
+        glyphs[1].width = 11.0;
+        glyphs[2].x = 17.0;
+        glyphs.extend(word("w", 0.5, 0.0));
+        assert_eq!(layout(&glyphs), "wmw");
+        assert_eq!(layout(&[]), "");
+    }
+}
@@ -1,23 +1,34 @@
 use std::{fmt::Display, rc::Rc};
 
+use object::Object;
 use xref::XRef;
 
 pub mod algebra;
 pub mod body;
 pub mod cmap;
 pub mod content;
+mod crypto;
+pub mod encoding;
+pub mod encryption;
+pub mod error;
 pub mod filters;
 pub mod info;
+pub mod layout;
+pub mod metrics;
 pub mod object;
 pub mod tokenizer;
 pub mod xref;
 
-#[derive(Debug, Clone)]
+pub use error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Extract {
     Text,
+    Layout,
     Chars,
     Font,
     RawContent,
+    Info,
 }
 
 #[derive(Debug)]
@@ -41,53 +52,249 @@ impl Display for PdfVersion {
     }
 }
 
-pub fn pdf_version(s: &[u8]) -> PdfVersion {
-    match &s[s.len() - 3..] {
-        b"1.7" => PdfVersion::V1_7,
-        b"1.6" => PdfVersion::V1_6,
-        b"1.5" => PdfVersion::V1_5,
-        b"1.4" => PdfVersion::V1_4,
-        b"1.3" => PdfVersion::V1_3,
-        _ => panic!("Pdf version not supported"),
+// Version given by the last bytes of the file header, e.g. %PDF-1.7
+pub fn pdf_version(s: &[u8]) -> Result<PdfVersion, Error> {
+    match s.get(s.len().saturating_sub(3)..) {
+        Some(b"1.7") => Ok(PdfVersion::V1_7),
+        Some(b"1.6") => Ok(PdfVersion::V1_6),
+        Some(b"1.5") => Ok(PdfVersion::V1_5),
+        Some(b"1.4") => Ok(PdfVersion::V1_4),
+        Some(b"1.3") => Ok(PdfVersion::V1_3),
+        _ => Err(Error::Tokenizer {
+            offset: 0,
+            message: format!(
+                "Pdf version not supported in header {:?}",
+                String::from_utf8_lossy(s)
+            ),
+        }),
     }
 }
 
 pub struct Pdf {
     file: Vec<u8>,
-    xref: xref::XRef,
+    // shared by the objects read from the document, with the object streams they decoded
+    xref: Rc<XRef>,
+}
+
+// Settings used to open a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options<'a> {
+    // password of encrypted documents, the empty user password is tried by default
+    pub password: &'a [u8],
+    // decoded streams larger than this are rejected to protect against decompression bombs
+    pub max_decoded_size: usize,
 }
 
-impl From<Vec<u8>> for Pdf {
-    fn from(value: Vec<u8>) -> Self {
-        // remove leading and trailing whitespaces
-        let file = value.trim_ascii();
-        // check file bytes ends with %%EOF
-        if &file[file.len() - 5..] != b"%%EOF" {
-            panic!("PDF file is corrupted; not consistent trailing charaters");
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            password: b"",
+            max_decoded_size: filters::DEFAULT_MAX_DECODED_SIZE,
         }
-        let startxref = xref::startxref(&value);
-        let xref = XRef::new(file, startxref);
-        Pdf { file: value, xref }
+    }
+}
+
+impl TryFrom<Vec<u8>> for Pdf {
+    type Error = Error;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Pdf::try_from_with_options(value, &Options::default())
     }
 }
 
 impl Pdf {
+    pub fn try_from_with_password(value: Vec<u8>, password: &[u8]) -> Result<Self, Error> {
+        Pdf::try_from_with_options(
+            value,
+            &Options {
+                password,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn try_from_with_options(value: Vec<u8>, options: &Options) -> Result<Self, Error> {
+        let Options {
+            password,
+            max_decoded_size,
+        } = *options;
+        // damaged files without a readable cross-reference section are reconstructed from their objects
+        let mut xref = match xref::startxref(&value)
+            .and_then(|offset| XRef::new(&value, offset, max_decoded_size))
+        {
+            Ok(xref) if xref.root().is_some() => xref,
+            Ok(_) => XRef::reconstruct(&value, max_decoded_size)?,
+            Err(e) => XRef::reconstruct(&value, max_decoded_size).map_err(|_| e)?,
+        };
+        if let Some(encrypt) = xref.encrypt() {
+            let security_handler = {
+                let temp_xref = Rc::new(xref.clone());
+                match Object::Ref(encrypt, temp_xref, &value).resolve()? {
+                    Object::Dictionary(dict) => encryption::SecurityHandler::new(
+                        &dict,
+                        xref.file_id().unwrap_or_default(),
+                        password,
+                    )?,
+                    o => {
+                        return Err(Error::encryption(format!(
+                            "Encrypt should be a dictionary, found {o:?}"
+                        )))
+                    }
+                }
+            };
+            xref.set_security_handler(security_handler);
+        }
+        Ok(Pdf {
+            file: value,
+            xref: Rc::new(xref),
+        })
+    }
+
+    // Panics on malformed documents, use Pdf::try_extract to handle errors
     pub fn extract(&self, e: Extract) -> String {
-        let xref = Rc::new(self.xref.clone());
-        let catalog_offset = xref.get_catalog_offset().unwrap();
-        let catalog = Pdf::read_catalog(&self.file, catalog_offset, xref);
-        catalog.extract(e)
+        self.try_extract(e).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    // Extract all pages, joined by a newline
+    pub fn try_extract(&self, e: Extract) -> Result<String, Error> {
+        self.try_extract_with_separator(e, "\n")
+    }
+
+    // Extract all pages, joined by the separator
+    pub fn try_extract_with_separator(&self, e: Extract, separator: &str) -> Result<String, Error> {
+        // document information is read from the trailer rather than from pages
+        if let Extract::Info = e {
+            return Ok(self
+                .info()?
+                .map(|info| info.to_string())
+                .unwrap_or_default());
+        }
+        Ok(self
+            .pages()?
+            .map(|page| page?.extract(e.clone()))
+            .collect::<Result<Vec<String>, Error>>()?
+            .join(separator))
+    }
+
+    // Number of pages in the document
+    pub fn page_count(&self) -> Result<usize, Error> {
+        self.page_tree()?.count()
+    }
+
+    // Page at the given zero-based index, without reading the other pages
+    pub fn page(&self, index: usize) -> Result<body::Page, Error> {
+        self.page_tree()?.page(index)
+    }
+
+    // Pages in document order, each page is read when the iterator reaches it
+    pub fn pages(&self) -> Result<body::Pages<'_>, Error> {
+        self.page_tree()?.pages()
+    }
+
+    fn page_tree(&self) -> Result<body::PageTree<'_>, Error> {
+        let (root, catalog) = self.catalog()?;
+        match catalog.get("Pages").map(Object::resolve).transpose()? {
+            Some(Object::Dictionary(pages)) => Ok(body::PageTree::new(pages)),
+            o => Err(
+                Error::object(format!("Pages should be a dictionary, found {o:?}")).with_id(root),
+            ),
+        }
+    }
+
+    // Document catalog referenced by the trailer, with its object number
+    fn catalog(&self) -> Result<(object::IndirectObject, object::Dictionary<'_>), Error> {
+        let xref = self.xref.clone();
+        let root = xref
+            .root()
+            .ok_or(Error::xref(None, "Catalog not found in trailer"))?;
+        match Object::Ref(root, xref, &self.file).resolve()? {
+            Object::Dictionary(dict) => Ok((root, dict)),
+            o => Err(
+                Error::object(format!("Catalog should be a dictionary, found {o:?}")).with_id(root),
+            ),
+        }
+    }
+
+    // Document information dictionary referenced by the trailer, if any
+    pub fn info(&self) -> Result<Option<info::Info>, Error> {
+        let Some(id) = self.xref.info() else {
+            return Ok(None);
+        };
+        let xref = self.xref.clone();
+        match Object::Ref(id, xref, &self.file).resolve()? {
+            Object::Dictionary(dict) => info::Info::try_from(dict).map(Some),
+            o => {
+                Err(Error::object(format!("Info should be a dictionary, found {o:?}")).with_id(id))
+            }
+        }
+    }
+
+    // Extract the pages at the given zero-based indexes, joined by the separator
+    // Indexes beyond the last page are ignored
+    pub fn try_extract_pages(
+        &self,
+        e: Extract,
+        indexes: &[usize],
+        separator: &str,
+    ) -> Result<String, Error> {
+        if let Extract::Info = e {
+            return self.try_extract(e);
+        }
+        let mut pages = self.pages()?;
+        // index of the page the iterator returns next
+        let mut position = 0;
+        let mut content = vec![];
+        for &index in indexes {
+            if index < position {
+                pages = self.pages()?;
+                position = 0;
+            }
+            match pages.nth(index - position) {
+                Some(page) => content.push(page?.extract(e.clone())?),
+                None => {
+                    pages = self.pages()?;
+                    position = 0;
+                    continue;
+                }
+            }
+            position = index + 1;
+        }
+        Ok(content.join(separator))
     }
 
     pub fn read_catalog(
         file_stream: &[u8],
         curr_idx: usize,
         xref: Rc<xref::XRef>,
-    ) -> body::Catalog {
+    ) -> Result<body::Catalog, Error> {
         body::Catalog::new(file_stream, curr_idx, xref)
     }
 
-    pub fn read_info(file_stream: &[u8], curr_idx: usize, xref: Rc<xref::XRef>) -> info::Info {
+    pub fn read_info(
+        file_stream: &[u8],
+        curr_idx: usize,
+        xref: Rc<xref::XRef>,
+    ) -> Result<info::Info, Error> {
         info::Info::new(file_stream, curr_idx, xref)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_xref() {
+        let file = std::fs::read("data/pdflatex-4-pages.pdf").unwrap();
+        let pdf = Pdf::try_from(file).unwrap();
+        // object streams decoded for a page are reused by the next calls
+        pdf.page(0).unwrap();
+        let object_stream = pdf.xref.cached_object_stream(5).unwrap();
+        pdf.page(1).unwrap();
+        pdf.try_extract(Extract::Text).unwrap();
+        assert!(Rc::ptr_eq(
+            &object_stream,
+            &pdf.xref.cached_object_stream(5).unwrap()
+        ));
+    }
+}
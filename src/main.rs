@@ -2,38 +2,307 @@ use std::env;
 
 use pdfrust::Extract;
 
+const USAGE: &str = "Usage: pdfrust [OPTIONS] <pdf_file>
+
+Extractions, several can be given and are printed in order (default: --text):
+\t--text\t\t\tformatted text
+\t--layout\t\ttext placed on a character grid, preserving columns
+\t--chars\t\t\ttext character font and positionning
+\t--raw-content\t\traw pdf content
+\t--font\t\t\tfont analyzer
+\t--info\t\t\tdocument information
+
+Options:
+\t--pages <ranges>\tpages to extract, numbered from 1, e.g. 1-3,7,10-
+\t--page-separator <sep>\tseparator between pages, escapes \\n \\t \\f are supported (default: \\n)
+\t-o, --output <file>\twrite the output to a file instead of stdout
+\t-h, --help\t\tprint this message";
+
+// Inclusive ranges of pages numbered from 1, an open range extends to the last page
+#[derive(Debug, PartialEq)]
+struct PageRanges(Vec<(usize, Option<usize>)>);
+
+impl PageRanges {
+    // Zero-based indexes of the selected pages, in the order of the ranges
+    fn indexes(&self, page_count: usize) -> Vec<usize> {
+        self.0
+            .iter()
+            .flat_map(|&(start, end)| {
+                let end = end.unwrap_or(page_count).min(page_count);
+                (start..=end).map(|page| page - 1)
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for PageRanges {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let page = |s: &str| match s.trim().parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!(
+                "Invalid page number {s:?} in page ranges {value:?}"
+            )),
+        };
+        value
+            .split(',')
+            .map(|range| match range.split_once('-') {
+                Some((start, end)) => {
+                    let start = match start.trim() {
+                        "" => 1,
+                        s => page(s)?,
+                    };
+                    let end = match end.trim() {
+                        "" => None,
+                        e => Some(page(e)?),
+                    };
+                    match end {
+                        Some(end) if end < start => {
+                            Err(format!("Invalid page range {range:?} in {value:?}"))
+                        }
+                        _ => Ok((start, end)),
+                    }
+                }
+                None => page(range).map(|n| (n, Some(n))),
+            })
+            .collect::<Result<Vec<(usize, Option<usize>)>, String>>()
+            .map(PageRanges)
+    }
+}
+
+#[derive(Debug, PartialEq)]
 struct Config {
     path: String,
-    flags: Extract,
+    flags: Vec<Extract>,
+    pages: Option<PageRanges>,
+    page_separator: String,
+    output: Option<String>,
 }
 
-impl Config {
-    fn new(args: env::Args) -> Config {
-        let args: Vec<String> = args.collect();
-        match args.len() {
-            2 => Config {
-                path: args[1].clone(),
-                flags: pdfrust::Extract::Text,
+// Replace escape sequences typed on the command line, \f is the form feed used by pdftotext
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some('f') => unescaped.push('\x0c'),
+                Some('\\') => unescaped.push('\\'),
+                Some(c) => {
+                    unescaped.push('\\');
+                    unescaped.push(c)
+                }
+                None => unescaped.push('\\'),
             },
-            3 => Config {
-                path: args[2].clone(),
-                flags: match args[1].as_str() {
-                    "--text" => pdfrust::Extract::Text,
-                    "--chars" => pdfrust::Extract::Chars,
-                    "--font" => pdfrust::Extract::Font,
-                    "--raw-content" => pdfrust::Extract::RawContent,
-                    f => panic!("Invalid flag: {f}\nPdfRust currently support:\n\t--text\t\tformatted text\n\t--chars\t\ttext character font and positionning\n\t--raw-content\traw pdf content\n\t--font\t\tfont analyzer"),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+impl Config {
+    // Ok(None) when help is requested
+    fn new(args: impl Iterator<Item = String>) -> Result<Option<Config>, String> {
+        let mut path = None;
+        let mut flags = vec![];
+        let mut pages = None;
+        let mut page_separator = String::from("\n");
+        let mut output = None;
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            // options values are given as the next argument or after an equal sign
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if arg.starts_with("--") => {
+                    (option.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(format!("Missing value for option {name}"))
+            };
+            match option.as_str() {
+                "--text" => flags.push(Extract::Text),
+                "--layout" => flags.push(Extract::Layout),
+                "--chars" => flags.push(Extract::Chars),
+                "--font" => flags.push(Extract::Font),
+                "--raw-content" => flags.push(Extract::RawContent),
+                "--info" => flags.push(Extract::Info),
+                "--pages" => pages = Some(PageRanges::try_from(value("--pages")?.as_str())?),
+                "--page-separator" => page_separator = unescape(&value("--page-separator")?),
+                "-o" | "--output" => output = Some(value("--output")?),
+                "-h" | "--help" => return Ok(None),
+                f if f.starts_with('-') && f.len() > 1 => return Err(format!("Invalid flag: {f}")),
+                _ => match path {
+                    None => path = Some(arg),
+                    Some(_) => return Err(format!("Unexpected argument: {arg}")),
                 },
-            },
-            _ => panic!("CLI should have 2 or 3 arguments"),
+            }
+        }
+        if flags.is_empty() {
+            flags.push(Extract::Text);
+        }
+        Ok(Some(Config {
+            path: path.ok_or("Missing pdf file")?,
+            flags,
+            pages,
+            page_separator,
+            output,
+        }))
+    }
+}
+
+fn run(config: Config) -> Result<(), String> {
+    let file = std::fs::read(&config.path).map_err(|e| format!("{}: {e}", config.path))?;
+    let pdf = pdfrust::Pdf::try_from(file).map_err(|e| e.to_string())?;
+    // page ranges are resolved with the page count, without ranges every page of the tree is read
+    // document information does not require reading the page tree
+    let indexes = match &config.pages {
+        Some(_) if config.flags.iter().all(|flag| *flag == Extract::Info) => None,
+        Some(ranges) => Some(ranges.indexes(pdf.page_count().map_err(|e| e.to_string())?)),
+        None => None,
+    };
+    let content = config
+        .flags
+        .iter()
+        .map(|flag| match &indexes {
+            Some(indexes) => pdf.try_extract_pages(flag.clone(), indexes, &config.page_separator),
+            None => pdf.try_extract_with_separator(flag.clone(), &config.page_separator),
+        })
+        .collect::<Result<Vec<String>, pdfrust::Error>>()
+        .map_err(|e| e.to_string())?
+        .join("\n");
+    match &config.output {
+        Some(path) => std::fs::write(path, content + "\n").map_err(|e| format!("{path}: {e}")),
+        None => {
+            println!("{content}");
+            Ok(())
         }
     }
 }
 
 fn main() {
-    let config = Config::new(env::args());
-    let file = std::fs::read(config.path).unwrap();
-    let pdf = pdfrust::Pdf::from(file);
-    let content = pdf.extract(config.flags);
-    println!("{content}");
+    let config = match Config::new(env::args()) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(config) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &str) -> Result<Option<Config>, String> {
+        Config::new(args.split(' ').map(String::from))
+    }
+
+    #[test]
+    fn test_page_ranges() {
+        let ranges = PageRanges::try_from("1-3,7,10-").unwrap();
+        assert_eq!(
+            ranges,
+            PageRanges(vec![(1, Some(3)), (7, Some(7)), (10, None)])
+        );
+        assert_eq!(ranges.indexes(12), vec![0, 1, 2, 6, 9, 10, 11]);
+        assert_eq!(ranges.indexes(5), vec![0, 1, 2]);
+        assert_eq!(PageRanges::try_from("-2").unwrap().indexes(5), vec![0, 1]);
+        for invalid in ["", "0", "3-1", "a", "1,,2", "1-2-3"] {
+            assert!(PageRanges::try_from(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_config() {
+        assert_eq!(
+            config("pdfrust file.pdf").unwrap(),
+            Some(Config {
+                path: String::from("file.pdf"),
+                flags: vec![Extract::Text],
+                pages: None,
+                page_separator: String::from("\n"),
+                output: None,
+            })
+        );
+        assert_eq!(
+            config("pdfrust --chars --pages 2-3 file.pdf --page-separator=\\f -o out.txt --info")
+                .unwrap(),
+            Some(Config {
+                path: String::from("file.pdf"),
+                flags: vec![Extract::Chars, Extract::Info],
+                pages: Some(PageRanges(vec![(2, Some(3))])),
+                page_separator: String::from("\x0c"),
+                output: Some(String::from("out.txt")),
+            })
+        );
+        assert_eq!(
+            config("pdfrust --layout file.pdf").unwrap().unwrap().flags,
+            vec![Extract::Layout]
+        );
+        assert_eq!(config("pdfrust --help").unwrap(), None);
+        assert!(config("pdfrust").is_err());
+        assert!(config("pdfrust --pages").is_err());
+        assert!(config("pdfrust --unknown file.pdf").is_err());
+        assert!(config("pdfrust a.pdf b.pdf").is_err());
+    }
+
+    #[test]
+    fn test_run_wrong_page_count() {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>",
+            "<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 1 /Resources << /Font << /F1 7 0 R >> >> >>",
+            "<< /Type /Page /Contents 5 0 R >>",
+            "<< /Type /Page /Contents 6 0 R >>",
+            "<< /Length 23 >>\nstream\nBT /F1 10 Tf (p1) Tj ET\nendstream",
+            "<< /Length 23 >>\nstream\nBT /F1 10 Tf (p2) Tj ET\nendstream",
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+        ];
+        let mut file = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        for (number, object) in objects.iter().enumerate() {
+            offsets.push(file.len());
+            file += &format!("{} 0 obj\n{object}\nendobj\n", number + 1);
+        }
+        let startxref = file.len();
+        file += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            file += &format!("{offset:010} 00000 n \n");
+        }
+        file += &format!("trailer\n<< /Size 8 /Root 1 0 R >>\nstartxref\n{startxref}\n%%EOF\n");
+        let path = env::temp_dir().join(format!("pdfrust-count-{}.pdf", std::process::id()));
+        let output = path.with_extension("txt");
+        std::fs::write(&path, file).unwrap();
+        let run_pages = |pages| {
+            let mut config = config(&format!(
+                "pdfrust {} -o {}",
+                path.display(),
+                output.display()
+            ))
+            .unwrap()
+            .unwrap();
+            config.pages = pages;
+            run(config).unwrap();
+            std::fs::read_to_string(&output).unwrap()
+        };
+        // pages beyond the root Count are read when no range is given
+        assert_eq!(run_pages(None), "p1\np2\n");
+        assert_eq!(run_pages(Some(PageRanges(vec![(1, None)]))), "p1\n");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
 }
@@ -0,0 +1,418 @@
+use crate::encoding::BaseEncoding;
+
+// The standard 14 fonts available to every PDF reader (page 416)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StandardFont {
+    Courier { bold: bool, italic: bool },
+    Helvetica { bold: bool, italic: bool },
+    Times { bold: bool, italic: bool },
+    Symbol,
+    ZapfDingbats,
+}
+
+impl StandardFont {
+    // Standard font matching a BaseFont name, with the usual aliases of TrueType fonts
+    // e.g. Arial,Bold, ArialMT, TimesNewRomanPS-BoldItalicMT or a subset ABCDEF+Helvetica
+    pub fn from_base_font(base_font: &str) -> Option<StandardFont> {
+        let name = match base_font.split_once('+') {
+            Some((tag, name)) if tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()) => {
+                name
+            }
+            _ => base_font,
+        };
+        let name = name.replace(' ', "");
+        let (family, style) = match name.find([',', '-']) {
+            Some(i) => (&name[..i], &name[i + 1..]),
+            None => (name.as_str(), ""),
+        };
+        let bold = style.contains("Bold") || family.ends_with("Bold");
+        let italic =
+            style.contains("Italic") || style.contains("Oblique") || family.ends_with("Italic");
+        let family = family.trim_end_matches("Italic").trim_end_matches("Bold");
+        match family {
+            "Courier" | "CourierNew" | "CourierNewPS" | "CourierNewPSMT" => {
+                Some(StandardFont::Courier { bold, italic })
+            }
+            "Helvetica" | "Arial" | "ArialMT" => Some(StandardFont::Helvetica { bold, italic }),
+            "Times" | "TimesNewRoman" | "TimesNewRomanPS" | "TimesNewRomanPSMT" => {
+                Some(StandardFont::Times { bold, italic })
+            }
+            "Symbol" => Some(StandardFont::Symbol),
+            "ZapfDingbats" => Some(StandardFont::ZapfDingbats),
+            _ => None,
+        }
+    }
+
+    // Glyph width in thousandths of text space unit, from the Adobe Core14 AFM files
+    pub fn width(&self, glyph_name: &str) -> Option<u16> {
+        let widths: &[(&str, u16)] = match self {
+            StandardFont::Courier { .. } => return Some(600),
+            StandardFont::Helvetica { bold: false, .. } => &HELVETICA,
+            StandardFont::Helvetica { bold: true, .. } => &HELVETICA_BOLD,
+            StandardFont::Times {
+                bold: false,
+                italic: false,
+            } => &TIMES_ROMAN,
+            StandardFont::Times {
+                bold: true,
+                italic: false,
+            } => &TIMES_BOLD,
+            StandardFont::Times {
+                bold: false,
+                italic: true,
+            } => &TIMES_ITALIC,
+            StandardFont::Times {
+                bold: true,
+                italic: true,
+            } => &TIMES_BOLD_ITALIC,
+            StandardFont::Symbol | StandardFont::ZapfDingbats => return None,
+        };
+        let search = |name: &str| {
+            widths
+                .binary_search_by(|(n, _)| n.cmp(&name))
+                .ok()
+                .map(|i| widths[i].1)
+        };
+        // accented letters have the width of their base letter
+        search(glyph_name).or_else(|| match glyph_name.split_at_checked(1) {
+            Some((letter, accent)) if ACCENTS.contains(&accent) => search(letter),
+            _ => None,
+        })
+    }
+
+    // Width of a code in the built-in encoding of the font, used when the font has no encoding
+    pub fn code_width(&self, code: u8) -> Option<u16> {
+        match self {
+            StandardFont::Symbol => match code {
+                32..=126 => Some(SYMBOL[usize::from(code) - 32]),
+                _ => None,
+            },
+            StandardFont::ZapfDingbats => None,
+            _ => BaseEncoding::Standard
+                .glyph_name(code)
+                .and_then(|name| self.width(name)),
+        }
+    }
+}
+
+const ACCENTS: [&str; 14] = [
+    "acute",
+    "breve",
+    "caron",
+    "cedilla",
+    "circumflex",
+    "commaaccent",
+    "dieresis",
+    "dotaccent",
+    "grave",
+    "hungarumlaut",
+    "macron",
+    "ogonek",
+    "ring",
+    "tilde",
+];
+
+#[rustfmt::skip]
+const HELVETICA: [(&str, u16); 170] = [
+    ("A", 667), ("AE", 1000), ("B", 667), ("C", 722), ("D", 722), ("E", 667),
+    ("Eth", 722), ("F", 611), ("G", 778), ("H", 722), ("I", 278), ("J", 500),
+    ("K", 667), ("L", 556), ("Lslash", 556), ("M", 833), ("N", 722), ("O", 778),
+    ("OE", 1000), ("Oslash", 778), ("P", 667), ("Q", 778), ("R", 722), ("S", 667),
+    ("T", 611), ("Thorn", 667), ("U", 722), ("V", 667), ("W", 944), ("X", 667),
+    ("Y", 667), ("Z", 611), ("a", 556), ("acute", 333), ("ae", 889), ("ampersand", 667),
+    ("asciicircum", 469), ("asciitilde", 584), ("asterisk", 389), ("at", 1015), ("b", 556), ("backslash", 278),
+    ("bar", 260), ("braceleft", 334), ("braceright", 334), ("bracketleft", 278), ("bracketright", 278), ("breve", 333),
+    ("brokenbar", 260), ("bullet", 350), ("c", 500), ("caron", 333), ("cedilla", 333), ("cent", 556),
+    ("circumflex", 333), ("colon", 278), ("comma", 278), ("copyright", 737), ("currency", 556), ("d", 556),
+    ("dagger", 556), ("daggerdbl", 556), ("degree", 400), ("dieresis", 333), ("divide", 584), ("dollar", 556),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 556), ("eight", 556), ("ellipsis", 1000), ("emdash", 1000),
+    ("endash", 556), ("equal", 584), ("eth", 556), ("exclam", 278), ("exclamdown", 333), ("f", 278),
+    ("fi", 500), ("five", 556), ("fl", 500), ("florin", 556), ("four", 556), ("fraction", 167),
+    ("g", 556), ("germandbls", 611), ("grave", 333), ("greater", 584), ("guillemotleft", 556), ("guillemotright", 556),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 556), ("hungarumlaut", 333), ("hyphen", 333), ("i", 222),
+    ("j", 222), ("k", 500), ("l", 222), ("less", 584), ("logicalnot", 584), ("lslash", 222),
+    ("m", 833), ("macron", 333), ("minus", 584), ("mu", 556), ("multiply", 584), ("n", 556),
+    ("nine", 556), ("numbersign", 556), ("o", 556), ("oe", 944), ("ogonek", 333), ("one", 556),
+    ("onehalf", 834), ("onequarter", 834), ("onesuperior", 333), ("ordfeminine", 370), ("ordmasculine", 365), ("oslash", 611),
+    ("p", 556), ("paragraph", 537), ("parenleft", 333), ("parenright", 333), ("percent", 889), ("period", 278),
+    ("periodcentered", 278), ("perthousand", 1000), ("plus", 584), ("plusminus", 584), ("q", 556), ("question", 556),
+    ("questiondown", 611), ("quotedbl", 355), ("quotedblbase", 333), ("quotedblleft", 333), ("quotedblright", 333), ("quoteleft", 222),
+    ("quoteright", 222), ("quotesinglbase", 222), ("quotesingle", 191), ("r", 333), ("registered", 737), ("ring", 333),
+    ("s", 500), ("section", 556), ("semicolon", 278), ("seven", 556), ("six", 556), ("slash", 278),
+    ("space", 278), ("sterling", 556), ("t", 278), ("thorn", 556), ("three", 556), ("threequarters", 834),
+    ("threesuperior", 333), ("tilde", 333), ("trademark", 1000), ("two", 556), ("twosuperior", 333), ("u", 556),
+    ("underscore", 556), ("v", 500), ("w", 722), ("x", 500), ("y", 500), ("yen", 556),
+    ("z", 500), ("zero", 556),
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD: [(&str, u16); 170] = [
+    ("A", 722), ("AE", 1000), ("B", 722), ("C", 722), ("D", 722), ("E", 667),
+    ("Eth", 722), ("F", 611), ("G", 778), ("H", 722), ("I", 278), ("J", 556),
+    ("K", 722), ("L", 611), ("Lslash", 611), ("M", 833), ("N", 722), ("O", 778),
+    ("OE", 1000), ("Oslash", 778), ("P", 667), ("Q", 778), ("R", 722), ("S", 667),
+    ("T", 611), ("Thorn", 667), ("U", 722), ("V", 667), ("W", 944), ("X", 667),
+    ("Y", 667), ("Z", 611), ("a", 556), ("acute", 333), ("ae", 889), ("ampersand", 722),
+    ("asciicircum", 584), ("asciitilde", 584), ("asterisk", 389), ("at", 975), ("b", 611), ("backslash", 278),
+    ("bar", 280), ("braceleft", 389), ("braceright", 389), ("bracketleft", 333), ("bracketright", 333), ("breve", 333),
+    ("brokenbar", 280), ("bullet", 350), ("c", 556), ("caron", 333), ("cedilla", 333), ("cent", 556),
+    ("circumflex", 333), ("colon", 333), ("comma", 278), ("copyright", 737), ("currency", 556), ("d", 611),
+    ("dagger", 556), ("daggerdbl", 556), ("degree", 400), ("dieresis", 333), ("divide", 584), ("dollar", 556),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 556), ("eight", 556), ("ellipsis", 1000), ("emdash", 1000),
+    ("endash", 556), ("equal", 584), ("eth", 611), ("exclam", 333), ("exclamdown", 333), ("f", 333),
+    ("fi", 611), ("five", 556), ("fl", 611), ("florin", 556), ("four", 556), ("fraction", 167),
+    ("g", 611), ("germandbls", 611), ("grave", 333), ("greater", 584), ("guillemotleft", 556), ("guillemotright", 556),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 611), ("hungarumlaut", 333), ("hyphen", 333), ("i", 278),
+    ("j", 278), ("k", 556), ("l", 278), ("less", 584), ("logicalnot", 584), ("lslash", 278),
+    ("m", 889), ("macron", 333), ("minus", 584), ("mu", 611), ("multiply", 584), ("n", 611),
+    ("nine", 556), ("numbersign", 556), ("o", 611), ("oe", 944), ("ogonek", 333), ("one", 556),
+    ("onehalf", 834), ("onequarter", 834), ("onesuperior", 333), ("ordfeminine", 370), ("ordmasculine", 365), ("oslash", 611),
+    ("p", 611), ("paragraph", 556), ("parenleft", 333), ("parenright", 333), ("percent", 889), ("period", 278),
+    ("periodcentered", 278), ("perthousand", 1000), ("plus", 584), ("plusminus", 584), ("q", 611), ("question", 611),
+    ("questiondown", 611), ("quotedbl", 474), ("quotedblbase", 500), ("quotedblleft", 500), ("quotedblright", 500), ("quoteleft", 278),
+    ("quoteright", 278), ("quotesinglbase", 278), ("quotesingle", 238), ("r", 389), ("registered", 737), ("ring", 333),
+    ("s", 556), ("section", 556), ("semicolon", 333), ("seven", 556), ("six", 556), ("slash", 278),
+    ("space", 278), ("sterling", 556), ("t", 333), ("thorn", 611), ("three", 556), ("threequarters", 834),
+    ("threesuperior", 333), ("tilde", 333), ("trademark", 1000), ("two", 556), ("twosuperior", 333), ("u", 611),
+    ("underscore", 556), ("v", 556), ("w", 778), ("x", 556), ("y", 556), ("yen", 556),
+    ("z", 500), ("zero", 556),
+];
+
+#[rustfmt::skip]
+const TIMES_ROMAN: [(&str, u16); 170] = [
+    ("A", 722), ("AE", 889), ("B", 667), ("C", 667), ("D", 722), ("E", 611),
+    ("Eth", 722), ("F", 556), ("G", 722), ("H", 722), ("I", 333), ("J", 389),
+    ("K", 722), ("L", 611), ("Lslash", 611), ("M", 889), ("N", 722), ("O", 722),
+    ("OE", 889), ("Oslash", 722), ("P", 556), ("Q", 722), ("R", 667), ("S", 556),
+    ("T", 611), ("Thorn", 556), ("U", 722), ("V", 722), ("W", 944), ("X", 722),
+    ("Y", 722), ("Z", 611), ("a", 444), ("acute", 333), ("ae", 667), ("ampersand", 778),
+    ("asciicircum", 469), ("asciitilde", 541), ("asterisk", 500), ("at", 921), ("b", 500), ("backslash", 278),
+    ("bar", 200), ("braceleft", 480), ("braceright", 480), ("bracketleft", 333), ("bracketright", 333), ("breve", 333),
+    ("brokenbar", 200), ("bullet", 350), ("c", 444), ("caron", 333), ("cedilla", 333), ("cent", 500),
+    ("circumflex", 333), ("colon", 278), ("comma", 250), ("copyright", 760), ("currency", 500), ("d", 500),
+    ("dagger", 500), ("daggerdbl", 500), ("degree", 400), ("dieresis", 333), ("divide", 564), ("dollar", 500),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 444), ("eight", 500), ("ellipsis", 1000), ("emdash", 1000),
+    ("endash", 500), ("equal", 564), ("eth", 500), ("exclam", 333), ("exclamdown", 333), ("f", 333),
+    ("fi", 556), ("five", 500), ("fl", 556), ("florin", 500), ("four", 500), ("fraction", 167),
+    ("g", 500), ("germandbls", 500), ("grave", 333), ("greater", 564), ("guillemotleft", 500), ("guillemotright", 500),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 500), ("hungarumlaut", 333), ("hyphen", 333), ("i", 278),
+    ("j", 278), ("k", 500), ("l", 278), ("less", 564), ("logicalnot", 564), ("lslash", 278),
+    ("m", 778), ("macron", 333), ("minus", 564), ("mu", 500), ("multiply", 564), ("n", 500),
+    ("nine", 500), ("numbersign", 500), ("o", 500), ("oe", 722), ("ogonek", 333), ("one", 500),
+    ("onehalf", 750), ("onequarter", 750), ("onesuperior", 300), ("ordfeminine", 276), ("ordmasculine", 310), ("oslash", 500),
+    ("p", 500), ("paragraph", 453), ("parenleft", 333), ("parenright", 333), ("percent", 833), ("period", 250),
+    ("periodcentered", 250), ("perthousand", 1000), ("plus", 564), ("plusminus", 564), ("q", 500), ("question", 444),
+    ("questiondown", 444), ("quotedbl", 408), ("quotedblbase", 444), ("quotedblleft", 444), ("quotedblright", 444), ("quoteleft", 333),
+    ("quoteright", 333), ("quotesinglbase", 333), ("quotesingle", 180), ("r", 333), ("registered", 760), ("ring", 333),
+    ("s", 389), ("section", 500), ("semicolon", 278), ("seven", 500), ("six", 500), ("slash", 278),
+    ("space", 250), ("sterling", 500), ("t", 278), ("thorn", 500), ("three", 500), ("threequarters", 750),
+    ("threesuperior", 300), ("tilde", 333), ("trademark", 980), ("two", 500), ("twosuperior", 300), ("u", 500),
+    ("underscore", 500), ("v", 500), ("w", 722), ("x", 500), ("y", 500), ("yen", 500),
+    ("z", 444), ("zero", 500),
+];
+
+#[rustfmt::skip]
+const TIMES_BOLD: [(&str, u16); 170] = [
+    ("A", 722), ("AE", 1000), ("B", 667), ("C", 722), ("D", 722), ("E", 667),
+    ("Eth", 722), ("F", 611), ("G", 778), ("H", 778), ("I", 389), ("J", 500),
+    ("K", 778), ("L", 667), ("Lslash", 667), ("M", 944), ("N", 722), ("O", 778),
+    ("OE", 1000), ("Oslash", 778), ("P", 611), ("Q", 778), ("R", 722), ("S", 556),
+    ("T", 667), ("Thorn", 611), ("U", 722), ("V", 722), ("W", 1000), ("X", 722),
+    ("Y", 722), ("Z", 667), ("a", 500), ("acute", 333), ("ae", 722), ("ampersand", 833),
+    ("asciicircum", 581), ("asciitilde", 520), ("asterisk", 500), ("at", 930), ("b", 556), ("backslash", 278),
+    ("bar", 220), ("braceleft", 394), ("braceright", 394), ("bracketleft", 333), ("bracketright", 333), ("breve", 333),
+    ("brokenbar", 220), ("bullet", 350), ("c", 444), ("caron", 333), ("cedilla", 333), ("cent", 500),
+    ("circumflex", 333), ("colon", 333), ("comma", 250), ("copyright", 747), ("currency", 500), ("d", 556),
+    ("dagger", 500), ("daggerdbl", 500), ("degree", 400), ("dieresis", 333), ("divide", 570), ("dollar", 500),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 444), ("eight", 500), ("ellipsis", 1000), ("emdash", 1000),
+    ("endash", 500), ("equal", 570), ("eth", 500), ("exclam", 333), ("exclamdown", 333), ("f", 333),
+    ("fi", 556), ("five", 500), ("fl", 556), ("florin", 500), ("four", 500), ("fraction", 167),
+    ("g", 500), ("germandbls", 556), ("grave", 333), ("greater", 570), ("guillemotleft", 500), ("guillemotright", 500),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 556), ("hungarumlaut", 333), ("hyphen", 333), ("i", 278),
+    ("j", 333), ("k", 556), ("l", 278), ("less", 570), ("logicalnot", 570), ("lslash", 278),
+    ("m", 833), ("macron", 333), ("minus", 570), ("mu", 556), ("multiply", 570), ("n", 556),
+    ("nine", 500), ("numbersign", 500), ("o", 500), ("oe", 722), ("ogonek", 333), ("one", 500),
+    ("onehalf", 750), ("onequarter", 750), ("onesuperior", 300), ("ordfeminine", 300), ("ordmasculine", 330), ("oslash", 500),
+    ("p", 556), ("paragraph", 540), ("parenleft", 333), ("parenright", 333), ("percent", 1000), ("period", 250),
+    ("periodcentered", 250), ("perthousand", 1000), ("plus", 570), ("plusminus", 570), ("q", 556), ("question", 500),
+    ("questiondown", 500), ("quotedbl", 555), ("quotedblbase", 500), ("quotedblleft", 500), ("quotedblright", 500), ("quoteleft", 333),
+    ("quoteright", 333), ("quotesinglbase", 333), ("quotesingle", 278), ("r", 444), ("registered", 747), ("ring", 333),
+    ("s", 389), ("section", 500), ("semicolon", 333), ("seven", 500), ("six", 500), ("slash", 278),
+    ("space", 250), ("sterling", 500), ("t", 333), ("thorn", 556), ("three", 500), ("threequarters", 750),
+    ("threesuperior", 300), ("tilde", 333), ("trademark", 1000), ("two", 500), ("twosuperior", 300), ("u", 556),
+    ("underscore", 500), ("v", 500), ("w", 722), ("x", 500), ("y", 500), ("yen", 500),
+    ("z", 444), ("zero", 500),
+];
+
+#[rustfmt::skip]
+const TIMES_ITALIC: [(&str, u16); 170] = [
+    ("A", 611), ("AE", 889), ("B", 611), ("C", 667), ("D", 722), ("E", 611),
+    ("Eth", 722), ("F", 611), ("G", 722), ("H", 722), ("I", 333), ("J", 444),
+    ("K", 667), ("L", 556), ("Lslash", 556), ("M", 833), ("N", 667), ("O", 722),
+    ("OE", 944), ("Oslash", 722), ("P", 611), ("Q", 722), ("R", 611), ("S", 500),
+    ("T", 556), ("Thorn", 611), ("U", 722), ("V", 611), ("W", 833), ("X", 611),
+    ("Y", 556), ("Z", 556), ("a", 500), ("acute", 333), ("ae", 667), ("ampersand", 778),
+    ("asciicircum", 422), ("asciitilde", 541), ("asterisk", 500), ("at", 920), ("b", 500), ("backslash", 278),
+    ("bar", 275), ("braceleft", 400), ("braceright", 400), ("bracketleft", 389), ("bracketright", 389), ("breve", 333),
+    ("brokenbar", 275), ("bullet", 350), ("c", 444), ("caron", 333), ("cedilla", 333), ("cent", 500),
+    ("circumflex", 333), ("colon", 333), ("comma", 250), ("copyright", 760), ("currency", 500), ("d", 500),
+    ("dagger", 500), ("daggerdbl", 500), ("degree", 400), ("dieresis", 333), ("divide", 675), ("dollar", 500),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 444), ("eight", 500), ("ellipsis", 889), ("emdash", 889),
+    ("endash", 500), ("equal", 675), ("eth", 500), ("exclam", 333), ("exclamdown", 389), ("f", 278),
+    ("fi", 500), ("five", 500), ("fl", 500), ("florin", 500), ("four", 500), ("fraction", 167),
+    ("g", 500), ("germandbls", 500), ("grave", 333), ("greater", 675), ("guillemotleft", 500), ("guillemotright", 500),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 500), ("hungarumlaut", 333), ("hyphen", 333), ("i", 278),
+    ("j", 278), ("k", 444), ("l", 278), ("less", 675), ("logicalnot", 675), ("lslash", 278),
+    ("m", 722), ("macron", 333), ("minus", 675), ("mu", 500), ("multiply", 675), ("n", 500),
+    ("nine", 500), ("numbersign", 500), ("o", 500), ("oe", 667), ("ogonek", 333), ("one", 500),
+    ("onehalf", 750), ("onequarter", 750), ("onesuperior", 300), ("ordfeminine", 276), ("ordmasculine", 310), ("oslash", 500),
+    ("p", 500), ("paragraph", 523), ("parenleft", 333), ("parenright", 333), ("percent", 833), ("period", 250),
+    ("periodcentered", 250), ("perthousand", 1000), ("plus", 675), ("plusminus", 675), ("q", 500), ("question", 500),
+    ("questiondown", 500), ("quotedbl", 420), ("quotedblbase", 556), ("quotedblleft", 556), ("quotedblright", 556), ("quoteleft", 333),
+    ("quoteright", 333), ("quotesinglbase", 333), ("quotesingle", 214), ("r", 389), ("registered", 760), ("ring", 333),
+    ("s", 389), ("section", 500), ("semicolon", 333), ("seven", 500), ("six", 500), ("slash", 278),
+    ("space", 250), ("sterling", 500), ("t", 278), ("thorn", 500), ("three", 500), ("threequarters", 750),
+    ("threesuperior", 300), ("tilde", 333), ("trademark", 980), ("two", 500), ("twosuperior", 300), ("u", 500),
+    ("underscore", 500), ("v", 444), ("w", 667), ("x", 444), ("y", 444), ("yen", 500),
+    ("z", 389), ("zero", 500),
+];
+
+#[rustfmt::skip]
+const TIMES_BOLD_ITALIC: [(&str, u16); 170] = [
+    ("A", 667), ("AE", 944), ("B", 667), ("C", 667), ("D", 722), ("E", 667),
+    ("Eth", 722), ("F", 667), ("G", 722), ("H", 778), ("I", 389), ("J", 500),
+    ("K", 667), ("L", 611), ("Lslash", 611), ("M", 889), ("N", 722), ("O", 722),
+    ("OE", 944), ("Oslash", 722), ("P", 611), ("Q", 722), ("R", 667), ("S", 556),
+    ("T", 611), ("Thorn", 611), ("U", 722), ("V", 667), ("W", 889), ("X", 667),
+    ("Y", 611), ("Z", 611), ("a", 500), ("acute", 333), ("ae", 722), ("ampersand", 778),
+    ("asciicircum", 570), ("asciitilde", 570), ("asterisk", 500), ("at", 832), ("b", 500), ("backslash", 278),
+    ("bar", 220), ("braceleft", 348), ("braceright", 348), ("bracketleft", 333), ("bracketright", 333), ("breve", 333),
+    ("brokenbar", 220), ("bullet", 350), ("c", 444), ("caron", 333), ("cedilla", 333), ("cent", 500),
+    ("circumflex", 333), ("colon", 333), ("comma", 250), ("copyright", 747), ("currency", 500), ("d", 500),
+    ("dagger", 500), ("daggerdbl", 500), ("degree", 400), ("dieresis", 333), ("divide", 570), ("dollar", 500),
+    ("dotaccent", 333), ("dotlessi", 278), ("e", 444), ("eight", 500), ("ellipsis", 1000), ("emdash", 1000),
+    ("endash", 500), ("equal", 570), ("eth", 500), ("exclam", 389), ("exclamdown", 389), ("f", 333),
+    ("fi", 556), ("five", 500), ("fl", 556), ("florin", 500), ("four", 500), ("fraction", 167),
+    ("g", 500), ("germandbls", 500), ("grave", 333), ("greater", 570), ("guillemotleft", 500), ("guillemotright", 500),
+    ("guilsinglleft", 333), ("guilsinglright", 333), ("h", 556), ("hungarumlaut", 333), ("hyphen", 333), ("i", 278),
+    ("j", 278), ("k", 500), ("l", 278), ("less", 570), ("logicalnot", 606), ("lslash", 278),
+    ("m", 778), ("macron", 333), ("minus", 606), ("mu", 576), ("multiply", 570), ("n", 556),
+    ("nine", 500), ("numbersign", 500), ("o", 500), ("oe", 722), ("ogonek", 333), ("one", 500),
+    ("onehalf", 750), ("onequarter", 750), ("onesuperior", 300), ("ordfeminine", 266), ("ordmasculine", 300), ("oslash", 500),
+    ("p", 500), ("paragraph", 500), ("parenleft", 333), ("parenright", 333), ("percent", 833), ("period", 250),
+    ("periodcentered", 250), ("perthousand", 1000), ("plus", 570), ("plusminus", 570), ("q", 500), ("question", 500),
+    ("questiondown", 500), ("quotedbl", 555), ("quotedblbase", 500), ("quotedblleft", 500), ("quotedblright", 500), ("quoteleft", 333),
+    ("quoteright", 333), ("quotesinglbase", 333), ("quotesingle", 278), ("r", 389), ("registered", 747), ("ring", 333),
+    ("s", 389), ("section", 500), ("semicolon", 333), ("seven", 500), ("six", 500), ("slash", 278),
+    ("space", 250), ("sterling", 500), ("t", 278), ("thorn", 500), ("three", 500), ("threequarters", 750),
+    ("threesuperior", 300), ("tilde", 333), ("trademark", 1000), ("two", 500), ("twosuperior", 300), ("u", 556),
+    ("underscore", 500), ("v", 444), ("w", 667), ("x", 500), ("y", 444), ("yen", 500),
+    ("z", 389), ("zero", 500),
+];
+
+// Symbol built-in encoding, codes 32 to 126
+#[rustfmt::skip]
+const SYMBOL: [u16; 95] = [
+    250, 333, 713, 500, 549, 833, 778, 439, 333, 333, 500, 549, 250, 549, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 549, 549, 549, 444,
+    549, 722, 667, 722, 612, 611, 763, 603, 722, 333, 631, 722, 686, 889, 722, 722,
+    768, 741, 556, 592, 611, 690, 439, 768, 645, 795, 611, 333, 863, 333, 658, 500,
+    500, 631, 549, 549, 494, 439, 521, 411, 603, 329, 603, 549, 549, 576, 521, 549,
+    549, 521, 549, 603, 439, 576, 713, 686, 493, 686, 494, 480, 200, 480, 549,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_font_aliases() {
+        let helvetica = |bold, italic| Some(StandardFont::Helvetica { bold, italic });
+        let times = |bold, italic| Some(StandardFont::Times { bold, italic });
+        assert_eq!(
+            StandardFont::from_base_font("Helvetica"),
+            helvetica(false, false)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("Helvetica-BoldOblique"),
+            helvetica(true, true)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("ArialMT"),
+            helvetica(false, false)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("Arial,Bold"),
+            helvetica(true, false)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("Arial-ItalicMT"),
+            helvetica(false, true)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("Times-Roman"),
+            times(false, false)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("TimesNewRoman"),
+            times(false, false)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("TimesNewRomanPS-BoldItalicMT"),
+            times(true, true)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("ABCDEF+TimesNewRoman,Italic"),
+            times(false, true)
+        );
+        assert_eq!(
+            StandardFont::from_base_font("CourierNew,BoldItalic"),
+            Some(StandardFont::Courier {
+                bold: true,
+                italic: true
+            })
+        );
+        assert_eq!(
+            StandardFont::from_base_font("Symbol"),
+            Some(StandardFont::Symbol)
+        );
+        assert_eq!(StandardFont::from_base_font("Calibri"), None);
+        assert_eq!(StandardFont::from_base_font("ABCDEF+Calibri-Bold"), None);
+    }
+
+    #[test]
+    fn test_widths() {
+        let helvetica = StandardFont::Helvetica {
+            bold: false,
+            italic: false,
+        };
+        assert_eq!(helvetica.width("H"), Some(722));
+        assert_eq!(helvetica.width("space"), Some(278));
+        assert_eq!(helvetica.width("eacute"), Some(556));
+        assert_eq!(helvetica.width("unknown"), None);
+        assert_eq!(helvetica.code_width(b'W'), Some(944));
+        let times_bold = StandardFont::Times {
+            bold: true,
+            italic: false,
+        };
+        assert_eq!(times_bold.width("W"), Some(1000));
+        assert_eq!(times_bold.width("Aring"), Some(722));
+        let courier = StandardFont::Courier {
+            bold: false,
+            italic: true,
+        };
+        assert_eq!(courier.code_width(b'i'), Some(600));
+        assert_eq!(StandardFont::Symbol.code_width(b'a'), Some(631));
+        assert_eq!(StandardFont::ZapfDingbats.code_width(b'a'), None);
+        for table in [
+            &HELVETICA,
+            &HELVETICA_BOLD,
+            &TIMES_ROMAN,
+            &TIMES_BOLD,
+            &TIMES_ITALIC,
+            &TIMES_BOLD_ITALIC,
+        ] {
+            assert!(table.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+    }
+}
@@ -2,7 +2,13 @@
 pub use crate::tokenizer::{Lemmatizer, Token};
 use std::{collections::HashMap, rc::Rc};
 
-use crate::{algebra::Number, tokenizer::Tokenizer, xref::XRef};
+use crate::{
+    algebra::Number,
+    filters::{decode, stream_filters},
+    tokenizer::Tokenizer,
+    xref::{ObjectLocation, XRef},
+    Error,
+};
 
 pub type Name = String;
 pub type IndirectObject = (i32, i32);
@@ -13,11 +19,26 @@ pub type Dictionary<'a> = HashMap<Name, Object<'a>>;
 pub struct Stream<'a> {
     pub header: Dictionary<'a>,
     pub bytes: Vec<u8>,
+    // upper bound of the size of the decoded data, set by the document the stream is read from
+    pub max_decoded_size: usize,
 }
 
 impl<'a> Stream<'a> {
-    fn new(header: Dictionary<'a>, bytes: Vec<u8>) -> Self {
-        Stream { header, bytes }
+    fn new(header: Dictionary<'a>, bytes: Vec<u8>, max_decoded_size: usize) -> Self {
+        Stream {
+            header,
+            bytes,
+            max_decoded_size,
+        }
+    }
+
+    // Data of the stream once its filters are applied
+    pub fn decode(&self) -> Result<Vec<u8>, Error> {
+        decode(
+            &self.bytes,
+            &stream_filters(&self.header)?,
+            self.max_decoded_size,
+        )
     }
 }
 
@@ -27,21 +48,27 @@ pub enum Object<'a> {
     Stream(Stream<'a>),
     Array(Array<'a>),
     Name(Name),
-    String(String),
+    String(Vec<u8>),
     HexString(Vec<u8>),
     Numeric(Number),
+    Boolean(bool),
+    Null,
     Ref(IndirectObject, Rc<XRef>, &'a [u8]),
 }
 
 impl<'a> TryFrom<&mut Lemmatizer<'a>> for Array<'a> {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(lemmatizer: &mut Lemmatizer<'a>) -> Result<Self, Self::Error> {
         let mut array = Array::new();
-        for t in lemmatizer.by_ref() {
+        while let Some(t) = lemmatizer.next() {
             match t {
                 Token::ArrayEnd => break,
-                _ => array.push(Object::try_from(t).unwrap()),
+                Token::ArrayBegin => array.push(Object::Array(Array::try_from(&mut *lemmatizer)?)),
+                Token::DictBegin => {
+                    array.push(Object::Dictionary(Dictionary::try_from(&mut *lemmatizer)?))
+                }
+                _ => array.push(Object::try_from(t)?),
             }
         }
         Ok(array)
@@ -49,7 +76,7 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Array<'a> {
 }
 
 impl<'a> TryFrom<&mut Lemmatizer<'a>> for Dictionary<'a> {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(tokenizer: &mut Lemmatizer<'a>) -> Result<Self, Self::Error> {
         let mut dict = Dictionary::new();
@@ -59,33 +86,48 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Dictionary<'a> {
                     let key = name;
                     let value = match tokenizer.next() {
                         Some(Token::DictBegin) => {
-                            Object::Dictionary(Dictionary::try_from(&mut *tokenizer).unwrap())
-                        }
-                        Some(Token::ArrayBegin) => {
-                            Object::Array(Array::try_from(&mut *tokenizer).unwrap())
-                        }
-                        Some(Token::LitteralString(s)) => {
-                            Object::String(String::from(std::str::from_utf8(&s).unwrap()))
-                        }
-                        Some(Token::String(s)) => {
-                            Object::Name(String::from(std::str::from_utf8(&s).unwrap()))
+                            Object::Dictionary(Dictionary::try_from(&mut *tokenizer)?)
                         }
+                        Some(Token::ArrayBegin) => Object::Array(Array::try_from(&mut *tokenizer)?),
+                        Some(Token::LitteralString(s)) => Object::String(s),
+                        Some(Token::String(s)) => Object::Name(utf8_string(s)?),
                         Some(Token::HexString(s)) => Object::HexString(s),
                         Some(Token::Name(n)) => Object::Name(n),
                         Some(Token::Numeric(n)) => Object::Numeric(n),
+                        Some(Token::Boolean(b)) => Object::Boolean(b),
+                        // an entry with a null value is equivalent to an absent entry
+                        Some(Token::Null) => continue,
                         Some(Token::IndirectRef((obj, gen), xref, bytes)) => {
                             Object::Ref((obj, gen), xref, bytes)
                         }
-                        Some(t) => panic!(
-                            "Unexpected token found in dictionary value {token:?}",
-                            token = t
-                        ),
-                        None => panic!("Unexpected end of stream found in dictionary value"),
+                        Some(t) => {
+                            return Err(Error::Object {
+                                id: None,
+                                offset: Some(tokenizer.offset()),
+                                message: format!(
+                                    "Unexpected token found in dictionary value {t:?}"
+                                ),
+                            })
+                        }
+                        None => {
+                            return Err(Error::Tokenizer {
+                                offset: tokenizer.offset(),
+                                message: String::from(
+                                    "Unexpected end of stream found in dictionary value",
+                                ),
+                            })
+                        }
                     };
                     dict.insert(key, value);
                 }
                 Token::DictEnd => break,
-                t => panic!("Unexpected token found in dictionary key {t:?}"),
+                t => {
+                    return Err(Error::Object {
+                        id: None,
+                        offset: Some(tokenizer.offset()),
+                        message: format!("Unexpected token found in dictionary key {t:?}"),
+                    })
+                }
             }
         }
         Ok(dict)
@@ -94,7 +136,7 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Dictionary<'a> {
 
 // object creation from tokenizer (pdf body)
 impl<'a> TryFrom<&mut Lemmatizer<'a>> for Object<'a> {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(tokenizer: &mut Lemmatizer<'a>) -> Result<Self, Self::Error> {
         let object;
@@ -102,37 +144,39 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Object<'a> {
             match tokenizer.next() {
                 Some(Token::ObjBegin) => continue 'start,
                 Some(Token::DictBegin) => {
-                    let dict = Dictionary::try_from(&mut *tokenizer).unwrap();
+                    let dict = Dictionary::try_from(&mut *tokenizer)?;
                     // check if next token is stream
                     object = match tokenizer.next() {
                         Some(Token::StreamBegin) => {
-                            let length = match dict.get("Length") {
-                                Some(Object::Numeric(Number::Integer(n))) => *n,
-                                Some(Object::Numeric(Number::Real(_))) => {
-                                    panic!("Real number found in stream length")
-                                }
-                                // follow reference to indirect object is required to get the length
-                                Some(Object::Ref((obj, gen), xref, bytes)) => {
-                                    match xref.get_and_fix(&(*obj, *gen), bytes) {
-                                        Some(address) => {
-                                            let mut t =
-                                                Lemmatizer::new(bytes, address, xref.clone());
-                                            matches!(t.next(), Some(Token::Numeric(_)));
-                                            match t.next() {
-                                                Some(Token::Numeric(Number::Integer(n))) => n,
-                                                Some(t) => panic!("Unexpected token found in object; found {t:?}"),
-                                                _ => panic!("Stream dictionary should have a Length key, {dict:?}"),
-                                            }
-                                        }
-                                        None => panic!(
+                            // follow reference to indirect object if required to get the length
+                            let length =
+                                match dict.get("Length").map(Object::resolve).transpose()? {
+                                    Some(Object::Numeric(Number::Integer(n))) if n >= 0 => n,
+                                    Some(o) => {
+                                        return Err(Error::Object {
+                                            id: None,
+                                            offset: Some(tokenizer.offset()),
+                                            message: format!(
+                                        "Stream Length should be a positive integer, found {o:?}"
+                                    ),
+                                        })
+                                    }
+                                    None => {
+                                        return Err(Error::Object {
+                                            id: None,
+                                            offset: Some(tokenizer.offset()),
+                                            message: format!(
                                             "Stream dictionary should have a Length key, {dict:?}"
                                         ),
+                                        })
                                     }
-                                }
-                                _ => panic!("Stream dictionary should have a Length key, {dict:?}"),
-                            };
+                                };
                             // collect next n bytes from the stream
-                            Object::Stream(Stream::new(dict, tokenizer.next_n(length as usize)))
+                            Object::Stream(Stream::new(
+                                dict,
+                                tokenizer.next_n(length as usize)?,
+                                tokenizer.max_decoded_size(),
+                            ))
                         }
                         _ => Object::Dictionary(dict),
                     };
@@ -142,13 +186,25 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Object<'a> {
                     object = Object::Numeric(n);
                     break;
                 }
-                Some(Token::String(s)) => panic!("{s:?}"),
                 Some(Token::ArrayBegin) => {
-                    object = Object::Array(Array::try_from(&mut *tokenizer).unwrap());
+                    object = Object::Array(Array::try_from(&mut *tokenizer)?);
+                    break;
+                }
+                // names, strings and references, found in object streams
+                Some(t) => {
+                    object = Object::try_from(t).map_err(|_| Error::Object {
+                        id: None,
+                        offset: Some(tokenizer.offset()),
+                        message: String::from("Unexpected token found in object"),
+                    })?;
                     break;
                 }
-                Some(t) => panic!("Unexpected token found in object; found {:?}", t),
-                None => panic!("Unexpected end of stream found in object"),
+                None => {
+                    return Err(Error::Tokenizer {
+                        offset: tokenizer.offset(),
+                        message: String::from("Unexpected end of stream found in object"),
+                    })
+                }
             };
         }
         Ok(object)
@@ -156,20 +212,226 @@ impl<'a> TryFrom<&mut Lemmatizer<'a>> for Object<'a> {
 }
 
 impl<'a> Object<'a> {
-    pub fn new(bytes: &'a [u8], curr_idx: usize, xref: Rc<XRef>) -> Self {
-        Self::try_from(&mut Lemmatizer::new(bytes, curr_idx, xref)).unwrap()
+    pub fn new(bytes: &'a [u8], curr_idx: usize, xref: Rc<XRef>) -> Result<Self, Error> {
+        if curr_idx >= bytes.len() {
+            return Err(Error::Object {
+                id: None,
+                offset: Some(curr_idx),
+                message: String::from("Object offset is beyond the end of file"),
+            });
+        }
+        Self::try_from(&mut Lemmatizer::new(bytes, curr_idx, xref))
+    }
+
+    // Follow an indirect reference to the object it points to, direct objects are returned as is
+    pub fn resolve(&self) -> Result<Object<'a>, Error> {
+        match self {
+            Object::Ref(id, xref, bytes) => {
+                xref.begin_resolve(*id).map_err(|e| e.with_id(*id))?;
+                let object = match xref.get_and_fix(id, bytes) {
+                    Some(ObjectLocation::Offset(address)) => {
+                        Object::new(bytes, address, xref.clone()).map(|object| {
+                            match xref.security_handler() {
+                                // the encryption dictionary itself is never encrypted
+                                Some(handler) if xref.encrypt() != Some(*id) => {
+                                    handler.decrypt_object(object, *id)
+                                }
+                                _ => object,
+                            }
+                        })
+                    }
+                    // strings of compressed objects are decrypted with their object stream
+                    Some(ObjectLocation::Compressed { stream, index }) => {
+                        ObjectStream::new(bytes, stream, xref.clone())
+                            .and_then(|s| s.get(*id, index, bytes, xref.clone()))
+                    }
+                    // free and missing objects are null (page 21)
+                    None => Ok(Object::Null),
+                };
+                xref.end_resolve();
+                object.map_err(|e| e.with_id(*id))
+            }
+            o => Ok(o.clone()),
+        }
     }
+
+    // Dictionary of an indirect stream, read without the stream data to check the type of large streams
+    // None is returned for other objects, streams are never compressed
+    pub fn stream_header(&self) -> Result<Option<Dictionary<'a>>, Error> {
+        let Object::Ref(id, xref, bytes) = self else {
+            return Ok(None);
+        };
+        let address = match xref.get_and_fix(id, bytes) {
+            Some(ObjectLocation::Offset(address)) if address < bytes.len() => address,
+            _ => return Ok(None),
+        };
+        let mut lemmatizer = Lemmatizer::new(bytes, address, xref.clone());
+        let header = match (lemmatizer.next(), lemmatizer.next()) {
+            (Some(Token::ObjBegin), Some(Token::DictBegin)) => {
+                Dictionary::try_from(&mut lemmatizer).map_err(|e| e.with_id(*id))?
+            }
+            _ => return Ok(None),
+        };
+        if lemmatizer.next() != Some(Token::StreamBegin) {
+            return Ok(None);
+        }
+        Ok(match xref.security_handler() {
+            Some(handler) => match handler.decrypt_object(Object::Dictionary(header), *id) {
+                Object::Dictionary(header) => Some(header),
+                _ => None,
+            },
+            None => Some(header),
+        })
+    }
+
+    // Point references to other bytes, used for objects parsed from a decoded object stream
+    fn rebind<'b>(self, bytes: &'b [u8]) -> Object<'b> {
+        let rebind_dict = |dict: Dictionary<'a>| -> Dictionary<'b> {
+            dict.into_iter()
+                .map(|(key, value)| (key, value.rebind(bytes)))
+                .collect()
+        };
+        match self {
+            Object::Dictionary(dict) => Object::Dictionary(rebind_dict(dict)),
+            Object::Stream(stream) => Object::Stream(Stream::new(
+                rebind_dict(stream.header),
+                stream.bytes,
+                stream.max_decoded_size,
+            )),
+            Object::Array(array) => {
+                Object::Array(array.into_iter().map(|o| o.rebind(bytes)).collect())
+            }
+            Object::Name(name) => Object::Name(name),
+            Object::String(string) => Object::String(string),
+            Object::HexString(string) => Object::HexString(string),
+            Object::Numeric(n) => Object::Numeric(n),
+            Object::Boolean(b) => Object::Boolean(b),
+            Object::Null => Object::Null,
+            Object::Ref(id, xref, _) => Object::Ref(id, xref, bytes),
+        }
+    }
+}
+
+// Stream of compressed objects (page 101)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjectStream {
+    // object number and byte offset relative to the first object
+    objects: Vec<(i32, usize)>,
+    // byte offset of the first object in the decoded data
+    first: usize,
+    data: Vec<u8>,
 }
 
-impl<'a> From<Tokenizer<'a>> for Object<'a> {
-    fn from(value: Tokenizer<'a>) -> Self {
-        Self::try_from(&mut Lemmatizer::from(value)).unwrap()
+impl ObjectStream {
+    // Read the object stream with the given object number, decoded streams are cached in the xref
+    pub fn new(bytes: &[u8], id: i32, xref: Rc<XRef>) -> Result<Rc<Self>, Error> {
+        if let Some(object_stream) = xref.cached_object_stream(id) {
+            return Ok(object_stream);
+        }
+        let object_stream = match xref.get_and_fix(&(id, 0), bytes) {
+            Some(ObjectLocation::Offset(address)) => {
+                match Object::new(bytes, address, xref.clone()) {
+                    Ok(Object::Stream(stream)) => Self::try_from(match xref.security_handler() {
+                        Some(handler) => {
+                            match handler.decrypt_object(Object::Stream(stream), (id, 0)) {
+                                Object::Stream(stream) => stream,
+                                _ => unreachable!(),
+                            }
+                        }
+                        None => stream,
+                    }),
+                    Ok(o) => Err(Error::object(format!(
+                        "Object stream should be a stream, found {o:?}"
+                    ))),
+                    Err(e) => Err(e),
+                }
+            }
+            // object streams cannot be compressed themselves
+            Some(ObjectLocation::Compressed { .. }) => {
+                Err(Error::object("Object stream should not be compressed"))
+            }
+            None => Err(Error::object("Object stream not found in xref table")),
+        }
+        .map_err(|e| e.with_id((id, 0)))?;
+        let object_stream = Rc::new(object_stream);
+        xref.cache_object_stream(id, object_stream.clone());
+        Ok(object_stream)
+    }
+
+    // Object numbers of the compressed objects, in index order
+    pub fn numbers(&self) -> impl Iterator<Item = i32> + '_ {
+        self.objects.iter().map(|(obj, _)| *obj)
+    }
+
+    // Read the object at the given index, the index is checked against the object number
+    pub fn get<'a>(
+        &self,
+        id: IndirectObject,
+        index: usize,
+        bytes: &'a [u8],
+        xref: Rc<XRef>,
+    ) -> Result<Object<'a>, Error> {
+        let offset = match self.objects.get(index) {
+            Some((obj, offset)) if *obj == id.0 => *offset,
+            _ => self
+                .objects
+                .iter()
+                .find(|(obj, _)| *obj == id.0)
+                .map(|(_, offset)| *offset)
+                .ok_or(Error::object("Object not found in object stream"))?,
+        };
+        Ok(Object::new(&self.data, self.first + offset, xref)?.rebind(bytes))
     }
 }
 
+impl TryFrom<Stream<'_>> for ObjectStream {
+    type Error = Error;
+
+    fn try_from(stream: Stream<'_>) -> Result<Self, Self::Error> {
+        let integer = |key: &str| match stream.header.get(key) {
+            Some(Object::Numeric(Number::Integer(n))) if *n >= 0 => Ok(*n as usize),
+            o => Err(Error::object(format!(
+                "Object stream {key} should be a positive integer, found {o:?}"
+            ))),
+        };
+        let (n, first) = (integer("N")?, integer("First")?);
+        let data = stream.decode()?;
+        // header is made of pairs of integers: object number and offset
+        let mut tokenizer = Tokenizer::new(&data, 0);
+        let objects = (0..n)
+            .map(|_| match (tokenizer.next(), tokenizer.next()) {
+                (
+                    Some(Token::Numeric(Number::Integer(obj))),
+                    Some(Token::Numeric(Number::Integer(offset))),
+                ) if offset >= 0 => Ok((obj, offset as usize)),
+                _ => Err(Error::object(
+                    "Object stream header should contain pairs of integers",
+                )),
+            })
+            .collect::<Result<Vec<(i32, usize)>, Error>>()?;
+        Ok(ObjectStream {
+            objects,
+            first,
+            data,
+        })
+    }
+}
+
+impl<'a> TryFrom<Tokenizer<'a>> for Object<'a> {
+    type Error = Error;
+
+    fn try_from(value: Tokenizer<'a>) -> Result<Self, Self::Error> {
+        Self::try_from(&mut Lemmatizer::from(value))
+    }
+}
+
+fn utf8_string(bytes: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(bytes).map_err(|e| Error::object(format!("String is not valid UTF-8: {e}")))
+}
+
 // conversion of bare pdf token to object
 impl<'a> TryFrom<Token<'a>> for Object<'a> {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(token: Token<'a>) -> Result<Self, Self::Error> {
         match token {
@@ -178,15 +440,15 @@ impl<'a> TryFrom<Token<'a>> for Object<'a> {
             // Token::IndirectObject => Ok(Object::Ref(IndirectObject::try_from(&mut tokenizer).unwrap())),
             Token::Name(n) => Ok(Object::Name(n)),
             Token::Numeric(n) => Ok(Object::Numeric(n)),
-            Token::String(s) => Ok(Object::String(String::from(
-                std::str::from_utf8(&s).unwrap(),
-            ))),
-            Token::LitteralString(s) => Ok(Object::String(String::from(
-                std::str::from_utf8(&s).unwrap(),
-            ))),
+            Token::Boolean(b) => Ok(Object::Boolean(b)),
+            Token::Null => Ok(Object::Null),
+            Token::String(s) => Ok(Object::String(s)),
+            Token::LitteralString(s) => Ok(Object::String(s)),
             Token::HexString(s) => Ok(Object::HexString(s)),
             Token::IndirectRef((obj, gen), xref, bytes) => Ok(Object::Ref((obj, gen), xref, bytes)),
-            t => panic!("Unexpected token found in object{t:?}"),
+            t => Err(Error::object(format!(
+                "Unexpected token found in object{t:?}"
+            ))),
         }
     }
 }
@@ -194,7 +456,11 @@ impl<'a> TryFrom<Token<'a>> for Object<'a> {
 #[cfg(test)]
 mod tests {
 
-    use crate::{tokenizer::Lemmatizer, xref::XRefTable};
+    use crate::{
+        filters::DEFAULT_MAX_DECODED_SIZE,
+        tokenizer::Lemmatizer,
+        xref::{Entry, XRefTable},
+    };
 
     use super::*;
 
@@ -209,15 +475,15 @@ mod tests {
         let dict = Dictionary::try_from(&mut t).unwrap();
         assert_eq!(
             dict.get(&String::from("Title")),
-            Some(&Object::String("sample".to_string()))
+            Some(&Object::String(b"sample".to_vec()))
         );
         assert_eq!(
             dict.get(&String::from("Author")),
-            Some(&Object::String("Philip Hutchison".to_string()))
+            Some(&Object::String(b"Philip Hutchison".to_vec()))
         );
         assert_eq!(
             dict.get(&String::from("Creator")),
-            Some(&Object::String("Pages".to_string()))
+            Some(&Object::String(b"Pages".to_vec()))
         );
     }
 
@@ -334,7 +600,9 @@ mod tests {
             Ok(Object::Stream(Stream {
                 header: d,
                 bytes: s,
+                max_decoded_size,
             })) => {
+                assert_eq!(max_decoded_size, DEFAULT_MAX_DECODED_SIZE);
                 assert_eq!(
                     d.get(&String::from("Length")),
                     Some(&Object::Numeric(Number::Integer(10)))
@@ -382,4 +650,132 @@ mod tests {
             Err(_) => todo!(),
         }
     }
+
+    #[test]
+    fn test_object_nested_array() {
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let mut t = Lemmatizer::new(b"[1 [2] << /A 3 >> /B]", 0, xref);
+        assert_eq!(
+            Object::try_from(&mut t).unwrap(),
+            Object::Array(vec![
+                Object::Numeric(Number::Integer(1)),
+                Object::Array(vec![Object::Numeric(Number::Integer(2))]),
+                Object::Dictionary(Dictionary::from([(
+                    String::from("A"),
+                    Object::Numeric(Number::Integer(3))
+                )])),
+                Object::Name(String::from("B")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_object_boolean_null() {
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let mut t = Lemmatizer::new(
+            b"<< /NeedAppearances true /Open false /Dest null /Kids [null true] >>",
+            0,
+            xref.clone(),
+        );
+        assert_eq!(
+            Object::try_from(&mut t).unwrap(),
+            Object::Dictionary(Dictionary::from([
+                (String::from("NeedAppearances"), Object::Boolean(true)),
+                (String::from("Open"), Object::Boolean(false)),
+                (
+                    String::from("Kids"),
+                    Object::Array(vec![Object::Null, Object::Boolean(true)])
+                ),
+            ]))
+        );
+        let mut t = Lemmatizer::new(b"1 0 obj\nnull\nendobj", 0, xref);
+        assert_eq!(Object::try_from(&mut t).unwrap(), Object::Null);
+    }
+
+    #[test]
+    fn test_null_references() {
+        let bytes = b"1 0 obj\n<< /Type /Catalog /A 2 0 R >>\nendobj\nxref\n0 3\n0000000000 65535 f \n0000000000 00000 n \n0000000000 00001 f \ntrailer\n<< /Size 3 /Root 1 0 R >>";
+        let xref = Rc::new(XRef::new(bytes, 45, DEFAULT_MAX_DECODED_SIZE).unwrap());
+        assert!(matches!(
+            Object::Ref((1, 0), xref.clone(), bytes).resolve(),
+            Ok(Object::Dictionary(_))
+        ));
+        // free entry, missing entry and entry with another generation number
+        for id in [(2, 0), (3, 0), (1, 1)] {
+            assert_eq!(
+                Object::Ref(id, xref.clone(), bytes).resolve(),
+                Ok(Object::Null)
+            );
+        }
+    }
+
+    #[test]
+    fn test_reference_cycles() {
+        let compressed = |stream, index| Entry::Compressed { stream, index };
+        let offset = |offset| Entry::InUse {
+            offset,
+            generation: 0,
+        };
+        // stream Length given by the stream itself, or by an object compressed in the stream
+        let bytes = b"1 0 obj\n<< /Length 1 0 R >>\nstream\nendstream\nendobj\n\
+            2 0 obj\n<< /Type /ObjStm /N 1 /First 4 /Length 3 0 R >>\nstream\n3 0 8\nendstream\nendobj\n";
+        let xref = Rc::new(XRef::XRefTable(XRefTable::from_entries([
+            (1, offset(0)),
+            (2, offset(52)),
+            (3, compressed(2, 0)),
+        ])));
+        for id in [(1, 0), (3, 0)] {
+            let error = Object::Ref(id, xref.clone(), bytes).resolve().unwrap_err();
+            assert!(
+                format!("{error:?}").contains("Reference cycle"),
+                "{error:?}"
+            );
+        }
+        // stream lengths resolved through a long chain of streams
+        let mut bytes = vec![];
+        let mut entries = vec![];
+        for number in 1..=100 {
+            entries.push((number, offset(bytes.len())));
+            let next = number + 1;
+            bytes.extend(
+                format!("{number} 0 obj\n<< /Length {next} 0 R >>\nstream\nendstream\nendobj\n")
+                    .as_bytes(),
+            );
+        }
+        let xref = Rc::new(XRef::XRefTable(XRefTable::from_entries(entries)));
+        let error = Object::Ref((1, 0), xref, &bytes).resolve().unwrap_err();
+        assert!(
+            format!("{error:?}").contains("Too many nested references"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn test_compressed_objects() {
+        let xref = Rc::new(XRef::XRefTable(XRefTable::default()));
+        let header = Dictionary::from([
+            (String::from("N"), Object::Numeric(Number::Integer(2))),
+            (String::from("First"), Object::Numeric(Number::Integer(9))),
+        ]);
+        let object_stream = ObjectStream::try_from(Stream::new(
+            header,
+            b"3 0 4 14 << /A 4 0 R >> /Name".to_vec(),
+            DEFAULT_MAX_DECODED_SIZE,
+        ))
+        .unwrap();
+        let file = b"%PDF-1.5";
+        assert_eq!(
+            object_stream.get((3, 0), 0, file, xref.clone()).unwrap(),
+            Object::Dictionary(Dictionary::from([(
+                String::from("A"),
+                Object::Ref((4, 0), xref.clone(), file)
+            )]))
+        );
+        // wrong index is recovered with the object number
+        assert_eq!(
+            object_stream.get((4, 0), 0, file, xref.clone()).unwrap(),
+            Object::Name(String::from("Name"))
+        );
+        assert!(object_stream.get((5, 0), 2, file, xref).is_err());
+    }
 }
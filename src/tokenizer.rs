@@ -4,6 +4,7 @@ use std::{char, iter::Peekable, rc::Rc, slice::Iter};
 use crate::{
     algebra::Number,
     xref::{XRef, XRefTable},
+    Error,
 };
 
 // Tokenizer for PDF objects
@@ -34,6 +35,8 @@ impl WhiteSpace {
 #[derive(Debug, PartialEq)]
 pub enum Token<'a> {
     Numeric(Number),
+    Boolean(bool),
+    Null,
     String(Vec<u8>),
     LitteralString(Vec<u8>),
     HexString(Vec<u8>),
@@ -111,9 +114,18 @@ impl<'a> Lemmatizer<'a> {
         }
     }
 
-    pub fn next_n(&mut self, length: usize) -> Vec<u8> {
+    pub fn next_n(&mut self, length: usize) -> Result<Vec<u8>, Error> {
         self.tokenizer.next_n(length)
     }
+
+    pub fn offset(&self) -> usize {
+        self.tokenizer.offset()
+    }
+
+    // Upper bound of the size of decoded streams of the document being read
+    pub fn max_decoded_size(&self) -> usize {
+        self.xref.max_decoded_size()
+    }
 }
 
 impl<'a> Iterator for Lemmatizer<'a> {
@@ -170,7 +182,7 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    pub fn next_n(&mut self, length: usize) -> Vec<u8> {
+    pub fn next_n(&mut self, length: usize) -> Result<Vec<u8>, Error> {
         // skip whitespaces characters
         loop {
             match self.byte.peek() {
@@ -178,26 +190,35 @@ impl<'a> Tokenizer<'a> {
                     CharacterSet::WhiteSpace(_) => self.byte.next(),
                     _ => break,
                 },
-                None => panic!("End of stream reached"),
+                None => {
+                    return Err(Error::Tokenizer {
+                        offset: self.offset(),
+                        message: String::from("End of stream reached before stream data"),
+                    })
+                }
             };
         }
-        self.byte.clone().take(length).copied().collect::<Vec<u8>>()
+        Ok(self.byte.clone().take(length).copied().collect::<Vec<u8>>())
     }
 
-    fn byte_to_digit(b: &u8) -> u8 {
-        match b {
-            b'0' => 0,
-            b'1' => 1,
-            b'2' => 2,
-            b'3' => 3,
-            b'4' => 4,
-            b'5' => 5,
-            b'6' => 6,
-            b'7' => 7,
-            b'8' => 8,
-            b'9' => 9,
-            _ => panic!(),
+    // Byte offset of the next character to be read
+    pub fn offset(&self) -> usize {
+        self.bytes.len() - self.byte.len()
+    }
+
+    // Read the remaining digits of an octal escape sequence (up to three digits)
+    fn read_octal(&mut self, first: u8) -> u8 {
+        let mut code = first - b'0';
+        for _ in 0..2 {
+            match self.byte.peek() {
+                Some(&&d @ b'0'..=b'7') => {
+                    code = code.wrapping_mul(8).wrapping_add(d - b'0');
+                    self.byte.next();
+                }
+                _ => break,
+            }
         }
+        code
     }
 }
 
@@ -230,17 +251,16 @@ impl<'a> Iterator for Tokenizer<'a> {
                                     self.byte.next();
                                     return Some(Token::DictBegin);
                                 }
-                                // Hexadecimal characters
-                                Some(
-                                    b'0' | b'1' | b'2' | b'3' | b'4' | b'5' | b'6' | b'7' | b'8'
-                                    | b'9' | b'A' | b'B' | b'C' | b'D' | b'E' | b'F' | b'a' | b'b'
-                                    | b'c' | b'd' | b'e' | b'f',
-                                ) => {
+                                // Hexadecimal characters, whitespaces are ignored
+                                Some(_) => {
                                     let mut buf: String = String::new();
                                     loop {
                                         match self.byte.next() {
                                             Some(b'>') => break,
-                                            Some(a) => buf.push(*a as char),
+                                            Some(a) if a.is_ascii_hexdigit() => {
+                                                buf.push(*a as char)
+                                            }
+                                            Some(_) => continue,
                                             None => return None,
                                         }
                                     }
@@ -248,7 +268,6 @@ impl<'a> Iterator for Tokenizer<'a> {
                                     if buf.len() % 2 == 1 {
                                         buf.push('0');
                                     }
-                                    // println!("{:?}", &buf);
                                     // Decode hex to u8
                                     let buf_decoded: Vec<u8> = (0..buf.len())
                                         .step_by(2)
@@ -256,8 +275,7 @@ impl<'a> Iterator for Tokenizer<'a> {
                                         .collect();
                                     return Some(Token::HexString(buf_decoded));
                                 }
-                                Some(b) => panic!("Character {b:} is not interpreted as hexstring"),
-                                None => panic!("No character following '<'"),
+                                None => return None,
                             },
                             b'>' => match self.byte.peek() {
                                 Some(b'>') => {
@@ -265,11 +283,12 @@ impl<'a> Iterator for Tokenizer<'a> {
                                     return Some(Token::DictEnd);
                                 }
                                 Some(_) => continue,
-                                None => panic!("Reached end of stream before end of litteral"),
+                                None => return None,
                             },
                             b'[' => return Some(Token::ArrayBegin),
                             b']' => return Some(Token::ArrayEnd),
-                            l => panic!("Character {l} is not covered"),
+                            // PostScript procedure delimiters are read as regular strings
+                            l => return Some(Token::String(vec![*l])),
                         }
                     }
                     Delimiter::Name => {
@@ -287,8 +306,8 @@ impl<'a> Iterator for Tokenizer<'a> {
                     Delimiter::String => {
                         let mut buf: Vec<u8> = vec![];
                         // nested parentesis counters
-                        let mut opened_parathesis: u8 = 1;
-                        let mut closed_parathesis: u8 = 0;
+                        let mut opened_parathesis: usize = 1;
+                        let mut closed_parathesis: usize = 0;
                         while let Some(cursor) = self.byte.next() {
                             match cursor {
                                 b'(' => opened_parathesis += 1,
@@ -310,15 +329,8 @@ impl<'a> Iterator for Tokenizer<'a> {
                                         b'\\' => b'\\',
                                         b'(' => b'(',
                                         b')' => b')',
-                                        b'0'..=b'9' => {
-                                            // convert octal digit to u8
-                                            let c = Tokenizer::byte_to_digit(c);
-                                            let d =
-                                                Tokenizer::byte_to_digit(self.byte.next().unwrap());
-                                            let e =
-                                                Tokenizer::byte_to_digit(self.byte.next().unwrap());
-                                            ((c * 8) + d * 8) + e
-                                        }
+                                        // convert octal digits to u8
+                                        b'0'..=b'7' => self.read_octal(*c),
                                         c => *c,
                                     },
                                     None => continue,
@@ -348,7 +360,7 @@ impl<'a> Iterator for Tokenizer<'a> {
                         self.byte.next();
                     }
                     if is_numeric {
-                        let numeric = std::str::from_utf8(&buf).unwrap();
+                        let numeric = String::from_utf8_lossy(&buf);
                         match numeric.parse::<i32>() {
                             Ok(n) => return Some(Token::Numeric(Number::Integer(n))),
                             Err(_) => {
@@ -362,6 +374,9 @@ impl<'a> Iterator for Tokenizer<'a> {
                         b"stream" => return Some(Token::StreamBegin),
                         b"endstream" => return Some(Token::StreamEnd),
                         b"endobj" => return Some(Token::ObjEnd),
+                        b"true" => return Some(Token::Boolean(true)),
+                        b"false" => return Some(Token::Boolean(false)),
+                        b"null" => return Some(Token::Null),
                         _ => return Some(Token::String(buf)),
                     }
                 }
@@ -391,12 +406,36 @@ mod tests {
         assert_eq!(pdf.next(), None);
     }
 
+    #[test]
+    fn test_boolean_null() {
+        let mut pdf = Tokenizer::new(b"true false null nulls", 0);
+        assert_eq!(pdf.next(), Some(Token::Boolean(true)));
+        assert_eq!(pdf.next(), Some(Token::Boolean(false)));
+        assert_eq!(pdf.next(), Some(Token::Null));
+        assert_eq!(pdf.next(), Some(Token::String(b"nulls".to_vec())));
+        assert_eq!(pdf.next(), None);
+    }
+
     #[test]
     fn test_litteral_string_octal() {
         let mut pdf = Tokenizer::new(b"(\\003)", 0);
         assert_eq!(pdf.next(), Some(Token::LitteralString(vec![3])))
     }
 
+    #[test]
+    fn test_litteral_string_octal_short() {
+        let mut pdf = Tokenizer::new(b"(\\101\\0a)", 0);
+        assert_eq!(pdf.next(), Some(Token::LitteralString(vec![65, 0, 97])))
+    }
+
+    #[test]
+    fn test_hex_string_whitespaces() {
+        let mut pdf = Tokenizer::new(b"<48 65\n6C6C 6F> <>", 0);
+        assert_eq!(pdf.next(), Some(Token::HexString(b"Hello".to_vec())));
+        assert_eq!(pdf.next(), Some(Token::HexString(vec![])));
+        assert_eq!(pdf.next(), None);
+    }
+
     #[test]
     fn test_pdfbytes_iterator_litteral_string() {
         let mut pdf = Tokenizer::new(b"(Hello World)", 0);
@@ -413,6 +452,13 @@ mod tests {
             pdf.next(),
             Some(Token::LitteralString(b"(Hello) (World)".to_vec()))
         );
+        // deeply nested parenthesis
+        let nested = [vec![b'('; 300], vec![b')'; 300]].concat();
+        let mut pdf = Tokenizer::new(&nested, 0);
+        assert_eq!(
+            pdf.next(),
+            Some(Token::LitteralString(nested[1..599].to_vec()))
+        );
     }
 
     #[test]
@@ -548,7 +594,7 @@ mod tests {
         assert_eq!(pdf.next(), Some(Token::ArrayBegin));
         assert_eq!(
             pdf.next(),
-            Some(Token::IndirectRef((3, 0), xref.clone(), &bytes.as_slice()))
+            Some(Token::IndirectRef((3, 0), xref.clone(), bytes.as_slice()))
         );
         assert_eq!(pdf.next(), Some(Token::ArrayEnd));
         assert_eq!(pdf.next(), Some(Token::DictEnd));
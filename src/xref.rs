@@ -1,12 +1,14 @@
 use crate::{
     algebra::Number,
-    filters::flate_decode,
-    object::Object,
+    encryption::SecurityHandler,
+    filters::DEFAULT_MAX_DECODED_SIZE,
+    object::{Object, ObjectStream},
     tokenizer::{Token, Tokenizer},
+    Error,
 };
 
 use super::object;
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum XRef {
@@ -14,140 +16,606 @@ pub enum XRef {
     XRefStream(XRefStream),
 }
 
+// Location of an indirect object (page 109)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ObjectLocation {
+    // Byte offset of the object in the file
+    Offset(usize),
+    // Object compressed in an object stream: object number of the stream and index of the object within it
+    Compressed { stream: i32, index: usize },
+}
+
+// Cross-reference entry of an object number (page 94 and 109)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Entry {
+    Free,
+    InUse { offset: usize, generation: i32 },
+    Compressed { stream: i32, index: usize },
+}
+
+type Entries = HashMap<i32, Entry>;
+
+type ObjectStreamCache = RefCell<HashMap<i32, Rc<ObjectStream>>>;
+
+// Upper bound of references resolved while reading another object, through stream lengths and object streams
+const MAX_NESTED_REFERENCES: usize = 32;
+
 impl XRef {
-    pub fn get_and_fix(&self, key: &object::IndirectObject, bytes: &[u8]) -> Option<usize> {
+    pub fn get_and_fix(
+        &self,
+        key: &object::IndirectObject,
+        bytes: &[u8],
+    ) -> Option<ObjectLocation> {
+        match self.get(key)? {
+            ObjectLocation::Offset(offset) => {
+                let mut pattern = format!("{} {} obj", key.0, key.1).as_bytes().to_owned();
+                // xref address is correct
+                if bytes.get(offset..).is_some_and(|b| b.starts_with(&pattern)) {
+                    Some(ObjectLocation::Offset(offset))
+                // xref table adress is broken
+                } else {
+                    // add a new line at the beginning of the pattern to avoid matching 11 0 obj with 1 0 obj
+                    pattern.insert(0, b'\n');
+                    // look for object header in byte stream
+                    bytes
+                        .windows(pattern.len())
+                        .position(|w: &[u8]| w == pattern)
+                        .map(|position| ObjectLocation::Offset(position + 1))
+                }
+            }
+            location => Some(location),
+        }
+    }
+
+    // Free entries and entries with another generation number are references to null
+    pub fn get(&self, key: &object::IndirectObject) -> Option<ObjectLocation> {
+        match self.entries().get(&key.0)? {
+            Entry::InUse { offset, generation } if *generation == key.1 => {
+                Some(ObjectLocation::Offset(*offset))
+            }
+            // compressed objects have a generation number of 0
+            Entry::Compressed { stream, index } if key.1 == 0 => Some(ObjectLocation::Compressed {
+                stream: *stream,
+                index: *index,
+            }),
+            _ => None,
+        }
+    }
+
+    fn entries(&self) -> &Entries {
         match self {
-            XRef::XRefStream(stream) => stream.get(key),
-            XRef::XRefTable(xref) => xref.get_and_fix(key, bytes),
+            XRef::XRefStream(stream) => &stream.table,
+            XRef::XRefTable(table) => &table.table,
         }
     }
 
-    pub fn get(&self, key: &object::IndirectObject) -> Option<usize> {
+    // Trailer - Catalog dictionary of the document
+    pub fn root(&self) -> Option<object::IndirectObject> {
         match self {
-            XRef::XRefStream(stream) => stream.get(key),
-            XRef::XRefTable(xref) => xref.get(key),
+            XRef::XRefStream(stream) => stream.root,
+            XRef::XRefTable(table) => table.root,
         }
     }
 
-    pub fn get_catalog_offset(&self) -> Option<usize> {
+    // Trailer - Information dictionary containing metadata
+    pub fn info(&self) -> Option<object::IndirectObject> {
         match self {
-            XRef::XRefStream(stream) => stream.get_catalog_offset(),
-            XRef::XRefTable(table) => table.get_catalog_offset(),
+            XRef::XRefStream(stream) => stream.info,
+            XRef::XRefTable(table) => table.info,
         }
     }
 
-    pub fn new(bytes: &[u8], offset: usize) -> Self {
-        Self::from(Tokenizer::new(bytes, offset))
+    // Trailer - Encryption dictionary
+    pub fn encrypt(&self) -> Option<object::IndirectObject> {
+        match self {
+            XRef::XRefStream(stream) => stream.encrypt,
+            XRef::XRefTable(table) => table.encrypt,
+        }
+    }
+
+    // Trailer - First byte string of the file identifier
+    pub fn file_id(&self) -> Option<&[u8]> {
+        match self {
+            XRef::XRefStream(stream) => stream.id.as_deref(),
+            XRef::XRefTable(table) => table.id.as_deref(),
+        }
+    }
+
+    // Decryption of the strings and streams of encrypted documents
+    pub fn security_handler(&self) -> Option<&SecurityHandler> {
+        match self {
+            XRef::XRefStream(stream) => stream.security_handler.as_ref(),
+            XRef::XRefTable(table) => table.security_handler.as_ref(),
+        }
+    }
+
+    pub fn set_security_handler(&mut self, security_handler: SecurityHandler) {
+        match self {
+            XRef::XRefStream(stream) => stream.security_handler = Some(security_handler),
+            XRef::XRefTable(table) => table.security_handler = Some(security_handler),
+        }
+    }
+
+    // Upper bound of the size of the streams of the document once decoded
+    pub fn max_decoded_size(&self) -> usize {
+        match self {
+            XRef::XRefStream(stream) => stream.max_decoded_size,
+            XRef::XRefTable(table) => table.max_decoded_size,
+        }
+    }
+
+    fn set_max_decoded_size(&mut self, max_decoded_size: usize) {
+        match self {
+            XRef::XRefStream(stream) => stream.max_decoded_size = max_decoded_size,
+            XRef::XRefTable(table) => table.max_decoded_size = max_decoded_size,
+        }
+    }
+
+    // Trailer - Byte offset of the previous cross-reference section
+    fn prev(&self) -> Option<usize> {
+        match self {
+            XRef::XRefStream(stream) => stream.prev,
+            XRef::XRefTable(table) => table.prev,
+        }
+    }
+
+    // Object streams already decoded
+    pub fn cached_object_stream(&self, id: i32) -> Option<Rc<ObjectStream>> {
+        self.object_streams().borrow().get(&id).cloned()
+    }
+
+    pub fn cache_object_stream(&self, id: i32, object_stream: Rc<ObjectStream>) {
+        self.object_streams().borrow_mut().insert(id, object_stream);
+    }
+
+    // Objects are resolved while reading another one for stream lengths and object streams
+    // An object met again while being read is a cycle, which would never end
+    pub fn begin_resolve(&self, id: object::IndirectObject) -> Result<(), Error> {
+        let mut resolving = self.resolving().borrow_mut();
+        if resolving.contains(&id) {
+            return Err(Error::object(
+                "Reference cycle found while reading the object",
+            ));
+        }
+        if resolving.len() >= MAX_NESTED_REFERENCES {
+            return Err(Error::object("Too many nested references"));
+        }
+        resolving.push(id);
+        Ok(())
+    }
+
+    pub fn end_resolve(&self) {
+        self.resolving().borrow_mut().pop();
+    }
+
+    fn resolving(&self) -> &RefCell<Vec<object::IndirectObject>> {
+        match self {
+            XRef::XRefStream(stream) => &stream.resolving,
+            XRef::XRefTable(table) => &table.resolving,
+        }
+    }
+
+    fn object_streams(&self) -> &ObjectStreamCache {
+        match self {
+            XRef::XRefStream(stream) => &stream.object_streams,
+            XRef::XRefTable(table) => &table.object_streams,
+        }
+    }
+
+    // Add the entries of an older section for object numbers missing in this section
+    // In hybrid-reference files, objects of the XRefStm stream may be listed as free in the table
+    fn merge(&mut self, older: XRef, hybrid: bool) {
+        let (root, encrypt, info, id, table) = match self {
+            XRef::XRefStream(s) => (
+                &mut s.root,
+                &mut s.encrypt,
+                &mut s.info,
+                &mut s.id,
+                &mut s.table,
+            ),
+            XRef::XRefTable(t) => (
+                &mut t.root,
+                &mut t.encrypt,
+                &mut t.info,
+                &mut t.id,
+                &mut t.table,
+            ),
+        };
+        for (number, entry) in older.entries() {
+            match table.get(number) {
+                None => {
+                    table.insert(*number, *entry);
+                }
+                Some(Entry::Free) if hybrid => {
+                    table.insert(*number, *entry);
+                }
+                Some(_) => (),
+            }
+        }
+        *root = root.or(older.root());
+        *info = info.or(older.info());
+        *encrypt = encrypt.or(older.encrypt());
+        if id.is_none() {
+            *id = older.file_id().map(<[u8]>::to_vec);
+        }
     }
-}
 
-impl From<Tokenizer<'_>> for XRef {
-    fn from(value: Tokenizer<'_>) -> Self {
-        match value.clone().peekable().peek() {
+    // Read the cross-reference section starting at offset, then the previous sections of incremental updates
+    // Streams of the document are decoded up to max_decoded_size bytes
+    pub fn new(bytes: &[u8], offset: usize, max_decoded_size: usize) -> Result<Self, Error> {
+        // offsets of sections already read, to stop on Prev loops
+        let mut visited = vec![];
+        let mut xref = XRef::section(bytes, offset, max_decoded_size, &mut visited)?;
+        let mut prev = xref.prev();
+        while let Some(offset) = prev.filter(|o| !visited.contains(o)) {
+            let section = XRef::section(bytes, offset, max_decoded_size, &mut visited)?;
+            prev = section.prev();
+            xref.merge(section, false);
+        }
+        Ok(xref)
+    }
+
+    // Read a single section, with its cross-reference stream for hybrid-reference files (page 114)
+    fn section(
+        bytes: &[u8],
+        offset: usize,
+        max_decoded_size: usize,
+        visited: &mut Vec<usize>,
+    ) -> Result<Self, Error> {
+        visited.push(offset);
+        let mut section = XRef::read(bytes, offset, max_decoded_size)?;
+        if let XRef::XRefTable(XRefTable {
+            xref_stm: Some(stream_offset),
+            ..
+        }) = section
+        {
+            if !visited.contains(&stream_offset) {
+                visited.push(stream_offset);
+                section.merge(XRef::read(bytes, stream_offset, max_decoded_size)?, true);
+            }
+        }
+        Ok(section)
+    }
+
+    fn read(bytes: &[u8], offset: usize, max_decoded_size: usize) -> Result<Self, Error> {
+        if offset >= bytes.len() {
+            return Err(Error::xref(
+                Some(offset),
+                "Cross-reference offset is beyond the end of file",
+            ));
+        }
+        let tokenizer = Tokenizer::new(bytes, offset);
+        let mut section = match tokenizer.clone().next() {
             // Cross reference table starts with "xref" token (page 93)
-            Some(Token::String(_)) => XRef::XRefTable(XRefTable::from(value)),
+            Some(Token::String(_)) => XRef::XRefTable(XRefTable::try_from(tokenizer)?),
             // Cross reference stream object starts with "0 0 obj"
-            Some(Token::Numeric(_)) => match Object::from(value) {
-                Object::Stream(s) => XRef::XRefStream(XRefStream::from(s)),
-                o => panic!("Xref object cannot be of type {o:?}"),
-            },
-            Some(_t) => panic!("Xref object or strign 'xref' not found"),
-            None => panic!("End of stream"),
+            Some(Token::Numeric(_)) => {
+                let xref = Rc::new(XRef::XRefTable(XRefTable {
+                    max_decoded_size,
+                    ..Default::default()
+                }));
+                match Object::new(bytes, offset, xref)? {
+                    Object::Stream(s) => XRef::XRefStream(XRefStream::try_from(s)?),
+                    o => {
+                        return Err(Error::xref(
+                            Some(offset),
+                            format!("Xref object cannot be of type {o:?}"),
+                        ))
+                    }
+                }
+            }
+            Some(_t) => {
+                return Err(Error::xref(
+                    Some(offset),
+                    "Xref object or string 'xref' not found",
+                ))
+            }
+            None => return Err(Error::xref(Some(offset), "End of stream")),
+        };
+        section.set_max_decoded_size(max_decoded_size);
+        Ok(section)
+    }
+}
+
+// Reconstruction of the cross-reference table of damaged files
+impl XRef {
+    // Rebuild the table by scanning the file for object headers, trailers and object streams
+    // Objects defined later in the file are newer versions and replace earlier definitions
+    pub fn reconstruct(bytes: &[u8], max_decoded_size: usize) -> Result<Self, Error> {
+        let mut table = Entries::new();
+        for (offset, (number, generation)) in XRef::object_headers(bytes) {
+            table.insert(number, Entry::InUse { offset, generation });
+        }
+        if table.is_empty() {
+            return Err(Error::xref(
+                None,
+                "No object found while reconstructing the cross-reference table",
+            ));
+        }
+        // table used to resolve stream lengths while reading the objects
+        let xref = Rc::new(XRef::XRefTable(XRefTable {
+            table: table.clone(),
+            max_decoded_size,
+            ..Default::default()
+        }));
+        let mut section = XRefTable {
+            max_decoded_size,
+            ..Default::default()
+        };
+        let mut catalog = None;
+        let mut numbers: Vec<&i32> = xref.entries().keys().collect();
+        numbers.sort_by_key(|number| match xref.entries().get(number) {
+            Some(Entry::InUse { offset, .. }) => *offset,
+            _ => 0,
+        });
+        for number in numbers {
+            let Some(Entry::InUse { offset, generation }) = xref.entries().get(number) else {
+                continue;
+            };
+            // objects which cannot be read are skipped
+            match Object::new(bytes, *offset, xref.clone()) {
+                Ok(Object::Stream(stream)) => match stream.header.get("Type") {
+                    Some(Object::Name(t)) if t == "ObjStm" => {
+                        if let Ok(object_stream) = ObjectStream::try_from(stream) {
+                            for (index, compressed) in object_stream.numbers().enumerate() {
+                                // objects found uncompressed in the file take precedence
+                                table.entry(compressed).or_insert(Entry::Compressed {
+                                    stream: *number,
+                                    index,
+                                });
+                            }
+                        }
+                    }
+                    Some(Object::Name(t)) if t == "XRef" => {
+                        section.read_trailer(&stream.header);
+                    }
+                    _ => (),
+                },
+                Ok(Object::Dictionary(dict)) => {
+                    if matches!(dict.get("Type"), Some(Object::Name(t)) if t == "Catalog") {
+                        catalog = Some((*number, *generation));
+                    }
+                }
+                _ => (),
+            }
+        }
+        // trailer dictionaries follow the "trailer" keyword
+        let pattern = b"trailer";
+        for (position, _) in bytes
+            .windows(pattern.len())
+            .enumerate()
+            .filter(|(_, w)| w == pattern)
+        {
+            if let Ok(Object::Dictionary(trailer)) =
+                Object::new(bytes, position + pattern.len(), xref.clone())
+            {
+                section.read_trailer(&trailer);
+            }
+        }
+        // root catalog is inferred when the trailers are missing or point to a missing object
+        if section.root.is_none_or(|root| !table.contains_key(&root.0)) {
+            section.root = catalog;
+        }
+        if section.root.is_none() {
+            return Err(Error::xref(
+                None,
+                "No catalog found while reconstructing the cross-reference table",
+            ));
         }
+        section.table = table;
+        Ok(XRef::XRefTable(section))
+    }
+
+    // Byte offsets and identifiers of "N G obj" object headers
+    fn object_headers(bytes: &[u8]) -> Vec<(usize, object::IndirectObject)> {
+        // read an integer ending at position, preceded by whitespaces
+        let integer = |end: usize| -> Option<(usize, i32)> {
+            let start = bytes[..end]
+                .iter()
+                .rposition(|c| !c.is_ascii_digit())
+                .map_or(0, |p| p + 1);
+            let value = std::str::from_utf8(&bytes[start..end]).ok()?.parse().ok()?;
+            Some((start, value))
+        };
+        let whitespaces = |end: usize| -> Option<usize> {
+            let start = bytes[..end]
+                .iter()
+                .rposition(|c| !c.is_ascii_whitespace())
+                .map_or(0, |p| p + 1);
+            (start < end).then_some(start)
+        };
+        let pattern = b"obj";
+        bytes
+            .windows(pattern.len())
+            .enumerate()
+            .filter(|(position, w)| {
+                w == pattern
+                    && !bytes
+                        .get(position + pattern.len())
+                        .is_some_and(|c| c.is_ascii_alphanumeric())
+            })
+            .filter_map(|(position, _)| {
+                let (start, generation) = integer(whitespaces(position)?)?;
+                let (start, number) = integer(whitespaces(start)?)?;
+                // object number should not be the end of another token
+                if start > 0 && bytes[start - 1].is_ascii_alphanumeric() {
+                    return None;
+                }
+                Some((start, (number, generation)))
+            })
+            .collect()
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct XRefTable {
     // Trailer - Object number
     size: Number,
     // Trailer - Byte offset from the beginning of the file to the beginning of the previous cross-reference section
-    prev: Option<Box<XRef>>,
+    prev: Option<usize>,
+    // Trailer - Byte offset of the cross-reference stream of hybrid-reference files
+    xref_stm: Option<usize>,
     // Trailer - Catalogue dictionnary or a reference to the root object of the page tree
     root: Option<(i32, i32)>,
     // Trailer - Encryption dictionnary
     encrypt: Option<(i32, i32)>,
     // Trailer - Information dictionary containing metadata
     info: Option<(i32, i32)>,
-    // Trailer - Array of two byte-strings constituting a file identifier
-    // id: Option<Array<'a>>,
-    // XRef table data
-    table: HashMap<object::IndirectObject, (usize, bool)>,
+    // Trailer - First of the two byte-strings constituting a file identifier
+    id: Option<Vec<u8>>,
+    // XRef table data, merged with previous sections
+    table: Entries,
+    // Object streams already decoded
+    object_streams: ObjectStreamCache,
+    // Objects being resolved
+    resolving: RefCell<Vec<object::IndirectObject>>,
+    // Security handler of encrypted documents
+    security_handler: Option<SecurityHandler>,
+    // Upper bound of the size of decoded streams
+    max_decoded_size: usize,
+}
+
+// Sections are compared by their content, decoded object streams and decryption state are ignored
+impl PartialEq for XRefTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.prev == other.prev
+            && self.xref_stm == other.xref_stm
+            && self.root == other.root
+            && self.encrypt == other.encrypt
+            && self.info == other.info
+            && self.id == other.id
+            && self.table == other.table
+    }
 }
 
-impl From<Tokenizer<'_>> for XRefTable {
-    fn from(mut tokenizer: Tokenizer<'_>) -> Self {
+impl TryFrom<Tokenizer<'_>> for XRefTable {
+    type Error = Error;
+
+    fn try_from(mut tokenizer: Tokenizer<'_>) -> Result<Self, Self::Error> {
         // Check that xref table starts with "xref" bytes
         match tokenizer.next() {
-            Some(Token::String(s)) => {
-                if s.as_slice() != b"xref" {
-                    panic!("Startxref string missing, found string {s:?}")
-                }
+            Some(Token::String(s)) if s.as_slice() == b"xref" => (),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    format!("Incorrect token found at the beginning of XRefTable, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    "End of file unexpected",
+                ))
             }
-            Some(t) => panic!(
-                "Incorrect token found at the beginning of XRefTable, found {:?}",
-                t
-            ),
-            None => panic!("End of file unexpected"),
         };
 
-        // Read table subsections
-        let table = XRefTable::read_table_subsection(&mut tokenizer);
+        // Read table subsections until the trailer
+        let mut table = Entries::new();
+        while let Some(Token::Numeric(_)) = tokenizer.clone().next() {
+            table.extend(XRefTable::read_table_subsection(&mut tokenizer)?);
+        }
 
         // Check that xref table trailer is starting with "trailer" bytes
         match tokenizer.next() {
-            Some(Token::String(s)) => {
-                if s.as_slice() != b"trailer" {
-                    panic!("Trailer string missing, found string {s:?}")
-                }
+            Some(Token::String(s)) if s.as_slice() == b"trailer" => (),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    format!("Incorrect token found at the beginning of trailer, found {t:?}"),
+                ))
             }
-            Some(t) => panic!(
-                "Incorrect token found at the beginning of trailer, found {:?}",
-                t
-            ),
-            None => panic!("End of file"),
+            None => return Err(Error::xref(Some(tokenizer.offset()), "End of file")),
         };
 
         // Read trailer dictionnary
-        let trailer = match Object::from(tokenizer.clone()) {
+        let trailer_offset = tokenizer.offset();
+        let trailer = match Object::try_from(tokenizer.clone())? {
             Object::Dictionary(dict) => dict,
-            _ => panic!("Trailer should be a dictionary"),
+            _ => {
+                return Err(Error::xref(
+                    Some(trailer_offset),
+                    "Trailer should be a dictionary",
+                ))
+            }
         };
 
-        XRefTable {
+        Ok(XRefTable {
             size: match trailer.get("Size") {
                 Some(Object::Numeric(n)) => n.clone(),
-                _ => panic!("Size should be a numeric"),
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "Size should be a numeric",
+                    ))
+                }
             },
             // Byte offset from the beginning of the file to the beginning of the previous cross-reference section
             prev: match trailer.get("Prev") {
-                Some(Object::Numeric(Number::Integer(offset))) => Some(Box::new(XRef::from(
-                    Tokenizer::new(tokenizer.bytes, *offset as usize),
-                ))),
+                Some(Object::Numeric(Number::Integer(offset))) if *offset >= 0 => {
+                    Some(*offset as usize)
+                }
+                None => None,
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "Prev should be a numeric",
+                    ))
+                }
+            },
+            // Byte offset of the cross-reference stream of hybrid-reference files
+            xref_stm: match trailer.get("XRefStm") {
+                Some(Object::Numeric(Number::Integer(offset))) if *offset >= 0 => {
+                    Some(*offset as usize)
+                }
                 None => None,
-                _ => panic!("Prev should be a numeric"),
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "XRefStm should be a numeric",
+                    ))
+                }
             },
             // Catalogue dictionnary or a reference to the root object of the page tree
             root: match trailer.get("Root") {
                 Some(Object::Ref(r, _, _)) => Some(*r),
-                _ => panic!("Root should be a Catalog object"),
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "Root should be a Catalog object",
+                    ))
+                }
             },
             // Encryption dictionnary
             encrypt: match trailer.get("Encrypt") {
                 Some(Object::Ref((obj, gen), _xref, _bytes)) => Some((*obj, *gen)),
                 None => None,
-                _ => panic!("Encrypt should be an indirect object"),
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "Encrypt should be an indirect object",
+                    ))
+                }
             },
             // Information dictionary containing metadata
             info: match trailer.get("Info") {
                 Some(Object::Ref(r, _, _)) => Some(*r),
                 None => None,
-                _ => panic!("Info should be an indirect object"),
+                _ => {
+                    return Err(Error::xref(
+                        Some(trailer_offset),
+                        "Info should be an indirect object",
+                    ))
+                }
             },
             // Array of two byte-strings constituting a file identifier
-            // id: Option<Array<'a>>,
+            id: file_id(&trailer),
             table,
-        }
+            object_streams: RefCell::new(HashMap::new()),
+            resolving: RefCell::new(vec![]),
+            security_handler: None,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+        })
     }
 }
 
@@ -156,114 +624,154 @@ impl Default for XRefTable {
         XRefTable {
             size: Number::Integer(0),
             prev: None,
+            xref_stm: None,
             root: None,
             encrypt: None,
             info: None,
-            // id: None,
+            id: None,
             table: HashMap::new(),
+            object_streams: RefCell::new(HashMap::new()),
+            resolving: RefCell::new(vec![]),
+            security_handler: None,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+        }
+    }
+}
+
+#[cfg(test)]
+impl XRefTable {
+    // Table of the given entries, used to build test documents
+    pub fn from_entries(entries: impl IntoIterator<Item = (i32, Entry)>) -> Self {
+        XRefTable {
+            table: entries.into_iter().collect(),
+            ..Default::default()
         }
     }
 }
 
 impl XRefTable {
-    fn read_subsection_entry(tokenizer: &mut Tokenizer) -> Option<XrefEntry> {
+    // Keep the references of a trailer found while reconstructing the table, later trailers are newer
+    fn read_trailer(&mut self, trailer: &object::Dictionary) {
+        let reference = |key: &str| match trailer.get(key) {
+            Some(Object::Ref(r, _, _)) => Some(*r),
+            _ => None,
+        };
+        self.root = reference("Root").or(self.root);
+        self.info = reference("Info").or(self.info);
+        self.encrypt = reference("Encrypt").or(self.encrypt);
+        self.id = file_id(trailer).or(self.id.take());
+    }
+
+    fn read_subsection_entry(tokenizer: &mut Tokenizer) -> Result<XrefEntry, Error> {
         // either the next obj num if free or byte offset if in use
         let number = match tokenizer.next() {
             Some(Token::Numeric(Number::Integer(n))) => n as usize,
-            Some(t) => panic!("Xref entry offset token should be an integer, found {t:?}"),
-            None => panic!("Xref entry incomplete"),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    format!("Xref entry offset token should be an integer, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    "Xref entry incomplete",
+                ))
+            }
         };
 
         let generation = match tokenizer.next() {
             Some(Token::Numeric(Number::Integer(n))) => n as usize,
-            Some(t) => panic!("Xref entry generation token should be an integer, found {t:?}"),
-            None => panic!("Xref entry incomplete"),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    format!("Xref entry generation token should be an integer, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    "Xref entry incomplete",
+                ))
+            }
         };
 
         let in_use = match tokenizer.next() {
             Some(Token::String(s)) => s == b"n".to_vec(),
-            Some(t) => panic!("Xref entry in_use token should be a regular string, found {t:?}"),
-            None => panic!("Xref entry incomplete"),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    format!("Xref entry in_use token should be a regular string, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tokenizer.offset()),
+                    "Xref entry incomplete",
+                ))
+            }
         };
 
-        Some(XrefEntry {
+        Ok(XrefEntry {
             number,
             generation,
             in_use,
         })
     }
 
-    fn read_table_subsection(
-        tok: &mut Tokenizer,
-    ) -> HashMap<object::IndirectObject, (usize, bool)> {
-        let mut table = HashMap::new();
+    fn read_table_subsection(tok: &mut Tokenizer) -> Result<Entries, Error> {
+        let mut table = Entries::new();
 
         let start = match tok.next() {
             Some(Token::Numeric(Number::Integer(n))) => n,
-            Some(t) => panic!("Table subsection header start should be an integer, found {t:?}"),
-            None => panic!("Unable to read table subsection header"),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tok.offset()),
+                    format!("Table subsection header start should be an integer, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tok.offset()),
+                    "Unable to read table subsection header",
+                ))
+            }
         };
 
         let size = match tok.next() {
             Some(Token::Numeric(Number::Integer(n))) => n,
-            Some(t) => panic!("Table subsection header size should be an integer, found {t:?}"),
-            None => panic!("Unable to read table subsection header"),
+            Some(t) => {
+                return Err(Error::xref(
+                    Some(tok.offset()),
+                    format!("Table subsection header size should be an integer, found {t:?}"),
+                ))
+            }
+            None => {
+                return Err(Error::xref(
+                    Some(tok.offset()),
+                    "Unable to read table subsection header",
+                ))
+            }
         };
 
         for object_idx in start..start + size {
-            match XRefTable::read_subsection_entry(tok) {
-                Some(o) => {
-                    table.insert((object_idx, o.generation as i32), (o.number, o.in_use));
-                }
-                None => panic!("Unable to read xref entry"),
-            }
+            let o = XRefTable::read_subsection_entry(tok)?;
+            let entry = match o.in_use {
+                true => Entry::InUse {
+                    offset: o.number,
+                    generation: o.generation as i32,
+                },
+                false => Entry::Free,
+            };
+            table.insert(object_idx, entry);
         }
-        table
-    }
-
-    pub fn get_catalog_offset(&self) -> Option<usize> {
-        self.get(&self.root.unwrap())
+        Ok(table)
     }
 
     pub fn get(&self, key: &object::IndirectObject) -> Option<usize> {
-        match self.table.get(key) {
-            Some(v) => {
-                if v.1 {
-                    Some(v.0)
-                } else {
-                    panic!("XReftable object was freed")
-                }
-            }
-            None => match &self.prev {
-                // look for the key in previous xref section
-                Some(xref) => xref.get(key),
-                None => None,
-            },
-        }
-    }
-
-    pub fn get_and_fix(&self, key: &object::IndirectObject, bytes: &[u8]) -> Option<usize> {
-        match self.get(key) {
-            Some(offset) => {
-                let mut pattern = format!("{} {} obj", key.0, key.1).as_bytes().to_owned();
-                // xref address is correct
-                if bytes[offset..].starts_with(&pattern) {
-                    Some(offset)
-                // xref table adress is broken
-                } else {
-                    // add a new line at the beginning of the pattern to avoid matching 11 0 obj with 1 0 obj
-                    pattern.insert(0, b'\n');
-                    // look for object header in byte stream
-                    Some(
-                        bytes
-                            .windows(pattern.len())
-                            .position(|w: &[u8]| w == pattern)
-                            .unwrap()
-                            + 1,
-                    )
-                }
-            }
-            None => None,
+        match self.table.get(&key.0) {
+            Some(Entry::InUse { offset, generation }) if *generation == key.1 => Some(*offset),
+            _ => None,
         }
     }
 
@@ -283,38 +791,67 @@ pub struct XrefEntry {
     in_use: bool,
 }
 
-pub fn startxref(pdf_bytes: &[u8]) -> usize {
+// First byte string of the trailer ID array
+fn file_id(trailer: &object::Dictionary) -> Option<Vec<u8>> {
+    match trailer.get("ID") {
+        Some(Object::Array(id)) => match id.first() {
+            Some(Object::String(s) | Object::HexString(s)) => Some(s.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn startxref(pdf_bytes: &[u8]) -> Result<usize, Error> {
     // Idea: improve search with backward search in double ended lemmatizer
     let pattern = b"startxref";
     // Index of last occurence of startxref in file bytes
     let index = pdf_bytes
         .windows(pattern.len())
         .rposition(|w| w == pattern)
-        .unwrap();
+        .ok_or(Error::xref(None, "Startxref keyword not found"))?;
     let mut tok: Tokenizer<'_> = Tokenizer::new(pdf_bytes, index);
+    tok.next();
     match tok.next() {
-        Some(Token::String(s)) => {
-            if s.as_slice() != b"startxref" {
-                panic!("Startxref string missing in tokenizer, found token string {s:?}")
-            }
-        }
-        Some(t) => panic!("Startxref string missing in tokenizer, found token {t:?}"),
-        None => panic!("End of stream"),
-    };
-    match tok.next() {
-        Some(Token::Numeric(Number::Integer(i))) => i as usize,
-        Some(t) => panic!("Startxref integer missing in tokenizer, found token {t:?}"),
-        None => panic!("End of stream"),
+        Some(Token::Numeric(Number::Integer(i))) if i >= 0 => Ok(i as usize),
+        Some(t) => Err(Error::xref(
+            Some(index),
+            format!("Startxref integer missing in tokenizer, found token {t:?}"),
+        )),
+        None => Err(Error::xref(Some(index), "End of stream")),
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct XRefStream {
-    size: usize,              // trailer size entry (object number used in this XRef)
-    index: (usize, usize),    // subsection object number ranges
-    prev: Option<i32>,        // byte offset of previous xref
-    w: (usize, usize, usize), // xref stream entry sizes in bytes
-    stream: Vec<u8>,          // uncompressed xref entries
+    size: usize,                // trailer size entry (object number used in this XRef)
+    index: Vec<(usize, usize)>, // subsections first object number and number of entries
+    prev: Option<usize>,        // byte offset of previous xref
+    root: Option<object::IndirectObject>, // catalog dictionary
+    encrypt: Option<object::IndirectObject>, // encryption dictionary
+    info: Option<object::IndirectObject>, // information dictionary
+    w: (usize, usize, usize),   // xref stream entry sizes in bytes
+    id: Option<Vec<u8>>,        // first byte string of the file identifier
+    table: Entries,             // xref entries, merged with previous sections
+    object_streams: ObjectStreamCache, // decoded object streams
+    resolving: RefCell<Vec<object::IndirectObject>>, // objects being resolved
+    security_handler: Option<SecurityHandler>, // decryption of encrypted documents
+    max_decoded_size: usize,    // upper bound of the size of decoded streams
+}
+
+// Sections are compared by their content, decoded object streams and decryption state are ignored
+impl PartialEq for XRefStream {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.index == other.index
+            && self.prev == other.prev
+            && self.root == other.root
+            && self.encrypt == other.encrypt
+            && self.info == other.info
+            && self.w == other.w
+            && self.id == other.id
+            && self.table == other.table
+    }
 }
 
 impl XRefStream {
@@ -328,102 +865,117 @@ impl XRefStream {
         res
     }
 
-    pub fn get_catalog_offset(&self) -> Option<usize> {
-        Some(0)
+    // read the entries of the decoded stream, subsections follow each other
+    fn read_entries(stream: &[u8], index: &[(usize, usize)], w: (usize, usize, usize)) -> Entries {
+        let entry_size = w.0 + w.1 + w.2;
+        let mut rows = stream.chunks_exact(entry_size.max(1));
+        let mut table = Entries::new();
+        for (start, count) in index {
+            for (object_number, entry) in (*start..start + count).zip(rows.by_ref()) {
+                // cross reference entries in page 109, type field defaults to 1 when absent
+                let entry_type = match w.0 {
+                    0 => 1,
+                    _ => XRefStream::num(&entry[..w.0]),
+                };
+                let entry_mid = XRefStream::num(&entry[w.0..w.0 + w.1]);
+                let entry_last = XRefStream::num(&entry[w.0 + w.1..]);
+                let entry = match entry_type {
+                    1 => Entry::InUse {
+                        offset: entry_mid,
+                        generation: entry_last as i32,
+                    },
+                    2 => Entry::Compressed {
+                        stream: entry_mid as i32,
+                        index: entry_last,
+                    },
+                    _ => Entry::Free, // freed objects and unknown entry types are references to null
+                };
+                table.insert(object_number as i32, entry);
+            }
+        }
+        table
+    }
+
+    fn integer(value: &Object, key: &str) -> Result<usize, Error> {
+        match value {
+            Object::Numeric(Number::Integer(n)) if *n >= 0 => Ok(*n as usize),
+            o => Err(Error::xref(
+                None,
+                format!("Cross reference stream dictionnary {key} values should be positive integers, found {o:?}"),
+            )),
+        }
     }
 
-    pub fn get(&self, key: &object::IndirectObject) -> Option<usize> {
-        let object_idx = key.0 as usize;
-        // check that object number is in index range
-        if object_idx > self.index.1 {
-            panic!("Object number {:?} is out of index", key.0)
-        }
-        let entry_size = self.w.0 + self.w.1 + self.w.2;
-        let entry = &self.stream[object_idx * entry_size..object_idx * entry_size + entry_size];
-        println!("{entry:?}");
-        // cross reference entries in page 109
-        let entry_type = XRefStream::num(&entry[..self.w.0]);
-        let entry_mid = XRefStream::num(&entry[self.w.0..self.w.0 + self.w.1]);
-        match entry_type {
-            1 => Some(entry_mid),
-            0 => None,                             // not implemented yet - freed objects
-            2 => self.get(&(entry_mid as i32, 0)), // not implemented yet - compressed object
-            _ => panic!("Cross reference stream data type can only be 0, 1 or 2"),
+    fn reference(
+        value: Option<&Object>,
+        key: &str,
+    ) -> Result<Option<object::IndirectObject>, Error> {
+        match value {
+            Some(Object::Ref(r, _, _)) => Ok(Some(*r)),
+            None => Ok(None),
+            Some(o) => Err(Error::xref(
+                None,
+                format!("Cross reference stream dictionnary {key} should be an indirect object, found {o:?}"),
+            )),
         }
     }
 }
 
-impl From<object::Stream<'_>> for XRefStream {
-    fn from(value: object::Stream<'_>) -> Self {
-        let size = match value.header.get("Size") {
-            Some(Object::Numeric(Number::Integer(n))) => *n as usize,
-            Some(o) => panic!(
-                "Cross reference stream dictionnary contains a Size with wrong type, found {o:?}"
-            ),
-            None => {
-                panic!("Cross reference stream dictionnary does not contains the required Size key")
-            }
-        };
+impl TryFrom<object::Stream<'_>> for XRefStream {
+    type Error = Error;
 
-        match value.header.get("DecodeParms") {
-            Some(Object::Dictionary(_)) => {
-                panic!("Data encoded with custom filters which is currently not supported")
-            }
-            Some(decode_parms_object) => {
-                panic!("DecodeParams should be a dictionnary, found {decode_parms_object:?}")
+    fn try_from(value: object::Stream<'_>) -> Result<Self, Self::Error> {
+        let size =
+            match value.header.get("Size") {
+                Some(o) => XRefStream::integer(o, "Size")?,
+                None => return Err(Error::xref(
+                    None,
+                    "Cross reference stream dictionnary does not contains the required Size key",
+                )),
+            };
+
+        let index = match value.header.get("Index") {
+            Some(Object::Array(a)) => {
+                if a.len() % 2 != 0 {
+                    return Err(Error::xref(None, "Cross reference stream key 'Index' is not an array of integer pairs"));
+                }
+                a.chunks(2)
+                    .map(|pair| Ok((XRefStream::integer(&pair[0], "Index")?, XRefStream::integer(&pair[1], "Index")?)))
+                    .collect::<Result<Vec<(usize, usize)>, Error>>()?
             }
-            None => (),
+            Some(o) => return Err(Error::xref(None, format!("Cross reference stream dictionnary contains a Index value with wrong type, found {o:?}"))),
+            None => vec![(0, size)] // default value (cf page 108)
+        };
+        let w = match value.header.get("W") {
+            Some(Object::Array(a)) if a.len() == 3 => (
+                XRefStream::integer(&a[0], "W")?,
+                XRefStream::integer(&a[1], "W")?,
+                XRefStream::integer(&a[2], "W")?,
+            ),
+            Some(o) => return Err(Error::xref(None, format!("Cross reference stream dictionnary key W should contain an array of 3 integers, found {o:?}"))),
+            None => return Err(Error::xref(None, "Cross reference stream dictionnary key W is required"))
         };
+        let stream = value.decode()?;
 
-        XRefStream {
+        Ok(XRefStream {
             size,
-            index: match value.header.get("Index") {
-                Some(Object::Array(a)) => {
-                    if a.len() != 2 {
-                        panic!("Cross reference stream key 'Index' is not an array of length 2");
-                    }
-                    (
-                        match a[0] {
-                            Object::Numeric(Number::Integer(n)) => n as usize,
-                            _ => panic!()
-                        },
-                        match a[1] {
-                            Object::Numeric(Number::Integer(n)) => n as usize,
-                            _ => panic!()
-                        }
-                    )
-                }
-                Some(o) => panic!("Cross reference stream dictionnary contains a Index value with wrong type, found {o:?}"),
-                None => (0, size) // default value (cf page 108)
-            },
+            table: XRefStream::read_entries(&stream, &index, w),
+            index,
+            w,
             prev: match value.header.get("Prev") {
-                Some(Object::Numeric(Number::Integer(n))) => Some(*n),
-                Some(o) => panic!("Cross reference stream dictionnary contains a Prev value with wrong type, found {o:?}"),
+                Some(Object::Numeric(Number::Integer(n))) if *n >= 0 => Some(*n as usize),
+                Some(o) => return Err(Error::xref(None, format!("Cross reference stream dictionnary contains a Prev value with wrong type, found {o:?}"))),
                 None => None
             },
-            w: match value.header.get("W") {
-                Some(Object::Array(a)) => {
-                    (
-                        match &a[0] {
-                            Object::Numeric(Number::Integer(n)) => *n as usize,
-                            o => panic!("Cross reference stream dictionnary Index subsection indexes should be numbers, found {o:?}")
-                        },
-                        match &a[1] {
-                            Object::Numeric(Number::Integer(n)) => *n as usize,
-                            o => panic!("Cross reference stream dictionnary Index subsection indexes should be numbers, found {o:?}")
-                        },
-                        match &a[2] {
-                            Object::Numeric(Number::Integer(n)) => *n as usize,
-                            o => panic!("Cross reference stream dictionnary Index subsection indexes should be numbers, found {o:?}")
-                        },
-                    )
-                },
-                Some(o) => panic!("Cross reference stream dictionnary key W should contain an array, found {o:?}"),
-                None => panic!("Cross reference stream dictionnary key W is required")
-            },
-            // header: &value.header,
-            stream: flate_decode(&value.bytes)
-        }
+            root: XRefStream::reference(value.header.get("Root"), "Root")?,
+            encrypt: XRefStream::reference(value.header.get("Encrypt"), "Encrypt")?,
+            info: XRefStream::reference(value.header.get("Info"), "Info")?,
+            id: file_id(&value.header),
+            object_streams: RefCell::new(HashMap::new()),
+            resolving: RefCell::new(vec![]),
+            security_handler: None,
+            max_decoded_size: value.max_decoded_size,
+        })
     }
 }
 
@@ -461,7 +1013,7 @@ mod tests {
     #[test]
     fn xref_table_valid() {
         let xref_sample = b"xref\n0 6\n0000000000 65535 f \n0000000010 00000 n \n0000000079 00000 n \n0000000173 00000 n \n0000000301 00000 n \n0000000380 00000 n \ntrailer\n<<\n  /Size 6\n  /Root 1 0 R\n>>";
-        let table = match XRef::new(xref_sample, 0) {
+        let table = match XRef::new(xref_sample, 0, DEFAULT_MAX_DECODED_SIZE).unwrap() {
             XRef::XRefTable(t) => t,
             XRef::XRefStream(_) => panic!(),
         };
@@ -474,9 +1026,120 @@ mod tests {
     #[test]
     fn xref_stream_valid() {
         let xref_sample = b"22 0 obj\n<<\n /Type /XRef\n/Index [0 23]\n/Size 23\n/W [1 2 1]\n/Root 20 0 R\n/Info 21 0 R\n/ID [<8EBF2018CB18810B2C88BDD4E7324774> <8EBF2018CB18810B2C88BDD4E7324774>]\n/Length 0        \n/Filter /FlateDecode\n>>\nstream\n\nendstream\nendobj";
-        match XRef::new(xref_sample, 0) {
+        match XRef::new(xref_sample, 0, DEFAULT_MAX_DECODED_SIZE).unwrap() {
             XRef::XRefStream(t) => t,
             XRef::XRefTable(_) => panic!(),
         };
     }
+
+    #[test]
+    fn xref_stream_png_predictor() {
+        let xref_sample = b"3 0 obj\n<< /Type /XRef /Size 3 /W [1 2 1] /Length 47 /Filter [/ASCIIHexDecode /FlateDecode] /DecodeParms [null << /Predictor 12 /Columns 4 >>] >>\nstream\n789c63626060f8cfc4c8c0cfc8c4c0c8c800000bbd0119>\nendstream\nendobj";
+        let xref = XRef::new(xref_sample, 0, DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert_eq!(xref.get(&(0, 0)), None);
+        assert_eq!(xref.get(&(1, 0)), Some(ObjectLocation::Offset(15)));
+        assert_eq!(xref.get(&(2, 0)), Some(ObjectLocation::Offset(272)));
+        assert_eq!(xref.max_decoded_size(), DEFAULT_MAX_DECODED_SIZE);
+        // the 23 bytes of hexadecimal data exceed the limit of the document
+        assert!(matches!(
+            XRef::new(xref_sample, 0, 22),
+            Err(Error::Filter { .. })
+        ));
+        assert_eq!(
+            XRef::new(xref_sample, 0, 23).unwrap().max_decoded_size(),
+            23
+        );
+    }
+
+    #[test]
+    fn xref_stream_subsections() {
+        // objects 0, 5 and 6: free entry, entry in use and compressed object
+        let xref_sample = b"7 0 obj\n<< /Type /XRef /Size 7 /Index [0 1 5 2] /W [1 2 1] /Root 1 0 R /Length 28 /Filter /ASCIIHexDecode >>\nstream\n00000000 01001000 02000603>\nendstream\nendobj";
+        let xref = XRef::new(xref_sample, 0, DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert_eq!(xref.root(), Some((1, 0)));
+        assert_eq!(xref.get(&(0, 0)), None);
+        assert_eq!(xref.get(&(1, 0)), None);
+        assert_eq!(xref.get(&(5, 0)), Some(ObjectLocation::Offset(16)));
+        assert_eq!(
+            xref.get(&(6, 0)),
+            Some(ObjectLocation::Compressed {
+                stream: 6,
+                index: 3
+            })
+        );
+    }
+
+    #[test]
+    fn xref_incremental_update() {
+        let mut file = b"%PDF-1.4\n".to_vec();
+        let obj1 = file.len();
+        file.extend(b"1 0 obj\n(a)\nendobj\n");
+        let obj2 = file.len();
+        file.extend(b"2 0 obj\n(b)\nendobj\n");
+        let xref1 = file.len();
+        file.extend(format!("xref\n0 3\n0000000000 65535 f \n{obj1:010} 00000 n \n{obj2:010} 00000 n \ntrailer\n<< /Size 3 /Root 1 0 R >>\n").as_bytes());
+        // object 2 is updated with a new generation number, object 3 is added as free
+        let obj2_update = file.len();
+        file.extend(b"2 1 obj\n(c)\nendobj\n");
+        let xref2 = file.len();
+        file.extend(format!("xref\n0 1\n0000000000 65535 f \n2 2\n{obj2_update:010} 00001 n \n0000000000 00001 f \ntrailer\n<< /Size 4 /Root 1 0 R /Prev {xref1} >>\n").as_bytes());
+        let xref = XRef::new(&file, xref2, DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert_eq!(xref.get(&(1, 0)), Some(ObjectLocation::Offset(obj1)));
+        assert_eq!(xref.get(&(2, 1)), Some(ObjectLocation::Offset(obj2_update)));
+        assert_eq!(xref.get(&(2, 0)), None);
+        assert_eq!(xref.get(&(3, 0)), None);
+    }
+
+    #[test]
+    fn xref_prev_loop() {
+        let xref_sample =
+            b"xref\n0 1\n0000000000 65535 f \ntrailer\n<< /Size 1 /Root 1 0 R /Prev 0 >>";
+        assert!(XRef::new(xref_sample, 0, DEFAULT_MAX_DECODED_SIZE).is_ok());
+    }
+
+    #[test]
+    fn xref_hybrid_reference() {
+        let mut file = b"xref\n0 2\n0000000000 65535 f \n0000000000 65535 f \ntrailer\n<< /Size 3 /Root 2 0 R /XRefStm ".to_vec();
+        let xref_stm = file.len() + 14;
+        file.extend(format!("{xref_stm:010} >>\n").as_bytes());
+        assert_eq!(file.len(), xref_stm);
+        // object 1 is compressed in object stream 5, object 2 is at offset 9
+        file.extend(b"3 0 obj\n<< /Type /XRef /Size 3 /Index [1 2] /W [1 1 1] /Length 13 /Filter /ASCIIHexDecode >>\nstream\n020500 010900>\nendstream\nendobj");
+        let xref = XRef::new(&file, 0, DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert_eq!(xref.root(), Some((2, 0)));
+        assert_eq!(
+            xref.get(&(1, 0)),
+            Some(ObjectLocation::Compressed {
+                stream: 5,
+                index: 0
+            })
+        );
+        assert_eq!(xref.get(&(2, 0)), Some(ObjectLocation::Offset(9)));
+    }
+
+    #[test]
+    fn xref_reconstruct() {
+        let file = b"%PDF-1.5\n1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n3 0 obj\n<< /Type /ObjStm /N 1 /First 4 /Length 21 >>\nstream\n2 0 << /Type /Pages >>\nendstream\nendobj\n%%EOF";
+        let xref = XRef::reconstruct(file, DEFAULT_MAX_DECODED_SIZE).unwrap();
+        // catalog is inferred without trailer
+        assert_eq!(xref.root(), Some((1, 0)));
+        assert_eq!(xref.get(&(1, 0)), Some(ObjectLocation::Offset(9)));
+        assert_eq!(
+            xref.get(&(2, 0)),
+            Some(ObjectLocation::Compressed {
+                stream: 3,
+                index: 0
+            })
+        );
+        assert!(XRef::reconstruct(b"no objects here", DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn xref_object_headers() {
+        let file = b"1 0 obj\n(a)\nendobj 12 3 obj x12 0 obj 4 0 objstm\n5\r\n0\tobj";
+        assert_eq!(
+            XRef::object_headers(file),
+            vec![(0, (1, 0)), (19, (12, 3)), (49, (5, 0))]
+        );
+    }
 }
@@ -1,7 +1,7 @@
 #[test]
 fn test_helloworld() {
     let file = std::fs::read("data/helloworld.pdf").unwrap();
-    let pdf = pdfrust::Pdf::from(file);
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
     assert_eq!(pdf.extract(pdfrust::Extract::Text), "Hello, world!");
     assert_eq!(
         pdf.extract(pdfrust::Extract::RawContent),
@@ -12,7 +12,7 @@ fn test_helloworld() {
 #[test]
 fn test_sample() {
     let file = std::fs::read("data/sample.pdf").unwrap();
-    let pdf = pdfrust::Pdf::from(file);
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
     pdf.extract(pdfrust::Extract::Text);
     pdf.extract(pdfrust::Extract::RawContent);
 }
@@ -20,7 +20,7 @@ fn test_sample() {
 #[test]
 fn test_tracemonkey() {
     let file = std::fs::read("data/tracemonkey.pdf").unwrap();
-    let pdf = pdfrust::Pdf::from(file);
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
     pdf.extract(pdfrust::Extract::Text);
     pdf.extract(pdfrust::Extract::RawContent);
 }
@@ -28,7 +28,7 @@ fn test_tracemonkey() {
 #[test]
 fn test_libreoffice() {
     let file = std::fs::read("data/002-trivial-libre-office-writer.pdf").unwrap();
-    let pdf = pdfrust::Pdf::from(file);
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
     pdf.extract(pdfrust::Extract::Text);
     pdf.extract(pdfrust::Extract::RawContent);
 }
@@ -36,15 +36,232 @@ fn test_libreoffice() {
 #[test]
 fn test_index() {
     let file = std::fs::read("data/index.pdf").unwrap();
-    let pdf = pdfrust::Pdf::from(file);
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
     pdf.extract(pdfrust::Extract::Text);
     pdf.extract(pdfrust::Extract::RawContent);
+    // the columns of the invoice table are aligned, bold text painted twice is kept once
+    let layout = pdf.extract(pdfrust::Extract::Layout);
+    assert!(layout.starts_with("Invoice\n"));
+    let lines: Vec<&str> = layout.lines().collect();
+    let header = lines.iter().find(|l| l.starts_with("Description")).unwrap();
+    let row = lines
+        .iter()
+        .find(|l| l.starts_with("Prince Upgrades"))
+        .unwrap();
+    assert_eq!(header.find("From"), row.find("Nov 26, 2016"));
+    assert_eq!(header.find("Until"), row.find("Nov 26, 2017"));
 }
 
-// #[test]
-// fn test_latex() {
-//     let file = std::fs::read("data/pdflatex-4-pages.pdf").unwrap();
-//     let pdf = pdfrust::Pdf::from(file);
-//     pdf.extract(pdfrust::Extract::Text);
-//     pdf.extract(pdfrust::Extract::RawContent);
-// }
+#[test]
+fn test_latex() {
+    let file = std::fs::read("data/pdflatex-4-pages.pdf").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    // words are separated by TJ offsets
+    assert!(pdf
+        .extract(pdfrust::Extract::Text)
+        .starts_with("Hello, here is some text without a meaning. This text should show"));
+    pdf.extract(pdfrust::Extract::RawContent);
+}
+
+#[test]
+fn test_invalid_pdf() {
+    assert!(pdfrust::Pdf::try_from(b"not a pdf".to_vec()).is_err());
+    // objects without catalog cannot be reconstructed
+    assert!(pdfrust::Pdf::try_from(b"%PDF-1.4\n1 0 obj\n(a)\nendobj\n%%EOF".to_vec()).is_err());
+    let pdf: Result<pdfrust::Pdf, pdfrust::Error> = b"not a pdf".to_vec().try_into();
+    assert!(pdf.is_err());
+    assert!(pdfrust::pdf_version(b"%PDF-2.5").is_err());
+    assert!(pdfrust::pdf_version(b"1").is_err());
+    assert_eq!(
+        pdfrust::pdf_version(b"%PDF-1.7").unwrap().to_string(),
+        "1.7"
+    );
+}
+
+#[test]
+fn test_reconstruct_xref() {
+    // file truncated before the end of the cross-reference table
+    let mut file = std::fs::read("data/helloworld.pdf").unwrap();
+    file.truncate(file.len() - 200);
+    file.extend_from_slice(b"\n%%EOF");
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    assert_eq!(
+        pdf.try_extract(pdfrust::Extract::Text).unwrap(),
+        "Hello, world!"
+    );
+    // startxref pointing to a wrong offset and shifted objects
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let mut shifted = b"%PDF-1.4\n%garbage\n".to_vec();
+    shifted.extend(file.iter().skip(9));
+    let pdf = pdfrust::Pdf::try_from(shifted).unwrap();
+    assert_eq!(
+        pdf.try_extract(pdfrust::Extract::Text).unwrap(),
+        "Hello, world!"
+    );
+    // missing startxref and %%EOF
+    let file = std::fs::read("data/tracemonkey.pdf").unwrap();
+    let position = file.windows(9).rposition(|w| w == b"startxref").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file[..position].to_vec()).unwrap();
+    assert!(pdf.try_extract(pdfrust::Extract::Text).is_ok());
+}
+
+#[test]
+fn test_missing_font() {
+    // content stream selects a font missing from page resources
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let file = String::from_utf8(file)
+        .unwrap()
+        .replace("/F1 12 Tf", "/F9 12 Tf");
+    let pdf = pdfrust::Pdf::try_from(file.into_bytes()).unwrap();
+    assert!(matches!(
+        pdf.try_extract(pdfrust::Extract::Text),
+        Err(pdfrust::Error::Font { .. })
+    ));
+    assert!(pdf.try_extract(pdfrust::Extract::RawContent).is_ok());
+}
+
+#[test]
+fn test_encrypted() {
+    // empty user password, revisions 3 and 4
+    for path in ["data/helloworld-rc4.pdf", "data/helloworld-aesv2.pdf"] {
+        let file = std::fs::read(path).unwrap();
+        let pdf = pdfrust::Pdf::try_from(file.clone()).unwrap();
+        assert_eq!(pdf.extract(pdfrust::Extract::Text), "Hello, world!");
+        let pdf = pdfrust::Pdf::try_from_with_password(file.clone(), b"owner").unwrap();
+        assert_eq!(pdf.extract(pdfrust::Extract::Text), "Hello, world!");
+        assert_eq!(pdf.extract(pdfrust::Extract::Info), "Title: Hello, world!");
+        assert!(matches!(
+            pdfrust::Pdf::try_from_with_password(file, b"wrong"),
+            Err(pdfrust::Error::Encryption { .. })
+        ));
+    }
+
+    // revision 6
+    let file = std::fs::read("data/helloworld-aesv3.pdf").unwrap();
+    for password in [b"user".as_slice(), b"owner"] {
+        let pdf = pdfrust::Pdf::try_from_with_password(file.clone(), password).unwrap();
+        assert_eq!(
+            pdf.extract(pdfrust::Extract::RawContent),
+            "BT\n70 50 TD\n/F1 12 Tf\n(Hello, world!) Tj\nET"
+        );
+    }
+    assert!(matches!(
+        pdfrust::Pdf::try_from(file.clone()),
+        Err(pdfrust::Error::Encryption { .. })
+    ));
+    assert!(matches!(
+        pdfrust::Pdf::try_from_with_password(file, b"wrong"),
+        Err(pdfrust::Error::Encryption { .. })
+    ));
+}
+
+#[test]
+fn test_info() {
+    let file = std::fs::read("data/pdflatex-4-pages.pdf").unwrap();
+    let info = pdfrust::Pdf::try_from(file)
+        .unwrap()
+        .info()
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.creator.as_deref(), Some("TeX"));
+    assert_eq!(info.trapped.as_deref(), Some("False"));
+    let creation_date = info.creation_date.unwrap();
+    assert_eq!(creation_date.to_rfc3339(), "2022-04-03T19:59:45+02:00");
+    assert_eq!(creation_date.timestamp(), 1649008785);
+    assert!(info.custom["PTEX.Fullbanner"].starts_with("This is pdfTeX"));
+
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    assert_eq!(pdf.info().unwrap(), None);
+    assert_eq!(pdf.extract(pdfrust::Extract::Info), "");
+}
+
+#[test]
+fn test_pages() {
+    let file = std::fs::read("data/tracemonkey.pdf").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    assert_eq!(pdf.page_count().unwrap(), 14);
+    let pages = pdf
+        .pages()
+        .unwrap()
+        .map(|page| page.and_then(|p| p.extract(pdfrust::Extract::Text)))
+        .collect::<Result<Vec<String>, pdfrust::Error>>()
+        .unwrap();
+    assert_eq!(pages.len(), 14);
+    assert_eq!(pages.join("\n"), pdf.extract(pdfrust::Extract::Text));
+    assert_eq!(
+        pages.join("\x0c"),
+        pdf.try_extract_with_separator(pdfrust::Extract::Text, "\x0c")
+            .unwrap()
+    );
+    assert_eq!(
+        pdf.page(9)
+            .unwrap()
+            .extract(pdfrust::Extract::Text)
+            .unwrap(),
+        pages[9]
+    );
+    assert!(pdf.page(14).is_err());
+
+    // MediaBox is inherited from the root of the page tree
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let page = pdfrust::Pdf::try_from(file).unwrap().page(0).unwrap();
+    let bbox = page.effective_bbox();
+    assert_eq!((bbox.width(), bbox.height()), (200.0, 200.0));
+}
+
+#[test]
+fn test_standard_font_widths() {
+    // helloworld.pdf uses Times-Roman without Widths, positions come from the font metrics
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    let chars = pdf.extract(pdfrust::Extract::Chars);
+    let mut lines = chars.lines();
+    assert_eq!(
+        lines.next(),
+        Some("'H', \"Type1\", \"Times-Roman\", (1, 0, 0, 1, 70, 50)")
+    );
+    // H is 722 thousandths wide in Times-Roman, at 12 points
+    assert_eq!(
+        lines.next(),
+        Some("'e', \"Type1\", \"Times-Roman\", (1, 0, 0, 1, 78.664, 50)")
+    );
+}
+
+#[test]
+fn test_content_stream() {
+    let file = std::fs::read("data/helloworld.pdf").unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    let stream = pdf.page(0).unwrap().content_stream().unwrap();
+    let operators: Vec<&str> = stream
+        .operations
+        .iter()
+        .map(|o| o.operator.as_str())
+        .collect();
+    assert_eq!(operators, vec!["BT", "TD", "Tf", "Tj", "ET"]);
+    assert_eq!(stream.operations[1].offset, 3);
+    assert_eq!(
+        String::from_utf8(stream.to_bytes()).unwrap(),
+        "BT\n70 50 TD\n/F1 12 Tf\n(Hello, world!) Tj\nET\n"
+    );
+}
+
+#[test]
+fn test_max_decoded_size() {
+    let file = std::fs::read("data/002-trivial-libre-office-writer.pdf").unwrap();
+    // documents opened in the same process keep their own limit
+    let limited = pdfrust::Pdf::try_from_with_options(
+        file.clone(),
+        &pdfrust::Options {
+            max_decoded_size: 10,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let pdf = pdfrust::Pdf::try_from(file).unwrap();
+    assert!(matches!(
+        limited.try_extract(pdfrust::Extract::RawContent),
+        Err(pdfrust::Error::Filter { .. })
+    ));
+    assert!(pdf.try_extract(pdfrust::Extract::RawContent).is_ok());
+}